@@ -0,0 +1,47 @@
+mod rules;
+mod webhook;
+
+pub use rules::{evaluate_rules, Alert, AlertDeduplicator, AlertRules};
+pub use webhook::{deliver_alert, Webhook};
+
+use crate::{
+    balances::{retrieve_account_balance, retrieve_account_open_positions},
+    compare_funding_rates::compare_funding_rates,
+};
+use reqwest::Client;
+use std::time::Duration;
+use tokio::try_join;
+
+/// evaluates `rules` every `interval` and posts new alerts to every webhook, runs forever
+pub async fn run_alerts(rules: AlertRules, webhooks: Vec<Webhook>, interval: Duration) {
+    let client = Client::new();
+    // one per webhook so a failed delivery is not repeated on the others
+    let mut dedups: Vec<_> = webhooks.iter().map(|_| AlertDeduplicator::new()).collect();
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        interval.tick().await;
+
+        let (funding_rates, positions, balance) = match try_join!(
+            compare_funding_rates(),
+            retrieve_account_open_positions(),
+            retrieve_account_balance()
+        ) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("Could not evaluate alerts. {e}");
+                continue;
+            }
+        };
+
+        let alerts = evaluate_rules(&rules, &funding_rates, &positions, &balance);
+        for (webhook, dedup) in webhooks.iter().zip(&mut dedups) {
+            for alert in dedup.new_alerts(alerts.clone()) {
+                if let Err(e) = deliver_alert(&client, webhook, &alert).await {
+                    log::error!("{e}");
+                    dedup.forget(&alert);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{
+    balances::{find_liquidation_risks, summarize_hedges, Balance, Position},
+    break_even::calculate_directional_spread,
+    compare_funding_rates::JointFundingRate,
+    symbols::symbols,
+    util::Platform,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// thresholds for each rule, `None` disables it
+#[derive(Debug, Clone, Default)]
+pub struct AlertRules {
+    pub min_funding_spread: Option<f64>, // hourly decimal form
+    pub max_leverage: Option<f64>,
+    pub min_liquidation_distance: Option<f64>, // decimal form
+    pub spread_flip: bool,                     // open hedge now pays funding
+    pub unhedged_legs: bool,                   // position open on a single platform
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Alert {
+    FundingSpread {
+        token: String,
+        funding_rate_difference: f64, // hourly decimal form
+    },
+    SpreadFlipped {
+        token: String,
+        long: Platform,
+        spread: f64, // hourly decimal form
+    },
+    Leverage {
+        platform: Platform,
+        leverage: f64,
+    },
+    Unhedged {
+        token: String,
+    },
+    LiquidationRisk {
+        token: String,
+        platform: Platform,
+        distance: f64, // decimal form
+    },
+}
+
+impl Alert {
+    /// identifies the condition, not the values, so moving numbers don't repeat the alert
+    pub fn key(&self) -> String {
+        match self {
+            Alert::FundingSpread { token, .. } => format!("funding_spread:{token}"),
+            Alert::SpreadFlipped { token, .. } => format!("spread_flipped:{token}"),
+            Alert::Leverage { platform, .. } => format!("leverage:{platform:?}"),
+            Alert::Unhedged { token } => format!("unhedged:{token}"),
+            Alert::LiquidationRisk {
+                token, platform, ..
+            } => format!("liquidation_risk:{token}:{platform:?}"),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Alert::FundingSpread {
+                token,
+                funding_rate_difference,
+            } => format!(
+                "{token} funding rate difference is {:.2}% apr",
+                funding_rate_difference * 24.0 * 365.0 * 100.0
+            ),
+            Alert::SpreadFlipped {
+                token,
+                long,
+                spread,
+            } => format!(
+                "{token} hedge (long {long:?}) now pays {:.2}% apr in funding",
+                -spread * 24.0 * 365.0 * 100.0
+            ),
+            Alert::Leverage { platform, leverage } => {
+                format!("{platform:?} leverage is {leverage:.2}x")
+            }
+            Alert::Unhedged { token } => format!("{token} has an unhedged leg"),
+            Alert::LiquidationRisk {
+                token,
+                platform,
+                distance,
+            } => format!(
+                "{token} {platform:?} leg is {:.2}% from liquidation",
+                distance * 100.0
+            ),
+        }
+    }
+}
+
+pub fn evaluate_rules(
+    rules: &AlertRules,
+    funding_rates: &[JointFundingRate],
+    positions: &[Position],
+    balance: &Balance,
+) -> Vec<Alert> {
+    let mut alerts = vec![];
+
+    if let Some(min_funding_spread) = rules.min_funding_spread {
+        alerts.extend(
+            funding_rates
+                .iter()
+                .filter(|jfr| jfr.funding_rate_difference > min_funding_spread)
+                .map(|jfr| Alert::FundingSpread {
+                    token: jfr.name.clone(),
+                    funding_rate_difference: jfr.funding_rate_difference,
+                }),
+        );
+    }
+
+    for summary in summarize_hedges(positions) {
+        let Some(hedge) = summary.hedge else {
+            if rules.unhedged_legs {
+                alerts.push(Alert::Unhedged {
+                    token: summary.token,
+                });
+            }
+            continue;
+        };
+
+        // hourly rate of the contract each leg is open on, the joint rates may list the other binance quote
+        let leg_rate = |platform: Platform| {
+            positions
+                .iter()
+                .find(|p| {
+                    p.platform == platform
+                        && (platform == Platform::Hyperliquid || p.quote == hedge.binance_quote)
+                        && symbols().canonical(platform, &p.coin) == summary.token
+                })
+                .map(|p| p.funding_rate / 24.0 / 365.0)
+        };
+        let (Some(b_rate), Some(hl_rate)) =
+            (leg_rate(Platform::Binance), leg_rate(Platform::Hyperliquid))
+        else {
+            continue;
+        };
+        let spread = match hedge.long {
+            Platform::Binance => calculate_directional_spread(b_rate, hl_rate),
+            Platform::Hyperliquid => calculate_directional_spread(hl_rate, b_rate),
+        };
+
+        if rules.spread_flip && spread < 0.0 {
+            alerts.push(Alert::SpreadFlipped {
+                token: summary.token,
+                long: hedge.long,
+                spread,
+            });
+        }
+    }
+
+    if let Some(min_liquidation_distance) = rules.min_liquidation_distance {
+        alerts.extend(
+            find_liquidation_risks(positions, min_liquidation_distance)
+                .into_iter()
+                .map(|risk| Alert::LiquidationRisk {
+                    token: risk.token,
+                    platform: risk.platform,
+                    distance: risk.distance,
+                }),
+        );
+    }
+
+    if let Some(max_leverage) = rules.max_leverage {
+        let exposures = [
+            (
+                Platform::Binance,
+                balance.binance_open_positions,
+                balance.binance_balance,
+            ),
+            (
+                Platform::Hyperliquid,
+                balance.hyperliquid_open_positions,
+                balance.hyperliquid_balance,
+            ),
+        ];
+
+        alerts.extend(
+            exposures
+                .into_iter()
+                // an empty or negative account has no meaningful leverage
+                .filter(|(_, _, account_balance)| *account_balance > 0.0)
+                .map(|(platform, open_positions, account_balance)| {
+                    (platform, open_positions / account_balance)
+                })
+                .filter(|(_, leverage)| *leverage > max_leverage)
+                .map(|(platform, leverage)| Alert::Leverage { platform, leverage }),
+        );
+    }
+
+    alerts
+}
+
+/// delivers each alert once while its condition holds, it can fire again after clearing
+#[derive(Debug, Default)]
+pub struct AlertDeduplicator {
+    active: HashSet<String>,
+}
+
+impl AlertDeduplicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// alerts that were not active on the previous evaluation
+    pub fn new_alerts(&mut self, alerts: Vec<Alert>) -> Vec<Alert> {
+        let previous = std::mem::take(&mut self.active);
+        self.active = alerts.iter().map(Alert::key).collect();
+
+        alerts
+            .into_iter()
+            .filter(|alert| !previous.contains(&alert.key()))
+            .collect()
+    }
+
+    /// lets an alert that could not be delivered fire again on the next evaluation
+    pub fn forget(&mut self, alert: &Alert) {
+        self.active.remove(&alert.key());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_fixtures::{joint_funding_rate as jfr, position},
+        util::QuoteAsset,
+    };
+
+    fn position_with_liquidation(
+        platform: Platform,
+        coin: &str,
+        direction: &str,
+        liquidation_price: Option<f64>,
+    ) -> Position {
+        Position {
+            liquidation_price,
+            ..position(platform, coin, direction)
+        }
+    }
+
+    fn balance(binance_open_positions: f64) -> Balance {
+        Balance {
+            binance_balance: 1000.0,
+            binance_open_positions,
+            binance_maintenance_margin: 10.0,
+            hyperliquid_balance: 1000.0,
+            hyperliquid_open_positions: 1000.0,
+            hyperliquid_maintenance_margin: 10.0,
+        }
+    }
+
+    fn rules() -> AlertRules {
+        AlertRules {
+            min_funding_spread: Some(0.0001),
+            max_leverage: Some(3.0),
+            min_liquidation_distance: Some(0.15),
+            spread_flip: true,
+            unhedged_legs: true,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_rules() {
+        let funding_rates = vec![jfr("WIF", 0.0003, 0.0), jfr("ETH", 0.00001, 0.00003)];
+        let positions = vec![
+            // short binance while binance pays less than hyperliquid
+            Position {
+                funding_rate: 0.5,
+                ..position(Platform::Binance, "ETH", "short")
+            },
+            Position {
+                funding_rate: 1.5,
+                ..position(Platform::Hyperliquid, "ETH", "long")
+            },
+            position(Platform::Binance, "TIA", "long"),
+        ];
+
+        let alerts = evaluate_rules(&rules(), &funding_rates, &positions, &balance(4000.0));
+
+        assert_eq!(
+            alerts,
+            vec![
+                Alert::FundingSpread {
+                    token: "WIF".to_string(),
+                    funding_rate_difference: 0.0003,
+                },
+                Alert::SpreadFlipped {
+                    token: "ETH".to_string(),
+                    long: Platform::Hyperliquid,
+                    spread: 0.5 / 24.0 / 365.0 - 1.5 / 24.0 / 365.0,
+                },
+                Alert::Unhedged {
+                    token: "TIA".to_string(),
+                },
+                Alert::Leverage {
+                    platform: Platform::Binance,
+                    leverage: 4.0,
+                },
+            ]
+        );
+
+        // nothing enabled
+        let alerts = evaluate_rules(
+            &AlertRules::default(),
+            &funding_rates,
+            &positions,
+            &balance(4000.0),
+        );
+        assert!(alerts.is_empty());
+
+        // an empty account is skipped rather than divided by
+        let empty = Balance {
+            hyperliquid_balance: 0.0,
+            ..balance(1000.0)
+        };
+        let alerts = evaluate_rules(&rules(), &[], &[], &empty);
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn test_spread_flip_follows_the_hedged_quote() {
+        // the usdt perpetual flipped but the hedge is open on the usdc one
+        let funding_rates = vec![jfr("ETH", 0.00001, 0.00003)];
+        let positions = vec![
+            Position {
+                quote: QuoteAsset::Usdc,
+                funding_rate: 1.5,
+                ..position(Platform::Binance, "ETH", "short")
+            },
+            Position {
+                funding_rate: 0.5,
+                ..position(Platform::Hyperliquid, "ETH", "long")
+            },
+        ];
+
+        let alerts = evaluate_rules(&rules(), &funding_rates, &positions, &balance(1000.0));
+
+        assert!(alerts
+            .iter()
+            .all(|alert| !matches!(alert, Alert::SpreadFlipped { .. })));
+    }
+
+    #[test]
+    fn test_liquidation_risk_on_hedged_leg() {
+        let positions = vec![
+            position_with_liquidation(Platform::Binance, "ETH", "short", Some(110.0)),
+            position_with_liquidation(Platform::Hyperliquid, "ETH", "long", Some(50.0)),
+        ];
+
+        let alerts = evaluate_rules(&rules(), &[], &positions, &balance(1000.0));
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].key(), "liquidation_risk:ETH:Binance");
+        assert_eq!(
+            alerts[0].message(),
+            "ETH Binance leg is 10.00% from liquidation"
+        );
+    }
+
+    #[test]
+    fn test_deduplicates_until_cleared() {
+        let mut dedup = AlertDeduplicator::new();
+        let unhedged = Alert::Unhedged {
+            token: "TIA".to_string(),
+        };
+        let leverage = |leverage| Alert::Leverage {
+            platform: Platform::Binance,
+            leverage,
+        };
+
+        assert_eq!(dedup.new_alerts(vec![leverage(4.0)]), vec![leverage(4.0)]);
+        // still active with a different value
+        assert_eq!(
+            dedup.new_alerts(vec![leverage(4.5), unhedged.clone()]),
+            vec![unhedged.clone()]
+        );
+        // cleared, then fires again
+        assert!(dedup.new_alerts(vec![unhedged.clone()]).is_empty());
+        assert_eq!(dedup.new_alerts(vec![leverage(5.0)]), vec![leverage(5.0)]);
+
+        // failed deliveries are retried on the next evaluation
+        dedup.forget(&leverage(5.0));
+        assert_eq!(dedup.new_alerts(vec![leverage(5.0)]), vec![leverage(5.0)]);
+    }
+}
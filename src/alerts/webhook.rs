@@ -0,0 +1,145 @@
+use super::Alert;
+use anyhow::{bail, Result};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::time::Duration;
+
+/// any endpoint accepting a json post. slack reads `text`, discord `content`
+/// and telegram `sendMessage` urls need the `chat_id` in the query string
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub url: String,
+    pub retries: u32,
+    pub backoff: Duration, // doubles after every failed attempt
+}
+
+impl Webhook {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// posts `alert` to `webhook`, retrying server errors and rate limits
+pub async fn deliver_alert(client: &Client, webhook: &Webhook, alert: &Alert) -> Result<()> {
+    let text = alert.message();
+    let payload = json!({
+        "text": text,
+        "content": text,
+        "alert": alert,
+    });
+
+    let mut backoff = webhook.backoff;
+    let mut attempt = 0;
+
+    loop {
+        let error = match client.post(&webhook.url).json(&payload).send().await {
+            Ok(res) if res.status().is_success() => return Ok(()),
+            Ok(res)
+                if res.status().is_client_error()
+                    && res.status() != StatusCode::TOO_MANY_REQUESTS =>
+            {
+                bail!("Webhook rejected the alert with {}", res.status())
+            }
+            Ok(res) => format!("status {}", res.status()),
+            Err(e) => e.to_string(),
+        };
+
+        attempt += 1;
+        if attempt > webhook.retries {
+            bail!("Could not deliver alert after {attempt} attempts. {error}")
+        }
+
+        log::warn!("Alert delivery failed ({error}), retrying in {backoff:?}");
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, routing::post, Json, Router};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    // statuses to answer with and payloads received
+    type ReceiverState = (Arc<Mutex<Vec<u16>>>, Arc<Mutex<Vec<Value>>>);
+
+    /// local receiver answering with `statuses` in order, recording every payload
+    async fn spawn_receiver(statuses: Vec<u16>) -> Result<(String, Arc<Mutex<Vec<Value>>>)> {
+        let received = Arc::new(Mutex::new(vec![]));
+        let state = (Arc::new(Mutex::new(statuses)), received.clone());
+
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State((statuses, received)): State<ReceiverState>,
+                     Json(payload): Json<Value>| async move {
+                        received.lock().unwrap().push(payload);
+                        let status = statuses.lock().unwrap().remove(0);
+                        StatusCode::from_u16(status).unwrap()
+                    },
+                ),
+            )
+            .with_state(state);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Ok((format!("http://{address}/hook"), received))
+    }
+
+    fn webhook(url: String) -> Webhook {
+        Webhook {
+            url,
+            retries: 2,
+            backoff: Duration::from_millis(10),
+        }
+    }
+
+    fn alert() -> Alert {
+        Alert::Unhedged {
+            token: "TIA".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_server_errors() -> Result<()> {
+        let (url, received) = spawn_receiver(vec![500, 429, 200]).await?;
+
+        deliver_alert(&Client::new(), &webhook(url), &alert()).await?;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[2]["text"], "TIA has an unhedged leg");
+        assert_eq!(received[2]["content"], received[2]["text"]);
+        assert_eq!(received[2]["alert"]["kind"], "unhedged");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delivery_gives_up() -> Result<()> {
+        // client errors are not retried
+        let (url, received) = spawn_receiver(vec![404]).await?;
+        assert!(deliver_alert(&Client::new(), &webhook(url), &alert())
+            .await
+            .is_err());
+        assert_eq!(received.lock().unwrap().len(), 1);
+
+        let (url, received) = spawn_receiver(vec![500, 502, 503]).await?;
+        assert!(deliver_alert(&Client::new(), &webhook(url), &alert())
+            .await
+            .is_err());
+        assert_eq!(received.lock().unwrap().len(), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use super::{retrieve_account_balance, retrieve_venue_balance, Balance};
+use crate::config::{in_account, profile};
+use anyhow::{Context, Result};
+use futures::future::try_join_all;
+use numfmt::{Formatter, Precision::Decimals};
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+use tokio::try_join;
+
+/// balance of one account of the profile, flat so it serializes to a csv row
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountBalance {
+    pub account: String,
+    pub binance_balance: f64,
+    pub binance_open_positions: f64,
+    pub binance_maintenance_margin: f64,
+    pub hyperliquid_balance: f64,
+    pub hyperliquid_open_positions: f64,
+    pub hyperliquid_maintenance_margin: f64,
+}
+
+impl AccountBalance {
+    pub fn new(account: &str, balance: Balance) -> Self {
+        Self {
+            account: account.to_string(),
+            binance_balance: balance.binance_balance,
+            binance_open_positions: balance.binance_open_positions,
+            binance_maintenance_margin: balance.binance_maintenance_margin,
+            hyperliquid_balance: balance.hyperliquid_balance,
+            hyperliquid_open_positions: balance.hyperliquid_open_positions,
+            hyperliquid_maintenance_margin: balance.hyperliquid_maintenance_margin,
+        }
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.binance_balance + self.hyperliquid_balance
+    }
+
+    pub fn open_positions(&self) -> f64 {
+        self.binance_open_positions + self.hyperliquid_open_positions
+    }
+}
+
+/// balances of the profile's own account, as `default`, and of every named account.
+/// a venue a named account shares with the profile is only counted under `default`
+pub async fn retrieve_all_account_balances() -> Result<Vec<AccountBalance>> {
+    let named = profile()
+        .accounts
+        .iter()
+        .map(|(name, credentials)| async move {
+            let balance = in_account(
+                credentials.clone(),
+                retrieve_venue_balance(credentials.owns_binance(), credentials.owns_hyperliquid()),
+            )
+            .await
+            .with_context(|| format!("Could not retrieve the balance of account {name}"))?;
+
+            Ok::<_, anyhow::Error>(AccountBalance::new(name, balance))
+        });
+
+    let (default, named) = try_join!(retrieve_account_balance(), try_join_all(named))?;
+
+    Ok(std::iter::once(AccountBalance::new("default", default))
+        .chain(named)
+        .collect())
+}
+
+pub fn total_balance(balances: &[AccountBalance]) -> AccountBalance {
+    let sum = |field: fn(&AccountBalance) -> f64| balances.iter().map(field).sum();
+
+    AccountBalance {
+        account: "total".to_string(),
+        binance_balance: sum(|b| b.binance_balance),
+        binance_open_positions: sum(|b| b.binance_open_positions),
+        binance_maintenance_margin: sum(|b| b.binance_maintenance_margin),
+        hyperliquid_balance: sum(|b| b.hyperliquid_balance),
+        hyperliquid_open_positions: sum(|b| b.hyperliquid_open_positions),
+        hyperliquid_maintenance_margin: sum(|b| b.hyperliquid_maintenance_margin),
+    }
+}
+
+pub fn build_all_accounts_balance_table(balances: &[AccountBalance]) -> Result<String> {
+    let mut table = Table::new();
+
+    let mut f = Formatter::new()
+        .separator(',')?
+        .prefix("$")?
+        .precision(Decimals(2));
+
+    table.add_row(Row::new(vec![
+        Cell::new("Account"),
+        Cell::new("Binance"),
+        Cell::new("Hyperliquid"),
+        Cell::new("Open Positions"),
+        Cell::new("Leverage"),
+    ]));
+
+    let total = total_balance(balances);
+    for balance in balances.iter().chain([&total]) {
+        // an account can hold nothing on either venue
+        let leverage = match balance.equity() > 0.0 {
+            true => format!("{:.2}", balance.open_positions() / balance.equity()),
+            false => "-".to_string(),
+        };
+
+        table.add_row(Row::new(vec![
+            Cell::new(&balance.account),
+            Cell::new(f.fmt2(balance.binance_balance)),
+            Cell::new(f.fmt2(balance.hyperliquid_balance)),
+            Cell::new(f.fmt2(balance.open_positions())),
+            Cell::new(&leverage),
+        ]));
+    }
+
+    Ok(table.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_balance_sums_accounts() {
+        let main = AccountBalance::new(
+            "default",
+            Balance {
+                binance_balance: 1000.0,
+                binance_open_positions: 2000.0,
+                binance_maintenance_margin: 20.0,
+                hyperliquid_balance: 800.0,
+                hyperliquid_open_positions: 1600.0,
+                hyperliquid_maintenance_margin: 16.0,
+            },
+        );
+        // hyperliquid sub-account sharing the profile's binance keys
+        let sub = AccountBalance::new(
+            "sub",
+            Balance {
+                hyperliquid_balance: 200.0,
+                hyperliquid_open_positions: 400.0,
+                hyperliquid_maintenance_margin: 4.0,
+                ..Default::default()
+            },
+        );
+
+        let total = total_balance(&[main, sub]);
+        assert_eq!(total.account, "total");
+        assert_eq!(total.binance_balance, 1000.0);
+        assert_eq!(total.hyperliquid_balance, 1000.0);
+        assert_eq!(total.open_positions(), 4000.0);
+        assert_eq!(total.hyperliquid_maintenance_margin, 20.0);
+    }
+}
@@ -0,0 +1,100 @@
+use super::Position;
+use crate::symbols::symbols;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// long and short size of a token summed across both venues
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeltaDrift {
+    pub token: String,
+    pub long_size: f64,  // tokens
+    pub short_size: f64, // tokens
+    pub net_size: f64,   // tokens, positive when the long side is larger
+    pub net_usd: f64,
+}
+
+/// net exposure per token, sorted by token
+pub fn calculate_delta_drifts(positions: &[Position]) -> Vec<DeltaDrift> {
+    let mut legs: BTreeMap<String, Vec<&Position>> = BTreeMap::new();
+    for p in positions {
+        legs.entry(symbols().canonical(p.platform, &p.coin))
+            .or_default()
+            .push(p);
+    }
+
+    legs.into_iter()
+        .map(|(token, legs)| {
+            let size = |direction: &str| -> f64 {
+                legs.iter()
+                    .filter(|p| p.direction == direction)
+                    .map(|p| p.size.abs())
+                    .sum()
+            };
+            let long_size = size("long");
+            let short_size = size("short");
+            let net_size = long_size - short_size;
+
+            // venues mark within a few bps of each other
+            let mark_price = legs.iter().map(|p| p.mark_price).sum::<f64>() / legs.len() as f64;
+
+            DeltaDrift {
+                token,
+                long_size,
+                short_size,
+                net_size,
+                net_usd: net_size * mark_price,
+            }
+        })
+        .collect()
+}
+
+/// tokens whose net exposure exceeds `min_usd` either way
+pub fn find_delta_drifts(positions: &[Position], min_usd: f64) -> Vec<DeltaDrift> {
+    calculate_delta_drifts(positions)
+        .into_iter()
+        .filter(|drift| drift.net_usd.abs() >= min_usd)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_fixtures, util::Platform};
+
+    #[test]
+    fn test_find_delta_drifts() {
+        let position = |platform, coin: &str, direction: &str, size, mark_price| Position {
+            size,
+            notional: size * mark_price,
+            mark_price,
+            ..test_fixtures::position(platform, coin, direction)
+        };
+
+        let positions = vec![
+            // rounding left the binance leg a little short
+            position(Platform::Binance, "1000PEPE", "short", 990.0, 0.01),
+            position(Platform::Hyperliquid, "kPEPE", "long", 1000.0, 0.01),
+            position(Platform::Binance, "ETH", "long", 2.0, 2000.0),
+            position(Platform::Hyperliquid, "ETH", "short", 1.5, 2000.0),
+            position(Platform::Binance, "SOL", "short", 10.0, 150.0),
+            position(Platform::Hyperliquid, "SOL", "long", 10.0, 150.0),
+        ];
+
+        let drifts = calculate_delta_drifts(&positions);
+        assert_eq!(drifts.len(), 3);
+        assert_eq!(
+            drifts[0],
+            DeltaDrift {
+                token: "ETH".to_string(),
+                long_size: 2.0,
+                short_size: 1.5,
+                net_size: 0.5,
+                net_usd: 1000.0,
+            }
+        );
+
+        let drifts = find_delta_drifts(&positions, 10.0);
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].token, "ETH");
+    }
+}
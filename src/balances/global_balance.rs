@@ -3,27 +3,42 @@ use crate::hyperliquid::account_information::retrieve_hl_account_info;
 use anyhow::Result;
 use numfmt::{Formatter, Precision::Decimals};
 use prettytable::{Cell, Row, Table};
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Balance {
     pub binance_balance: f64,
     pub binance_open_positions: f64,
+    pub binance_maintenance_margin: f64,
     pub hyperliquid_balance: f64,
     pub hyperliquid_open_positions: f64,
+    pub hyperliquid_maintenance_margin: f64,
 }
 
-async fn retrieve_account_balance() -> Result<Balance> {
-    let binance_acct_info = retrieve_binance_account_info().await?;
-    let hyperliquid_acct_info = retrieve_hl_account_info().await?;
+/// margin balance and open notional of both accounts
+pub async fn retrieve_account_balance() -> Result<Balance> {
+    retrieve_venue_balance(true, true).await
+}
 
-    let binance_open_positions: f64 = binance_acct_info.positions.iter().map(|p| p.notional).sum();
+/// like `retrieve_account_balance`, a venue left out counts as empty
+pub async fn retrieve_venue_balance(binance: bool, hyperliquid: bool) -> Result<Balance> {
+    let mut balance = Balance::default();
 
-    let balance = Balance {
-        binance_balance: binance_acct_info.total_margin_balance,
-        binance_open_positions,
-        hyperliquid_balance: hyperliquid_acct_info.cross_margin_summary.account_value,
-        hyperliquid_open_positions: hyperliquid_acct_info.cross_margin_summary.total_ntl_pos,
-    };
+    if binance {
+        let binance_acct_info = retrieve_binance_account_info().await?;
+        balance.binance_balance = binance_acct_info.total_margin_balance;
+        balance.binance_open_positions =
+            binance_acct_info.positions.iter().map(|p| p.notional).sum();
+        balance.binance_maintenance_margin = binance_acct_info.total_maint_margin;
+    }
+    if hyperliquid {
+        let hyperliquid_acct_info = retrieve_hl_account_info().await?;
+        balance.hyperliquid_balance = hyperliquid_acct_info.cross_margin_summary.account_value;
+        balance.hyperliquid_open_positions =
+            hyperliquid_acct_info.cross_margin_summary.total_ntl_pos;
+        balance.hyperliquid_maintenance_margin =
+            hyperliquid_acct_info.cross_maintenance_margin_used;
+    }
 
     Ok(balance)
 }
@@ -46,18 +61,33 @@ pub async fn build_account_balance_table() -> Result<String> {
         Cell::new("Amount"),
         Cell::new("Open Positions"),
         Cell::new("Leverage"),
+        Cell::new("Margin Ratio"),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("Binance"),
         Cell::new(f.fmt2(b_balance)),
         Cell::new(f.fmt2(balances.binance_open_positions)),
         Cell::new(format!("{:.2}", balances.binance_open_positions / b_balance).as_str()),
+        Cell::new(
+            format!(
+                "{:.2}%",
+                balances.binance_maintenance_margin / b_balance * 100.0
+            )
+            .as_str(),
+        ),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("Hyperliquid"),
         Cell::new(f.fmt2(hl_balance)),
         Cell::new(f.fmt2(balances.hyperliquid_open_positions)),
         Cell::new(format!("{:.2}", balances.hyperliquid_open_positions / hl_balance).as_str()),
+        Cell::new(
+            format!(
+                "{:.2}%",
+                balances.hyperliquid_maintenance_margin / hl_balance * 100.0
+            )
+            .as_str(),
+        ),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("Total"),
@@ -71,6 +101,7 @@ pub async fn build_account_balance_table() -> Result<String> {
             )
             .as_str(),
         ),
+        Cell::new(""),
     ]));
 
     Ok(table.to_string())
@@ -1,5 +1,17 @@
+mod all_accounts;
+mod delta_drift;
 mod global_balance;
 mod open_positions;
 
-pub use global_balance::build_account_balance_table;
-pub use open_positions::{build_account_open_positions_table, retrieve_account_open_positions};
+pub use all_accounts::{
+    build_all_accounts_balance_table, retrieve_all_account_balances, total_balance, AccountBalance,
+};
+pub use delta_drift::{calculate_delta_drifts, find_delta_drifts, DeltaDrift};
+pub use global_balance::{
+    build_account_balance_table, retrieve_account_balance, retrieve_venue_balance, Balance,
+};
+pub use open_positions::{
+    build_account_open_positions_table, find_liquidation_risks, find_open_hedge,
+    retrieve_account_open_positions, summarize_hedges, HedgeSummary, LiquidationRisk, OpenHedge,
+    Position,
+};
@@ -1,64 +1,220 @@
-use std::ops::Neg;
+use std::{collections::BTreeSet, ops::Neg};
 
 use crate::{
+    balances::find_delta_drifts,
     binance::{
         account_information::retrieve_binance_account_info,
-        funding_rates::retrieve_binance_hourly_funding_rates,
+        funding_rates::retrieve_binance_hourly_funding_rates, retrieve_binance_position_risk,
     },
+    constants::{hyperliquid_base_url, LIQUIDATION_RISK_DISTANCE, MIN_DELTA_DRIFT_USD},
     hyperliquid::{
         account_information::retrieve_hl_account_info,
         funding_rates::retrieve_hl_hourly_funding_rates,
     },
-    util::Platform,
+    symbols::{binance_pair, load_symbols, symbols},
+    util::{Platform, QuoteAsset},
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
 use hyperliquid_rust_sdk::InfoClient;
 use numfmt::Formatter;
 use prettytable::{Cell, Row, Table};
+use serde::Serialize;
 use tokio::try_join;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Position {
     pub platform: Platform,
-    pub coin: String,      // without quote
+    pub coin: String,      // venue symbol without quote
+    pub quote: QuoteAsset, // always USDC on hyperliquid
     pub direction: String, // short || long
-    pub size: f64,         // amount of tokens/cryptocurrency
+    pub size: f64,         // units of the asset, see `Asset::scale`
     pub pnl: f64,
-    pub funding: f64,      // USD
-    pub funding_rate: f64, // annualized
-    pub notional: f64,     // notional value of position USD
+    pub funding: f64,                   // USD
+    pub funding_rate: f64,              // annualized
+    pub notional: f64,                  // notional value of position USD
+    pub mark_price: f64,                // per unit of the asset
+    pub liquidation_price: Option<f64>, // none when the leg can't be liquidated
 }
 
+impl Position {
+    /// move of the mark price that liquidates the leg, decimal form
+    pub fn liquidation_distance(&self) -> Option<f64> {
+        let liquidation_price = self.liquidation_price?;
+
+        let distance = if self.direction == "long" {
+            self.mark_price - liquidation_price
+        } else {
+            liquidation_price - self.mark_price
+        };
+
+        Some(distance / self.mark_price)
+    }
+}
+
+/// both legs of a funding rate position on the same token
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenHedge {
+    pub long: Platform,
+    pub size: f64,                 // hedged units of the asset per leg
+    pub binance_quote: QuoteAsset, // perpetual the binance leg is open on
+}
+
+pub fn find_open_hedge(positions: &[Position], token: &str) -> Result<OpenHedge> {
+    let leg = |platform: Platform| {
+        positions
+            .iter()
+            .find(|p| p.platform == platform && symbols().canonical(platform, &p.coin) == token)
+    };
+    let (b_position, hl_position) = (leg(Platform::Binance), leg(Platform::Hyperliquid));
+
+    let (b_position, hl_position) = match (b_position, hl_position) {
+        (Some(b), Some(hl)) => (b, hl),
+        (None, None) => bail!("No open position for {token}"),
+        _ => bail!("Position for {token} is only open on one platform"),
+    };
+
+    if b_position.direction == hl_position.direction {
+        bail!("Both legs of {token} are {}", b_position.direction)
+    }
+
+    let long = if b_position.direction == "long" {
+        Platform::Binance
+    } else {
+        Platform::Hyperliquid
+    };
+
+    Ok(OpenHedge {
+        long,
+        size: b_position.size.min(hl_position.size),
+        binance_quote: b_position.quote,
+    })
+}
+
+/// positions grouped per token with both legs combined
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeSummary {
+    pub token: String,
+    pub hedge: Option<OpenHedge>, // none when a leg is missing or both face the same way
+    pub notional: f64,
+    pub pnl: f64,
+    pub funding: f64,
+    pub liquidation_distance: Option<f64>, // closest leg, decimal form
+}
+
+pub fn summarize_hedges(positions: &[Position]) -> Vec<HedgeSummary> {
+    let tokens: BTreeSet<String> = positions
+        .iter()
+        .map(|p| symbols().canonical(p.platform, &p.coin))
+        .collect();
+
+    tokens
+        .into_iter()
+        .map(|token| {
+            let legs = positions
+                .iter()
+                .filter(|p| symbols().canonical(p.platform, &p.coin) == token);
+
+            HedgeSummary {
+                hedge: find_open_hedge(positions, &token).ok(),
+                notional: legs.clone().map(|p| p.notional).sum(),
+                pnl: legs.clone().map(|p| p.pnl).sum(),
+                funding: legs.clone().map(|p| p.funding).sum(),
+                liquidation_distance: legs
+                    .filter_map(Position::liquidation_distance)
+                    .min_by(f64::total_cmp),
+                token,
+            }
+        })
+        .collect()
+}
+
+/// leg of a delta neutral hedge that is close to liquidation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LiquidationRisk {
+    pub token: String,
+    pub platform: Platform,
+    pub direction: String,
+    pub distance: f64, // decimal form
+}
+
+/// legs of open hedges within `max_distance` (decimal form) of their liquidation price
+pub fn find_liquidation_risks(positions: &[Position], max_distance: f64) -> Vec<LiquidationRisk> {
+    summarize_hedges(positions)
+        .into_iter()
+        .filter(|summary| summary.hedge.is_some())
+        .flat_map(|summary| {
+            positions
+                .iter()
+                .filter(move |p| symbols().canonical(p.platform, &p.coin) == summary.token)
+                .filter_map(|p| {
+                    let distance = p.liquidation_distance()?;
+                    (distance < max_distance).then(|| LiquidationRisk {
+                        token: symbols().canonical(p.platform, &p.coin),
+                        platform: p.platform,
+                        direction: p.direction.clone(),
+                        distance,
+                    })
+                })
+        })
+        .collect()
+}
+
+/// open positions of both accounts sorted by coin
 pub async fn retrieve_account_open_positions() -> Result<Vec<Position>> {
-    let info_client = InfoClient::new(None, None).await.unwrap();
+    let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
 
-    let (binance_acct_info, hyperliquid_acct_info, binance_funding_rates, hl_funding_rates) = try_join!(
+    let (
+        binance_acct_info,
+        hyperliquid_acct_info,
+        binance_funding_rates,
+        hl_funding_rates,
+        binance_position_risk,
+        registry,
+    ) = try_join!(
         retrieve_binance_account_info(),
         retrieve_hl_account_info(),
         retrieve_binance_hourly_funding_rates(),
-        retrieve_hl_hourly_funding_rates(&info_client)
+        retrieve_hl_hourly_funding_rates(&info_client),
+        retrieve_binance_position_risk(),
+        load_symbols()
     )?;
+    // legs of an asset listed with different contract sizes are compared in units of the asset
+    let scale = |platform: Platform, coin: &str| {
+        registry
+            .asset(&registry.canonical(platform, coin))
+            .scale(platform)
+    };
 
     let mut binance_positions: Vec<_> = binance_acct_info
         .positions
         .into_iter()
         .map(|p| {
-            let coin = p.symbol.trim_end_matches("USDT").to_string();
+            let (coin, quote) = binance_pair(&p.symbol).expect("usdt or usdc perpetual");
+            let coin = coin.to_string();
+            let scale = scale(Platform::Binance, &coin);
             let funding_rate = binance_funding_rates
                 .iter()
-                .find(|&rate| rate.name == coin)
+                .find(|&rate| rate.name == coin && rate.quote == quote)
                 .map(|rate| (rate.hourly_funding_rate * 24.0 * 365.0))
                 .expect("funding rate not found");
             let direction = p.position_side.to_lowercase();
+            let risk = binance_position_risk
+                .iter()
+                .find(|risk| risk.is_of(&p.symbol, &p.position_side));
             Position {
                 platform: Platform::Binance,
                 coin,
+                quote,
                 direction,
                 funding_rate,
                 funding: p.funding,
                 notional: p.notional,
                 pnl: p.unrealized_profit,
-                size: p.size,
+                size: p.size * scale,
+                mark_price: risk.map_or(p.notional / p.size, |risk| risk.mark_price) / scale,
+                liquidation_price: risk
+                    .map(|risk| risk.liquidation_price / scale)
+                    .filter(|&price| price > 0.0),
             }
         })
         .collect();
@@ -68,6 +224,7 @@ pub async fn retrieve_account_open_positions() -> Result<Vec<Position>> {
         .into_iter()
         .map(|p| {
             let coin = p.position.coin;
+            let scale = scale(Platform::Hyperliquid, &coin);
             let pnl: f64 = p.position.unrealized_pnl.parse().unwrap();
             let funding_rate = hl_funding_rates
                 .iter()
@@ -80,18 +237,27 @@ pub async fn retrieve_account_open_positions() -> Result<Vec<Position>> {
                 "short".to_string()
             };
             let size = p.position.szi.parse::<f64>().unwrap().abs();
-            Position {
+            let liquidation_price = p
+                .position
+                .liquidation_px
+                .as_deref()
+                .map(str::parse)
+                .transpose()?;
+            Ok(Position {
                 platform: Platform::Hyperliquid,
                 coin,
+                quote: QuoteAsset::Usdc,
                 direction,
                 notional: p.position.notional,
                 funding_rate,
                 funding: p.position.cum_funding.since_open.neg(),
                 pnl,
-                size,
-            }
+                size: size * scale,
+                mark_price: p.position.notional / (size * scale),
+                liquidation_price: liquidation_price.map(|price: f64| price / scale),
+            })
         })
-        .collect();
+        .collect::<Result<_>>()?;
 
     binance_positions.extend(hyperliquid_positions);
     binance_positions.sort_by(|a, b| a.coin.cmp(&b.coin));
@@ -118,6 +284,7 @@ pub async fn build_account_open_positions_table() -> Result<String> {
         Cell::new("Pnl"),
         Cell::new("Funding"),
         Cell::new("Funding rate (apr)"),
+        Cell::new("Liq. distance"),
     ]));
 
     let mut f = Formatter::new()
@@ -125,8 +292,12 @@ pub async fn build_account_open_positions_table() -> Result<String> {
         .prefix("$")?
         .separator(',')?;
 
-    for position in open_positions {
+    for position in &open_positions {
         let fmt_annualized_fr = format!("{:.2}%", position.funding_rate * 100.0);
+        let fmt_liq_distance = match position.liquidation_distance() {
+            Some(distance) => format!("{:.2}%", distance * 100.0),
+            None => "-".to_string(),
+        };
         table.add_row(Row::new(vec![
             Cell::new(&format!("{:?}", position.platform)),
             Cell::new(&position.coin),
@@ -135,15 +306,139 @@ pub async fn build_account_open_positions_table() -> Result<String> {
             Cell::new(f.fmt2(position.pnl)),
             Cell::new(f.fmt2(position.funding)),
             Cell::new(&fmt_annualized_fr),
+            Cell::new(&fmt_liq_distance),
         ]));
     }
 
-    Ok(table.to_string())
+    let mut text = table.to_string();
+    for risk in find_liquidation_risks(&open_positions, LIQUIDATION_RISK_DISTANCE) {
+        text.push_str(&format!(
+            "At risk: {} {:?} {} leg is {:.2}% from liquidation\n",
+            risk.token,
+            risk.platform,
+            risk.direction,
+            risk.distance * 100.0
+        ));
+    }
+    for drift in find_delta_drifts(&open_positions, MIN_DELTA_DRIFT_USD) {
+        text.push_str(&format!(
+            "Delta drift: {} is net {} {:.4} tokens (${:.2}), `hedge {}` restores neutrality\n",
+            drift.token,
+            if drift.net_size > 0.0 {
+                "long"
+            } else {
+                "short"
+            },
+            drift.net_size.abs(),
+            drift.net_usd.abs(),
+            drift.token
+        ));
+    }
+
+    Ok(text)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures;
+
+    #[test]
+    fn test_find_open_hedge() -> Result<()> {
+        let position = |platform, coin: &str, direction: &str, size| Position {
+            size,
+            ..test_fixtures::position(platform, coin, direction)
+        };
+
+        let positions = vec![
+            position(Platform::Binance, "1000PEPE", "short", 1000.0),
+            position(Platform::Hyperliquid, "kPEPE", "long", 990.0),
+            position(Platform::Binance, "TIA", "long", 10.0),
+        ];
+
+        let hedge = find_open_hedge(&positions, "PEPE")?;
+        assert_eq!(
+            hedge,
+            OpenHedge {
+                long: Platform::Hyperliquid,
+                size: 990.0,
+                binance_quote: QuoteAsset::Usdt,
+            }
+        );
+
+        // one leg only
+        assert!(find_open_hedge(&positions, "TIA").is_err());
+        // nothing open
+        assert!(find_open_hedge(&positions, "BTC").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_hedges() {
+        let position = |platform, coin: &str, direction: &str, pnl| Position {
+            pnl,
+            funding: 1.0,
+            notional: 100.0,
+            mark_price: 10.0,
+            ..test_fixtures::position(platform, coin, direction)
+        };
+
+        let positions = vec![
+            position(Platform::Binance, "1000PEPE", "short", 5.0),
+            position(Platform::Hyperliquid, "kPEPE", "long", -3.0),
+            position(Platform::Binance, "TIA", "long", 2.0),
+        ];
+
+        let summaries = summarize_hedges(&positions);
+        assert_eq!(summaries.len(), 2);
+
+        assert_eq!(summaries[0].token, "PEPE");
+        assert_eq!(summaries[0].pnl, 2.0);
+        assert_eq!(summaries[0].funding, 2.0);
+        assert_eq!(summaries[0].notional, 200.0);
+        assert_eq!(
+            summaries[0].hedge.as_ref().map(|h| h.long),
+            Some(Platform::Hyperliquid)
+        );
+
+        assert_eq!(summaries[1].token, "TIA");
+        assert!(summaries[1].hedge.is_none());
+    }
+
+    #[test]
+    fn test_find_liquidation_risks() {
+        let position = |platform, coin: &str, direction: &str, liquidation_price| Position {
+            liquidation_price,
+            ..test_fixtures::position(platform, coin, direction)
+        };
+
+        let positions = vec![
+            // delta neutral but the short leg is 8% away
+            position(Platform::Binance, "ETH", "short", Some(108.0)),
+            position(Platform::Hyperliquid, "ETH", "long", Some(60.0)),
+            position(Platform::Binance, "SOL", "short", Some(150.0)),
+            position(Platform::Hyperliquid, "SOL", "long", None),
+            // unhedged legs are not flagged
+            position(Platform::Binance, "TIA", "long", Some(95.0)),
+        ];
+
+        assert_eq!(positions[1].liquidation_distance(), Some(0.4));
+        assert_eq!(positions[3].liquidation_distance(), None);
+
+        let summaries = summarize_hedges(&positions);
+        assert_eq!(summaries[0].liquidation_distance, Some(0.08));
+
+        assert_eq!(
+            find_liquidation_risks(&positions, 0.15),
+            vec![LiquidationRisk {
+                token: "ETH".to_string(),
+                platform: Platform::Binance,
+                direction: "short".to_string(),
+                distance: 0.08,
+            }]
+        );
+    }
 
     #[tokio::test]
     async fn test_retrieve_account_open_positions() -> Result<()> {
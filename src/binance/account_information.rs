@@ -1,9 +1,13 @@
 use crate::{
-    binance::retrieve_funding_payments, hyperliquid::get_wallet, util::generate_hmac_signature,
+    binance::retrieve_funding_payments,
+    config::binance_api_key,
+    constants::{binance_api_url, hyperliquid_base_url},
+    hyperliquid::get_account_address,
+    symbols::{binance_pair, symbols},
+    util::{generate_hmac_signature, Platform},
 };
 use anyhow::Result;
-use ethers::signers::Signer;
-use hyperliquid_rust_sdk::{BaseUrl, InfoClient, UserFillsResponse};
+use hyperliquid_rust_sdk::{InfoClient, UserFillsResponse};
 use reqwest::Client;
 use serde::Deserialize;
 use serde_aux::field_attributes::deserialize_number_from_string;
@@ -13,6 +17,10 @@ use serde_aux::field_attributes::deserialize_number_from_string;
 pub struct BinanceAccountRes {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub total_margin_balance: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub total_maint_margin: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_withdraw_amount: f64,
     pub positions: Vec<Position>,
 }
 
@@ -42,17 +50,22 @@ pub async fn retrieve_binance_account_info() -> Result<BinanceAccountRes> {
     let signature = generate_hmac_signature(Some(format!("timestamp={timestamp}").to_string()))?;
 
     let url = format!(
-        "https://fapi.binance.com/fapi/v3/account?timestamp={}&signature={}",
-        timestamp, signature
+        "{}/fapi/v3/account?timestamp={}&signature={}",
+        binance_api_url(),
+        timestamp,
+        signature
     );
 
     let res = client
         .get(url)
-        .header("X-MBX-APIKEY", std::env::var("BINANCE_API_KEY")?)
+        .header("X-MBX-APIKEY", binance_api_key()?)
         .send()
         .await?;
 
     let mut binance_account_res: BinanceAccountRes = res.json().await?;
+    binance_account_res
+        .positions
+        .retain(|p| binance_pair(&p.symbol).is_some());
     let user_fills_hl = retrieve_user_fills_hl().await?;
 
     for p in binance_account_res.positions.iter_mut() {
@@ -64,13 +77,17 @@ pub async fn retrieve_binance_account_info() -> Result<BinanceAccountRes> {
             p.position_side = "LONG".to_string();
         }
 
-        let symbol = p.symbol.trim_end_matches("USDT").trim_end_matches("USDC");
+        let (symbol, quote) = binance_pair(&p.symbol).expect("retained above");
+        let asset = symbols().canonical(Platform::Binance, symbol);
         let timestamp_of_interest = user_fills_hl
             .iter()
-            .find(|uf| &uf.coin == symbol && is_directions_match(&p.position_side, &uf.dir))
+            .find(|uf| {
+                symbols().canonical(Platform::Hyperliquid, &uf.coin) == asset
+                    && is_directions_match(&p.position_side, &uf.dir)
+            })
             .expect("token should be there")
             .time;
-        let funding_fees = retrieve_funding_payments(symbol, timestamp_of_interest).await?;
+        let funding_fees = retrieve_funding_payments(symbol, quote, timestamp_of_interest).await?;
         let funding_fees = funding_fees.iter().map(|fee| fee.income).sum::<f64>();
 
         p.funding = funding_fees;
@@ -88,9 +105,9 @@ fn is_directions_match(b_dir: &str, hl_dir: &str) -> bool {
 }
 
 async fn retrieve_user_fills_hl() -> Result<Vec<UserFillsResponse>> {
-    let info_client = InfoClient::new(None, Some(BaseUrl::Mainnet)).await?;
+    let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
 
-    let user_fills = info_client.user_fills(get_wallet()?.address()).await?;
+    let user_fills = info_client.user_fills(get_account_address()?).await?;
     let user_fills = user_fills
         .into_iter()
         .filter(|uf| &uf.start_position == "0.0")
@@ -116,12 +133,14 @@ mod tests {
         let signature =
             generate_hmac_signature(Some(format!("timestamp={timestamp}").to_string()))?;
         let url = format!(
-            "https://fapi.binance.com/fapi/v3/account?timestamp={}&signature={}",
-            timestamp, signature
+            "{}/fapi/v3/account?timestamp={}&signature={}",
+            binance_api_url(),
+            timestamp,
+            signature
         );
         let res = client
             .get(url)
-            .header("X-MBX-APIKEY", std::env::var("BINANCE_API_KEY")?)
+            .header("X-MBX-APIKEY", binance_api_key()?)
             .send()
             .await?;
 
@@ -1,6 +1,8 @@
+use crate::constants::binance_api_url;
 use crate::util::LimitOrder;
 use crate::util::Orderbook;
 use crate::util::Platform;
+use crate::util::QuoteAsset;
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -11,10 +13,15 @@ struct BinanceOrderBook {
     asks: Vec<Vec<String>>,
 }
 
-pub async fn retrieve_binance_order_book(token: &str) -> Result<Orderbook> {
+/// snapshot of the `quote` perpetual book of `token`, already in binance naming (e.g. `1000PEPE`)
+pub async fn retrieve_binance_order_book(token: &str, quote: QuoteAsset) -> Result<Orderbook> {
     let client = Client::new();
 
-    let url = format!("https://fapi.binance.com/fapi/v1/depth?symbol={token}USDT");
+    let url = format!(
+        "{}/fapi/v1/depth?symbol={}",
+        binance_api_url(),
+        quote.pair(token)
+    );
 
     let response = client.get(&url).send().await?;
     let orderbook: BinanceOrderBook = response.json().await?;
@@ -54,7 +61,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_retrieve_binance_asks() {
-        let result = retrieve_binance_order_book("1000PEPE").await.unwrap();
+        let result = retrieve_binance_order_book("1000PEPE", QuoteAsset::Usdt)
+            .await
+            .unwrap();
 
         println!("{:#?}", result);
 
@@ -0,0 +1,51 @@
+use crate::{
+    config::binance_api_key,
+    constants::binance_api_url,
+    fees::TradingFees,
+    util::{generate_hmac_signature, QuoteAsset},
+};
+use anyhow::{bail, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CommissionRateRes {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    maker_commission_rate: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    taker_commission_rate: f64,
+}
+
+/// maker and taker rate of the account on the `quote` perpetual of `token`, vip tier included, the bnb discount is not.
+/// USDC perpetuals usually charge less
+pub async fn retrieve_binance_commission_rate(
+    token: &str,
+    quote: QuoteAsset,
+) -> Result<TradingFees> {
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let query = format!("symbol={}&timestamp={timestamp}", quote.pair(token));
+    let signature = generate_hmac_signature(Some(query.clone()))?;
+
+    let res = Client::new()
+        .get(format!(
+            "{}/fapi/v1/commissionRate?{query}&signature={signature}",
+            binance_api_url()
+        ))
+        .header("X-MBX-APIKEY", binance_api_key()?)
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error = res.text().await?;
+        bail!("Could not retrieve Binance commission rate. {error}")
+    }
+
+    let rates: CommissionRateRes = res.json().await?;
+
+    Ok(TradingFees {
+        maker: rates.maker_commission_rate,
+        taker: rates.taker_commission_rate,
+    })
+}
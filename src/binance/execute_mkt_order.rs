@@ -1,5 +1,7 @@
 use super::get_binance_avg_price;
-use crate::util::{generate_hmac_signature, OrderFilled, Platform, Side};
+use crate::config::binance_api_key;
+use crate::constants::binance_api_url;
+use crate::util::{generate_hmac_signature, OrderFilled, Platform, QuoteAsset, Side};
 use anyhow::{bail, Result};
 use reqwest::Client;
 use serde::Deserialize;
@@ -10,20 +12,27 @@ pub struct MktOrderRes {
     pub order_id: u128,
 }
 
-pub async fn execute_mkt_order(token: String, size: f64, is_buy: bool) -> Result<OrderFilled> {
+/// market order of `size` tokens on the `quote` perpetual of `token`
+pub async fn execute_mkt_order(
+    token: String,
+    quote: QuoteAsset,
+    size: f64,
+    is_buy: bool,
+) -> Result<OrderFilled> {
     let client = Client::new();
     let timestamp = chrono::Utc::now().timestamp_millis();
 
     let side = if is_buy { Side::Buy } else { Side::Sell };
     let side_ = format!("{:?}", side).to_uppercase();
+    let symbol = quote.pair(&token);
     let signature = generate_hmac_signature(Some(format!(
-        "symbol={token}USDT&side={side_}&type=MARKET&quantity={size}&timestamp={timestamp}"
+        "symbol={symbol}&side={side_}&type=MARKET&quantity={size}&timestamp={timestamp}"
     )))?;
-    let url = format!("https://fapi.binance.com/fapi/v1/order?symbol={token}USDT&side={side_}&type=MARKET&quantity={size}&timestamp={timestamp}&signature={signature}");
+    let url = format!("{}/fapi/v1/order?symbol={symbol}&side={side_}&type=MARKET&quantity={size}&timestamp={timestamp}&signature={signature}", binance_api_url());
 
     let res = client
         .post(url)
-        .header("X-MBX-APIKEY", std::env::var("BINANCE_API_KEY")?)
+        .header("X-MBX-APIKEY", binance_api_key()?)
         .send()
         .await?;
 
@@ -34,7 +43,8 @@ pub async fn execute_mkt_order(token: String, size: f64, is_buy: bool) -> Result
 
     let binance_account_res: MktOrderRes = res.json().await?;
 
-    let avg_price = get_binance_avg_price(token.clone(), binance_account_res.order_id).await?;
+    let avg_price =
+        get_binance_avg_price(token.clone(), quote, binance_account_res.order_id).await?;
 
     Ok(OrderFilled {
         token,
@@ -58,7 +68,7 @@ mod tests {
     async fn test_mkt_order() -> Result<()> {
         dotenv::dotenv().ok();
 
-        let res = execute_mkt_order("TIA".to_string(), 7000.0, true).await?;
+        let res = execute_mkt_order("TIA".to_string(), QuoteAsset::Usdt, 7000.0, true).await?;
 
         println!("{res:#?}");
 
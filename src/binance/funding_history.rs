@@ -1,3 +1,5 @@
+use crate::constants::binance_api_url;
+use crate::util::QuoteAsset;
 use anyhow::{bail, Result};
 use reqwest::Client;
 use serde::Deserialize;
@@ -11,12 +13,17 @@ struct FundingHistory {
     funding_time: u64, // ms timestamp
 }
 
-async fn retrieve_binance_funding_history(token: String) -> Result<Vec<FundingHistory>> {
+async fn retrieve_binance_funding_history(
+    token: String,
+    quote: QuoteAsset,
+) -> Result<Vec<FundingHistory>> {
     let client = Client::new();
 
     let res = client
         .get(format!(
-            "https://fapi.binance.com/fapi/v1/fundingRate?symbol={token}USDT"
+            "{}/fapi/v1/fundingRate?symbol={}",
+            binance_api_url(),
+            quote.pair(&token)
         ))
         .send()
         .await?;
@@ -30,8 +37,12 @@ async fn retrieve_binance_funding_history(token: String) -> Result<Vec<FundingHi
     Ok(fh)
 }
 
-pub async fn retrieve_binance_past_daily_fh(token: String, past_days: u16) -> Result<Vec<f64>> {
-    let mut fh = retrieve_binance_funding_history(token).await?;
+pub async fn retrieve_binance_past_daily_fh(
+    token: String,
+    quote: QuoteAsset,
+    past_days: u16,
+) -> Result<Vec<f64>> {
+    let mut fh = retrieve_binance_funding_history(token, quote).await?;
     fh.sort_by(|a, b| b.funding_time.cmp(&a.funding_time));
 
     // diff between two consecutive funding times `&fh[0] > &fh[1]`
@@ -53,7 +64,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_retrieve_hl_funding_history() -> Result<()> {
-        let funding_hist = retrieve_binance_funding_history("ETH".to_string()).await?;
+        let funding_hist =
+            retrieve_binance_funding_history("ETH".to_string(), QuoteAsset::Usdt).await?;
 
         println!("{funding_hist:#?}");
 
@@ -64,7 +76,8 @@ mod tests {
     async fn test_retrieve_hl_fh_avg() -> Result<()> {
         let coin = "WIF".to_string();
         let past_days = 3;
-        let avg_funding_rate = retrieve_binance_past_daily_fh(coin, past_days).await?;
+        let avg_funding_rate =
+            retrieve_binance_past_daily_fh(coin, QuoteAsset::Usdt, past_days).await?;
 
         println!("{avg_funding_rate:#?}");
 
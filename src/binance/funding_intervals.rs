@@ -1,3 +1,4 @@
+use crate::constants::binance_api_url;
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -13,7 +14,7 @@ pub async fn retrieve_binance_funding_info() -> Result<Vec<BinanceFundingInfo>>
     let client = Client::new();
 
     let req = client
-        .get("https://fapi.binance.com/fapi/v1/fundingInfo")
+        .get(format!("{}/fapi/v1/fundingInfo", binance_api_url()))
         .send()
         .await
         .unwrap();
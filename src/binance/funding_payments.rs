@@ -1,4 +1,6 @@
-use crate::util::generate_hmac_signature;
+use crate::config::binance_api_key;
+use crate::constants::binance_api_url;
+use crate::util::{generate_hmac_signature, QuoteAsset};
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -14,27 +16,30 @@ pub struct FundingPayment {
 
 pub async fn retrieve_funding_payments(
     token: &str,
+    quote: QuoteAsset,
     start_time: u64,
 ) -> Result<Vec<FundingPayment>> {
     let client = Client::new();
 
     let timestamp = chrono::Utc::now().timestamp_millis();
+    let symbol = quote.pair(token);
 
     let signature = generate_hmac_signature(Some(
         format!(
-            "symbol={token}USDT&incomeType=FUNDING_FEE&startTime={start_time}&endTime={timestamp}&timestamp={timestamp}"
+            "symbol={symbol}&incomeType=FUNDING_FEE&startTime={start_time}&endTime={timestamp}&timestamp={timestamp}"
         )
         .to_string(),
     ))?;
 
     let url = format!(
-        "https://fapi.binance.com/fapi/v1/income?symbol={}USDT&incomeType=FUNDING_FEE&startTime={}&endTime={}&timestamp={}&signature={}",
-        token, start_time, timestamp, timestamp, signature
+        "{}/fapi/v1/income?symbol={}&incomeType=FUNDING_FEE&startTime={}&endTime={}&timestamp={}&signature={}",
+        binance_api_url(),
+        symbol, start_time, timestamp, timestamp, signature
     );
 
     let res = client
         .get(url)
-        .header("X-MBX-APIKEY", std::env::var("BINANCE_API_KEY")?)
+        .header("X-MBX-APIKEY", binance_api_key()?)
         .send()
         .await?;
 
@@ -3,17 +3,25 @@ use crate::binance::{
     funding_intervals::retrieve_binance_funding_info,
     raw_funding_rate::retrieve_binance_raw_funding_rates,
 };
+use crate::util::QuoteAsset;
 use anyhow::Result;
 use tokio::try_join;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BinanceFundingRate {
-    pub name: String,
+    pub name: String, // without quote
+    pub quote: QuoteAsset,
     pub hourly_funding_rate: f64,
     pub max_leverage: f64,
     pub mark_price: f64,
+    pub next_funding_rate: f64, // predicted rate of the next payment, per funding interval
+    pub next_funding_time: i64, // unix ms
+    pub funding_interval: f64,  // hours between payments
+    pub interest_rate: f64,     // per funding interval, decimal form
+    pub premium: f64,           // mark price over the index, decimal form
 }
 
+/// USDT and USDC perpetuals with their funding rate normalized to one hour
 pub async fn retrieve_binance_hourly_funding_rates() -> Result<Vec<BinanceFundingRate>> {
     let (raw_funding_rates, funding_info, token_leverage) = try_join!(
         retrieve_binance_raw_funding_rates(),
@@ -24,40 +32,37 @@ pub async fn retrieve_binance_hourly_funding_rates() -> Result<Vec<BinanceFundin
     let mut hourly_funding_rates = Vec::new();
 
     for rates in raw_funding_rates {
+        // only perpetuals, named by their base asset
+        let Some((listing, quote)) = token_leverage
+            .iter()
+            .find(|t| t.symbol == rates.symbol && t.is_perpetual())
+            .and_then(|t| Some((t, t.quote()?)))
+        else {
+            continue;
+        };
         let interval = funding_info
             .iter()
             .find(|info| info.symbol == rates.symbol)
             .map(|info| info.funding_interval_hours)
             .unwrap_or(8.0);
-        let required_margin_percent = token_leverage
-            .iter()
-            .find(|t| t.symbol == rates.symbol)
-            .map(|t| t.required_margin_percent)
-            .unwrap_or(5.0);
 
         let hourly_rate = rates.last_funding_rate / interval;
-        let max_leverage = 100.0 / required_margin_percent;
+        let max_leverage = 100.0 / listing.required_margin_percent;
 
         hourly_funding_rates.push(BinanceFundingRate {
-            name: rates.symbol,
+            name: listing.base_asset.clone(),
+            quote,
             mark_price: rates.mark_price,
             hourly_funding_rate: hourly_rate,
             max_leverage,
+            next_funding_rate: rates.last_funding_rate,
+            next_funding_time: rates.next_funding_time,
+            funding_interval: interval,
+            interest_rate: rates.interest_rate,
+            premium: rates.premium(),
         });
     }
 
-    // Remove anything other than USDT and only use Base symbol name
-    let hourly_funding_rates = hourly_funding_rates
-        .into_iter()
-        .filter(|t| t.name.ends_with("USDT"))
-        .map(|t| BinanceFundingRate {
-            name: t.name.trim_end_matches("USDT").to_string(),
-            mark_price: t.mark_price,
-            hourly_funding_rate: t.hourly_funding_rate,
-            max_leverage: t.max_leverage,
-        })
-        .collect();
-
     Ok(hourly_funding_rates)
 }
 
@@ -1,3 +1,5 @@
+use crate::constants::binance_api_url;
+use crate::util::QuoteAsset;
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Deserializer};
@@ -14,12 +16,26 @@ struct Response {
 #[serde(rename_all = "camelCase")]
 pub struct TokenLeverage {
     pub symbol: String,
+    pub base_asset: String, // contract prefix included, e.g. 1000PEPE
+    pub quote_asset: String,
+    pub contract_type: String, // PERPETUAL || CURRENT_QUARTER ...
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub required_margin_percent: f64,
     #[serde(deserialize_with = "deserialize_nth_item")]
     pub filters: MarketLotSize,
 }
 
+impl TokenLeverage {
+    /// USDT or USDC perpetual, the contracts a hedge can trade
+    pub fn is_perpetual(&self) -> bool {
+        self.contract_type == "PERPETUAL" && self.quote().is_some()
+    }
+
+    pub fn quote(&self) -> Option<QuoteAsset> {
+        self.quote_asset.parse().ok()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketLotSize {
@@ -31,7 +47,7 @@ pub async fn retrieve_binance_general_info() -> Result<Vec<TokenLeverage>> {
     let client = Client::new();
 
     let req = client
-        .get("https://fapi.binance.com/fapi/v1/exchangeInfo")
+        .get(format!("{}/fapi/v1/exchangeInfo", binance_api_url()))
         .send()
         .await?;
 
@@ -1,4 +1,6 @@
-use crate::util::generate_hmac_signature;
+use crate::config::binance_api_key;
+use crate::constants::binance_api_url;
+use crate::util::{generate_hmac_signature, QuoteAsset};
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -15,25 +17,35 @@ struct BinanceOrder {
     orig_qty: String,
 }
 
-pub async fn get_binance_avg_price(token: String, order_id: u128) -> Result<f64> {
-    let o = get_binance_order(token, order_id).await?;
+pub async fn get_binance_avg_price(
+    token: String,
+    quote: QuoteAsset,
+    order_id: u128,
+) -> Result<f64> {
+    let o = get_binance_order(token, quote, order_id).await?;
     Ok(o.avg_price)
 }
 
-async fn get_binance_order(token: String, order_id: u128) -> Result<BinanceOrder> {
+async fn get_binance_order(
+    token: String,
+    quote: QuoteAsset,
+    order_id: u128,
+) -> Result<BinanceOrder> {
     let client = Client::new();
     let timestamp = chrono::Utc::now().timestamp_millis();
+    let symbol = quote.pair(&token);
 
     let signature = generate_hmac_signature(Some(format!(
-        "symbol={token}USDT&orderId={order_id}&timestamp={timestamp}"
+        "symbol={symbol}&orderId={order_id}&timestamp={timestamp}"
     )))?;
     let url = format!(
-        "https://fapi.binance.com/fapi/v1/order?symbol={token}USDT&orderId={order_id}&timestamp={timestamp}&signature={signature}"
+        "{}/fapi/v1/order?symbol={symbol}&orderId={order_id}&timestamp={timestamp}&signature={signature}",
+        binance_api_url()
     );
 
     let res = client
         .get(url)
-        .header("X-MBX-APIKEY", std::env::var("BINANCE_API_KEY")?)
+        .header("X-MBX-APIKEY", binance_api_key()?)
         .send()
         .await?;
 
@@ -50,7 +62,7 @@ mod tests {
     async fn test_query_order() -> Result<()> {
         dotenv::dotenv().ok();
 
-        let res = get_binance_order("TIA".to_string(), 7371815734).await?;
+        let res = get_binance_order("TIA".to_string(), QuoteAsset::Usdt, 7371815734).await?;
 
         println!("{res:#?}");
 
@@ -0,0 +1,49 @@
+use super::signed_request::send_signed;
+use crate::{constants::binance_api_url, util::QuoteAsset};
+use anyhow::{bail, Result};
+use reqwest::Method;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+struct ErrorRes {
+    code: i64,
+    msg: String,
+}
+
+const NO_NEED_TO_CHANGE_MARGIN_TYPE: i64 = -4046;
+
+/// sets the initial leverage of the `quote` perpetual of `token`
+pub async fn set_binance_leverage(token: &str, quote: QuoteAsset, leverage: u32) -> Result<()> {
+    signed_post(
+        "/fapi/v1/leverage",
+        format!("symbol={}&leverage={leverage}", quote.pair(token)),
+    )
+    .await
+}
+
+/// switches the `quote` perpetual of `token` between isolated and cross margin,
+/// fails while a position or order is open in the other mode
+pub async fn set_binance_margin_type(token: &str, quote: QuoteAsset, isolated: bool) -> Result<()> {
+    let margin_type = if isolated { "ISOLATED" } else { "CROSSED" };
+
+    signed_post(
+        "/fapi/v1/marginType",
+        format!("symbol={}&marginType={margin_type}", quote.pair(token)),
+    )
+    .await
+}
+
+async fn signed_post(path: &str, query: String) -> Result<()> {
+    let res = send_signed(Method::POST, &binance_api_url(), path, query).await?;
+
+    if res.status().is_success() {
+        return Ok(());
+    }
+
+    let error: ErrorRes = res.json().await?;
+    if error.code == NO_NEED_TO_CHANGE_MARGIN_TYPE {
+        return Ok(());
+    }
+
+    bail!("Binance request to {path} failed. {}", error.msg)
+}
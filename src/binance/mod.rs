@@ -1,5 +1,6 @@
 pub mod account_information;
 mod b_orderbook;
+mod commission_rate;
 mod execute_mkt_order;
 mod funding_history;
 mod funding_intervals;
@@ -7,15 +8,33 @@ mod funding_payments;
 pub mod funding_rates;
 mod general_info;
 mod get_order;
+mod leverage;
 mod open_interest;
+mod position_risk;
+mod premium_index;
 mod raw_funding_rate;
+mod signed_request;
 mod step_size;
+mod transfers;
+mod ws_mark_price;
+mod ws_orderbook;
 
 pub use b_orderbook::retrieve_binance_order_book;
+pub use commission_rate::retrieve_binance_commission_rate;
 pub use execute_mkt_order::execute_mkt_order;
 pub use funding_history::retrieve_binance_past_daily_fh;
+pub use funding_intervals::retrieve_binance_funding_info;
 pub use funding_payments::*;
 pub use general_info::retrieve_binance_general_info;
 pub use get_order::get_binance_avg_price;
+pub use leverage::{set_binance_leverage, set_binance_margin_type};
 pub use open_interest::retrieve_token_open_interest;
-pub use step_size::{get_trimmed_quantity, retrieve_step_size};
+pub use position_risk::{retrieve_binance_position_risk, PositionRisk};
+pub use premium_index::retrieve_binance_hourly_premiums;
+pub use step_size::{get_floored_quantity, get_trimmed_quantity, retrieve_step_size};
+pub use transfers::{
+    convert_usdc, retrieve_spot_balance, retrieve_usdc_deposit_address, transfer_futures,
+    withdraw_usdc_to_arbitrum,
+};
+pub use ws_mark_price::{stream_binance_mark_prices, MarkPriceUpdate};
+pub use ws_orderbook::stream_binance_order_book;
@@ -1,3 +1,5 @@
+use crate::constants::binance_api_url;
+use crate::util::QuoteAsset;
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -10,10 +12,17 @@ pub struct OpenInterest {
     pub open_interest: String,
 }
 
-pub async fn retrieve_token_open_interest(token: String) -> Result<OpenInterest> {
+pub async fn retrieve_token_open_interest(
+    token: String,
+    quote: QuoteAsset,
+) -> Result<OpenInterest> {
     let client = Client::new();
 
-    let url = format!("https://fapi.binance.com/fapi/v1/openInterest?symbol={token}USDT");
+    let url = format!(
+        "{}/fapi/v1/openInterest?symbol={}",
+        binance_api_url(),
+        quote.pair(&token)
+    );
     let req = client.get(url).send().await?;
 
     let pair_oi: OpenInterest = req.json().await?;
@@ -27,7 +36,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_retrieve_binance_leverage() {
-        let pair_oi = retrieve_token_open_interest("BTC".to_string())
+        let pair_oi = retrieve_token_open_interest("BTC".to_string(), QuoteAsset::Usdt)
             .await
             .unwrap();
 
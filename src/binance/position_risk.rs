@@ -0,0 +1,69 @@
+use crate::config::binance_api_key;
+use crate::constants::binance_api_url;
+use crate::util::generate_hmac_signature;
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionRisk {
+    pub symbol: String,
+    pub position_side: String, // BOTH in one-way mode, LONG or SHORT in hedge mode
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub mark_price: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub liquidation_price: f64, // 0 when the position can't be liquidated
+}
+
+impl PositionRisk {
+    /// whether this is the risk of the `position_side` (LONG or SHORT) position on `symbol`
+    pub fn is_of(&self, symbol: &str, position_side: &str) -> bool {
+        self.symbol == symbol
+            && (self.position_side == "BOTH" || self.position_side == position_side)
+    }
+}
+
+/// mark and liquidation prices of the open positions
+pub async fn retrieve_binance_position_risk() -> Result<Vec<PositionRisk>> {
+    let client = Client::new();
+    let timestamp = chrono::Utc::now().timestamp_millis();
+
+    let signature = generate_hmac_signature(Some(format!("timestamp={timestamp}")))?;
+    let url = format!(
+        "{}/fapi/v3/positionRisk?timestamp={timestamp}&signature={signature}",
+        binance_api_url()
+    );
+
+    let res = client
+        .get(url)
+        .header("X-MBX-APIKEY", binance_api_key()?)
+        .send()
+        .await?;
+
+    let position_risk: Vec<PositionRisk> = res.json().await?;
+
+    Ok(position_risk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_risk_matches_position_side() {
+        let risk = |position_side: &str| PositionRisk {
+            symbol: "ETHUSDT".to_string(),
+            position_side: position_side.to_string(),
+            mark_price: 2500.0,
+            liquidation_price: 0.0,
+        };
+
+        // hedge mode keeps one risk per side
+        assert!(risk("SHORT").is_of("ETHUSDT", "SHORT"));
+        assert!(!risk("LONG").is_of("ETHUSDT", "SHORT"));
+        assert!(risk("BOTH").is_of("ETHUSDT", "LONG"));
+        assert!(!risk("BOTH").is_of("ETHUSDC", "LONG"));
+    }
+}
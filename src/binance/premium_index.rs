@@ -0,0 +1,72 @@
+use crate::constants::binance_api_url;
+use crate::util::QuoteAsset;
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::Value;
+
+// binance caps the klines of one request
+const MAX_KLINES: u16 = 1500;
+
+/// hourly closes of the premium index of `token`'s `quote` perpetual over the past `hours`, oldest first.
+/// the last one belongs to the hour in progress
+pub async fn retrieve_binance_hourly_premiums(
+    token: String,
+    quote: QuoteAsset,
+    hours: u16,
+) -> Result<Vec<f64>> {
+    let client = Client::new();
+
+    let url = format!(
+        "{}/fapi/v1/premiumIndexKlines?symbol={}&interval=1h&limit={}",
+        binance_api_url(),
+        quote.pair(&token),
+        hours.min(MAX_KLINES)
+    );
+    let res = client.get(url).send().await?;
+
+    let klines: Vec<Vec<Value>> = res.json().await?;
+
+    parse_kline_closes(&klines)
+}
+
+/// klines are arrays of `[open time, open, high, low, close, ..]` with prices as strings
+fn parse_kline_closes(klines: &[Vec<Value>]) -> Result<Vec<f64>> {
+    klines
+        .iter()
+        .map(|kline| {
+            let close = kline
+                .get(4)
+                .and_then(Value::as_str)
+                .context("premium index kline without a close")?;
+            Ok(close.parse()?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kline_closes() {
+        let klines: Vec<Vec<Value>> = serde_json::from_str(
+            r#"[
+                [1704103200000,"-0.00010000","0.00002000","-0.00020000","-0.00005000","0",1704106799999,"0",60,"0","0","0"],
+                [1704106800000,"-0.00005000","0.00030000","-0.00005000","0.00025000","0",1704110399999,"0",60,"0","0","0"]
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(parse_kline_closes(&klines).unwrap(), [-0.00005, 0.00025]);
+        assert!(parse_kline_closes(&[vec![Value::from(1)]]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_binance_hourly_premiums() {
+        let premiums = retrieve_binance_hourly_premiums("ETH".to_string(), QuoteAsset::Usdt, 48)
+            .await
+            .unwrap();
+
+        println!("{premiums:#?}");
+    }
+}
@@ -1,3 +1,5 @@
+use crate::constants::binance_api_url;
+use crate::util::calculate_premium;
 use anyhow::Result;
 use reqwest::Client;
 use serde::Deserialize;
@@ -8,16 +10,28 @@ use serde_aux::field_attributes::deserialize_number_from_string;
 pub struct BinanceIndexFundingRate {
     pub symbol: String,
     #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub last_funding_rate: f64,
+    pub last_funding_rate: f64, // predicted rate of the next payment, per funding interval
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub mark_price: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub index_price: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub interest_rate: f64, // per funding interval, decimal form
+    pub next_funding_time: i64, // unix ms
+}
+
+impl BinanceIndexFundingRate {
+    /// mark price over the index, decimal form
+    pub fn premium(&self) -> f64 {
+        calculate_premium(self.mark_price, self.index_price)
+    }
 }
 
 pub async fn retrieve_binance_raw_funding_rates() -> Result<Vec<BinanceIndexFundingRate>> {
     let client = Client::new();
 
     let req = client
-        .get("https://fapi.binance.com/fapi/v1/premiumIndex")
+        .get(format!("{}/fapi/v1/premiumIndex", binance_api_url()))
         .send()
         .await?;
 
@@ -0,0 +1,47 @@
+use crate::{config::binance_api_key, util::generate_hmac_signature};
+use anyhow::{bail, Result};
+use reqwest::{Client, Method, Response};
+
+/// signs `query` with the current timestamp and sends it to `base_url`, error statuses are left to the caller
+pub(crate) async fn send_signed(
+    method: Method,
+    base_url: &str,
+    path: &str,
+    query: String,
+) -> Result<Response> {
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let query = if query.is_empty() {
+        format!("timestamp={timestamp}")
+    } else {
+        format!("{query}&timestamp={timestamp}")
+    };
+    let signature = generate_hmac_signature(Some(query.clone()))?;
+
+    let res = Client::new()
+        .request(
+            method,
+            format!("{base_url}{path}?{query}&signature={signature}"),
+        )
+        .header("X-MBX-APIKEY", binance_api_key()?)
+        .send()
+        .await?;
+
+    Ok(res)
+}
+
+/// like `send_signed` but fails with binance's message on an error status
+pub(crate) async fn signed_request(
+    method: Method,
+    base_url: &str,
+    path: &str,
+    query: String,
+) -> Result<Response> {
+    let res = send_signed(method, base_url, path, query).await?;
+
+    if !res.status().is_success() {
+        let error = res.text().await?;
+        bail!("Binance request to {path} failed. {error}")
+    }
+
+    Ok(res)
+}
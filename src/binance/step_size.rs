@@ -1,12 +1,13 @@
 use super::retrieve_binance_general_info;
-use anyhow::Result;
+use crate::util::QuoteAsset;
+use anyhow::{Context, Result};
 
-pub async fn retrieve_step_size(token: String) -> Result<f64> {
+pub async fn retrieve_step_size(token: String, quote: QuoteAsset) -> Result<f64> {
     let step_size = retrieve_binance_general_info()
         .await?
         .iter()
-        .find(|t| t.symbol == format!("{token}USDT"))
-        .expect("could not find token")
+        .find(|t| t.symbol == quote.pair(&token))
+        .with_context(|| format!("{} is not listed on Binance", quote.pair(&token)))?
         .filters
         .step_size;
 
@@ -14,5 +15,38 @@ pub async fn retrieve_step_size(token: String) -> Result<f64> {
 }
 
 pub fn get_trimmed_quantity(qty: f64, step_size: f64) -> f64 {
-    (qty / step_size).round() * step_size
+    // strip float noise so the quantity prints with the step's decimals
+    let decimals = (-step_size.log10()).ceil().max(0.0) as i32;
+    let factor = 10f64.powi(decimals);
+    ((qty / step_size).round() * step_size * factor).round() / factor
+}
+
+/// like `get_trimmed_quantity` but never rounds up, an order can't exceed `qty`
+pub fn get_floored_quantity(qty: f64, step_size: f64) -> f64 {
+    let decimals = (-step_size.log10()).ceil().max(0.0) as i32;
+    let factor = 10f64.powi(decimals);
+    // the epsilon keeps an exact multiple from flooring a whole step lower
+    ((qty / step_size + 1e-9).floor() * step_size * factor).round() / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_trimmed_quantity() {
+        assert_eq!(get_trimmed_quantity(0.30000000000000004, 0.1), 0.3);
+        assert_eq!(get_trimmed_quantity(12.3456, 0.01), 12.35);
+        assert_eq!(get_trimmed_quantity(1234.0, 1.0), 1234.0);
+        assert_eq!(get_trimmed_quantity(1234.0, 10.0), 1230.0);
+        assert_eq!(format!("{}", get_trimmed_quantity(0.7, 0.1)), "0.7");
+    }
+
+    #[test]
+    fn test_get_floored_quantity() {
+        assert_eq!(get_floored_quantity(12.3456, 0.01), 12.34);
+        assert_eq!(get_floored_quantity(0.3, 0.1), 0.3);
+        assert_eq!(get_floored_quantity(0.19, 0.1), 0.1);
+        assert_eq!(get_floored_quantity(1239.0, 10.0), 1230.0);
+    }
 }
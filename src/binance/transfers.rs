@@ -0,0 +1,120 @@
+use super::signed_request::signed_request;
+use crate::{
+    constants::binance_spot_api_url,
+    util::{fmt_transfer_amount, QuoteAsset},
+};
+use anyhow::Result;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+#[derive(Deserialize, Debug)]
+struct DepositAddressRes {
+    address: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct SpotAccountRes {
+    balances: Vec<SpotBalance>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SpotBalance {
+    asset: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    free: f64,
+}
+
+/// moves `amount` of `asset` between the usd-m futures wallet and the spot wallet
+pub async fn transfer_futures(asset: QuoteAsset, amount: f64, to_futures: bool) -> Result<()> {
+    let transfer_type = if to_futures {
+        "MAIN_UMFUTURE"
+    } else {
+        "UMFUTURE_MAIN"
+    };
+
+    signed_request(
+        Method::POST,
+        &binance_spot_api_url(),
+        "/sapi/v1/asset/transfer",
+        format!(
+            "type={transfer_type}&asset={asset}&amount={}",
+            fmt_transfer_amount(amount)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// market swaps `amount` usdt for usdc on spot, or `amount` usdc back to usdt
+pub async fn convert_usdc(amount: f64, buy_usdc: bool) -> Result<()> {
+    let amount = fmt_transfer_amount(amount);
+    let query = if buy_usdc {
+        format!("symbol=USDCUSDT&side=BUY&type=MARKET&quoteOrderQty={amount}")
+    } else {
+        format!("symbol=USDCUSDT&side=SELL&type=MARKET&quantity={amount}")
+    };
+
+    signed_request(
+        Method::POST,
+        &binance_spot_api_url(),
+        "/api/v3/order",
+        query,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// withdraws `amount` usdc from the spot wallet to `address` on arbitrum
+pub async fn withdraw_usdc_to_arbitrum(amount: f64, address: &str) -> Result<()> {
+    signed_request(
+        Method::POST,
+        &binance_spot_api_url(),
+        "/sapi/v1/capital/withdraw/apply",
+        format!(
+            "coin=USDC&network=ARBITRUM&address={address}&amount={}",
+            fmt_transfer_amount(amount)
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// arbitrum address that credits usdc to the spot wallet
+pub async fn retrieve_usdc_deposit_address() -> Result<String> {
+    let res = signed_request(
+        Method::GET,
+        &binance_spot_api_url(),
+        "/sapi/v1/capital/deposit/address",
+        "coin=USDC&network=ARBITRUM".to_string(),
+    )
+    .await?;
+
+    let deposit_address: DepositAddressRes = res.json().await?;
+
+    Ok(deposit_address.address)
+}
+
+/// free balance of `asset` in the spot wallet
+pub async fn retrieve_spot_balance(asset: &str) -> Result<f64> {
+    let res = signed_request(
+        Method::GET,
+        &binance_spot_api_url(),
+        "/api/v3/account",
+        String::new(),
+    )
+    .await?;
+    let spot_account: SpotAccountRes = res.json().await?;
+
+    let free = spot_account
+        .balances
+        .iter()
+        .find(|b| b.asset == asset)
+        .map(|b| b.free)
+        .unwrap_or_default();
+
+    Ok(free)
+}
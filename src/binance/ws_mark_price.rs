@@ -0,0 +1,49 @@
+use crate::live_funding_rates::LiveFundingRates;
+use crate::live_orderbooks::RECONNECT_DELAY;
+use anyhow::{bail, Result};
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+#[derive(Debug, Deserialize)]
+pub struct MarkPriceUpdate {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p", deserialize_with = "deserialize_number_from_string")]
+    pub mark_price: f64,
+    #[serde(rename = "i", deserialize_with = "deserialize_number_from_string")]
+    pub index_price: f64,
+    #[serde(rename = "r", deserialize_with = "deserialize_number_from_string")]
+    pub funding_rate: f64, // per funding interval
+    #[serde(rename = "T")]
+    pub next_funding_time: i64, // unix ms
+}
+
+/// keeps `store` updated with the mark price and funding rate of every symbol, reconnecting forever
+pub async fn stream_binance_mark_prices(store: LiveFundingRates) {
+    loop {
+        if let Err(e) = run_mark_price_stream(&store).await {
+            log::warn!("Binance mark price stream dropped. {e}");
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn run_mark_price_stream(store: &LiveFundingRates) -> Result<()> {
+    let (mut ws, _) = connect_async("wss://fstream.binance.com/ws/!markPrice@arr@1s").await?;
+
+    while let Some(message) = ws.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => bail!("connection closed by binance"),
+            _ => continue,
+        };
+
+        let updates: Vec<MarkPriceUpdate> = serde_json::from_str(&text)?;
+        store.apply_binance_mark_prices(updates);
+    }
+
+    bail!("stream ended")
+}
@@ -0,0 +1,265 @@
+use crate::constants::binance_api_url;
+use crate::live_orderbooks::{BookUpdate, LiveOrderbooks, RECONNECT_DELAY, STREAM_READ_TIMEOUT};
+use crate::util::{Orderbook, Platform};
+use anyhow::{bail, Result};
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+#[derive(Debug, Deserialize)]
+pub struct DepthUpdate {
+    #[serde(rename = "U")]
+    pub first_update_id: u64,
+    #[serde(rename = "u")]
+    pub final_update_id: u64,
+    #[serde(rename = "pu")]
+    pub prev_final_update_id: u64,
+    #[serde(rename = "b")]
+    pub bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    pub asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthSnapshot {
+    pub last_update_id: u64,
+    pub bids: Vec<[String; 2]>,
+    pub asks: Vec<[String; 2]>,
+}
+
+/// keeps a local book in sync with the diff depth stream.
+/// https://binance-docs.github.io/apidocs/futures/en/#how-to-manage-a-local-order-book-correctly
+#[derive(Default)]
+pub struct BinanceBookSync {
+    book: Option<Orderbook>,
+    last_update_id: u64,
+    is_synced: bool,
+    buffer: Vec<DepthUpdate>,
+}
+
+impl BinanceBookSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// drops the book, updates are buffered again until the next snapshot
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn orderbook(&self) -> Option<&Orderbook> {
+        self.book.as_ref().filter(|_| self.is_synced)
+    }
+
+    pub fn apply_snapshot(&mut self, snapshot: DepthSnapshot) -> Result<BookUpdate> {
+        let mut book = Orderbook {
+            platform: Platform::Binance,
+            bids: vec![],
+            asks: vec![],
+        };
+        apply_levels(&mut book, &snapshot.bids, &snapshot.asks)?;
+
+        self.book = Some(book);
+        self.last_update_id = snapshot.last_update_id;
+        self.is_synced = false;
+
+        for update in std::mem::take(&mut self.buffer) {
+            if self.apply_update(update)? == BookUpdate::Gap {
+                return Ok(BookUpdate::Gap);
+            }
+        }
+
+        Ok(BookUpdate::Applied)
+    }
+
+    pub fn apply_update(&mut self, update: DepthUpdate) -> Result<BookUpdate> {
+        let Some(book) = self.book.as_mut() else {
+            self.buffer.push(update);
+            return Ok(BookUpdate::Buffered);
+        };
+
+        if !self.is_synced {
+            if update.final_update_id < self.last_update_id {
+                return Ok(BookUpdate::Dropped);
+            }
+            // the first update must straddle the snapshot
+            if update.first_update_id > self.last_update_id {
+                return Ok(BookUpdate::Gap);
+            }
+        } else if update.prev_final_update_id != self.last_update_id {
+            return Ok(BookUpdate::Gap);
+        }
+
+        apply_levels(book, &update.bids, &update.asks)?;
+        self.last_update_id = update.final_update_id;
+        self.is_synced = true;
+
+        Ok(BookUpdate::Applied)
+    }
+}
+
+fn apply_levels(book: &mut Orderbook, bids: &[[String; 2]], asks: &[[String; 2]]) -> Result<()> {
+    for [price, size] in bids {
+        book.update_level(true, price.parse()?, size.parse()?);
+    }
+    for [price, size] in asks {
+        book.update_level(false, price.parse()?, size.parse()?);
+    }
+
+    Ok(())
+}
+
+async fn retrieve_binance_depth_snapshot(symbol: &str) -> Result<DepthSnapshot> {
+    let client = Client::new();
+
+    let url = format!(
+        "{}/fapi/v1/depth?symbol={symbol}&limit=1000",
+        binance_api_url()
+    );
+
+    let response = client.get(&url).send().await?;
+    let snapshot: DepthSnapshot = response.json().await?;
+
+    Ok(snapshot)
+}
+
+/// keeps `store` updated with the binance book of the pair `symbol` (e.g. `ETHUSDC`), reconnecting forever
+pub async fn stream_binance_order_book(symbol: String, store: LiveOrderbooks) {
+    loop {
+        if let Err(e) = run_depth_stream(&symbol, &store).await {
+            log::warn!("Binance depth stream for {symbol} dropped. {e}");
+        }
+
+        store.remove(Platform::Binance, &symbol);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn run_depth_stream(symbol: &str, store: &LiveOrderbooks) -> Result<()> {
+    let url = format!(
+        "wss://fstream.binance.com/ws/{}@depth@100ms",
+        symbol.to_lowercase()
+    );
+    let (mut ws, _) = connect_async(url).await?;
+
+    let mut sync = BinanceBookSync::new();
+    let mut needs_snapshot = true;
+
+    loop {
+        let Ok(message) = tokio::time::timeout(STREAM_READ_TIMEOUT, ws.next()).await else {
+            bail!("no update for {}s", STREAM_READ_TIMEOUT.as_secs())
+        };
+        let Some(message) = message else {
+            bail!("stream ended")
+        };
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => bail!("connection closed by binance"),
+            _ => continue,
+        };
+
+        let update: DepthUpdate = serde_json::from_str(&text)?;
+        if sync.apply_update(update)? == BookUpdate::Gap {
+            log::warn!("Binance depth gap for {symbol}, resyncing");
+            sync.reset();
+            needs_snapshot = true;
+            continue;
+        }
+
+        // the first buffered update guarantees the snapshot is not older than the stream
+        if needs_snapshot {
+            let snapshot = retrieve_binance_depth_snapshot(symbol).await?;
+            needs_snapshot = sync.apply_snapshot(snapshot)? == BookUpdate::Gap;
+            if needs_snapshot {
+                sync.reset();
+            }
+        }
+
+        if let Some(book) = sync.orderbook() {
+            store.insert(symbol, book.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(text: &str) -> DepthUpdate {
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn snapshot() -> DepthSnapshot {
+        serde_json::from_str(
+            r#"{"lastUpdateId":100,"E":1,"T":1,
+                "bids":[["10.0","1.0"],["9.9","2.0"]],
+                "asks":[["10.1","1.0"],["10.2","2.0"]]}"#,
+        )
+        .unwrap()
+    }
+
+    const STALE: &str = r#"{"e":"depthUpdate","E":1,"T":1,"s":"ETHUSDT","U":90,"u":95,"pu":89,"b":[["10.0","7.0"]],"a":[]}"#;
+    const STRADDLE: &str = r#"{"e":"depthUpdate","E":2,"T":2,"s":"ETHUSDT","U":98,"u":103,"pu":95,"b":[["10.0","0"]],"a":[["10.05","3.0"]]}"#;
+    const NEXT: &str = r#"{"e":"depthUpdate","E":3,"T":3,"s":"ETHUSDT","U":104,"u":110,"pu":103,"b":[["9.95","4.0"]],"a":[]}"#;
+    const AFTER_GAP: &str = r#"{"e":"depthUpdate","E":5,"T":5,"s":"ETHUSDT","U":121,"u":130,"pu":120,"b":[],"a":[["10.1","0"]]}"#;
+
+    #[test]
+    fn test_sync_buffers_until_snapshot() -> Result<()> {
+        let mut sync = BinanceBookSync::new();
+
+        assert_eq!(sync.apply_update(update(STALE))?, BookUpdate::Buffered);
+        assert_eq!(sync.apply_update(update(STRADDLE))?, BookUpdate::Buffered);
+        assert!(sync.orderbook().is_none());
+
+        // stale update is dropped and the straddling one applied
+        assert_eq!(sync.apply_snapshot(snapshot())?, BookUpdate::Applied);
+        let book = sync.orderbook().unwrap();
+        assert_eq!(book.bids[0].price, 9.9);
+        assert_eq!(book.asks[0].price, 10.05);
+
+        assert_eq!(sync.apply_update(update(NEXT))?, BookUpdate::Applied);
+        let book = sync.orderbook().unwrap();
+        assert_eq!(book.bids[0].price, 9.95);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_detects_gap_and_recovers_after_reconnect() -> Result<()> {
+        let mut sync = BinanceBookSync::new();
+        sync.apply_update(update(STRADDLE))?;
+        sync.apply_snapshot(snapshot())?;
+
+        // update 111..120 was never received
+        assert_eq!(sync.apply_update(update(NEXT))?, BookUpdate::Applied);
+        assert_eq!(sync.apply_update(update(AFTER_GAP))?, BookUpdate::Gap);
+
+        // reconnect: buffer again and sync from a fresh snapshot
+        sync.reset();
+        assert!(sync.orderbook().is_none());
+        assert_eq!(sync.apply_update(update(AFTER_GAP))?, BookUpdate::Buffered);
+
+        let fresh: DepthSnapshot = serde_json::from_str(
+            r#"{"lastUpdateId":125,"bids":[["9.8","1.0"]],"asks":[["10.1","1.0"],["10.3","1.0"]]}"#,
+        )?;
+        assert_eq!(sync.apply_snapshot(fresh)?, BookUpdate::Applied);
+        let book = sync.orderbook().unwrap();
+        assert_eq!(book.asks[0].price, 10.3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_rejects_snapshot_older_than_stream() -> Result<()> {
+        let mut sync = BinanceBookSync::new();
+
+        // buffered update starts after the snapshot ends
+        sync.apply_update(update(AFTER_GAP))?;
+        assert_eq!(sync.apply_snapshot(snapshot())?, BookUpdate::Gap);
+        assert!(sync.orderbook().is_none());
+
+        Ok(())
+    }
+}
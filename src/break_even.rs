@@ -0,0 +1,237 @@
+use crate::binance::{
+    funding_rates::retrieve_binance_hourly_funding_rates, retrieve_binance_funding_info,
+    retrieve_binance_past_daily_fh,
+};
+use crate::constants::hyperliquid_base_url;
+use crate::hyperliquid::{
+    funding_rates::retrieve_hl_hourly_funding_rates, retrieve_hl_past_daily_fh,
+};
+use crate::symbols::load_symbols;
+use crate::util::{Platform, QuoteAsset};
+use anyhow::{Context, Result};
+use hyperliquid_rust_sdk::InfoClient;
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+use tokio::try_join;
+
+/// days of funding history used for the average spread
+pub const AVG_FUNDING_PAST_DAYS: u16 = 7;
+/// holding periods (days) for the projected net apr
+pub const HOLDING_PERIODS: [f64; 3] = [1.0, 7.0, 30.0];
+
+/// funding spread earned by a hedge that longs on `long`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FundingSpread {
+    pub current: f64,                // hourly decimal form
+    pub average: f64,                // hourly decimal form
+    pub funding_interval_hours: f64, // binance leg
+}
+
+/// positive when the short leg receives more than the long leg pays
+pub fn calculate_directional_spread(long_rate: f64, short_rate: f64) -> f64 {
+    short_rate - long_rate
+}
+
+/// hours needed for `hourly_funding` to pay back `round_trip_cost`
+pub fn calculate_break_even_hours(round_trip_cost: f64, hourly_funding: f64) -> Option<f64> {
+    if hourly_funding <= 0.0 {
+        return None;
+    }
+
+    Some(round_trip_cost / hourly_funding)
+}
+
+/// annualized return after paying `round_trip_cost` once over `holding_days`
+pub fn calculate_net_apr(round_trip_cost: f64, hourly_funding: f64, holding_days: f64) -> f64 {
+    let net_return = hourly_funding * 24.0 * holding_days - round_trip_cost;
+    net_return * 365.0 / holding_days
+}
+
+/// hourly average of both venues' daily funding sums, newest day first.
+/// only the days both venues have are averaged, a recent listing doesn't skew the other venue
+pub fn average_hourly_funding(b_daily: &[f64], hl_daily: &[f64]) -> (f64, f64) {
+    let days = b_daily.len().min(hl_daily.len());
+    if days == 0 {
+        return (0.0, 0.0);
+    }
+    let average = |daily: &[f64]| daily.iter().take(days).sum::<f64>() / days as f64 / 24.0;
+
+    (average(b_daily), average(hl_daily))
+}
+
+/// funding of a hedge on `token` longing on `long`, binance on its `quote` perpetual
+pub async fn retrieve_funding_spread(
+    token: &str,
+    quote: QuoteAsset,
+    long: Platform,
+) -> Result<FundingSpread> {
+    let (b_token, hl_token) = load_symbols().await?.venue_symbols(token);
+    let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
+
+    let (b_rates, hl_rates, funding_info, b_daily, hl_daily) = try_join!(
+        retrieve_binance_hourly_funding_rates(),
+        retrieve_hl_hourly_funding_rates(&info_client),
+        retrieve_binance_funding_info(),
+        retrieve_binance_past_daily_fh(b_token.clone(), quote, AVG_FUNDING_PAST_DAYS),
+        retrieve_hl_past_daily_fh(hl_token.clone(), AVG_FUNDING_PAST_DAYS)
+    )?;
+
+    let b_rate = b_rates
+        .iter()
+        .find(|r| r.name == b_token && r.quote == quote)
+        .map(|r| r.hourly_funding_rate)
+        .with_context(|| format!("no binance funding rate for {}", quote.pair(&b_token)))?;
+    let hl_rate = hl_rates
+        .iter()
+        .find(|r| r.name == hl_token)
+        .map(|r| r.hourly_funding_rate)
+        .with_context(|| format!("no hyperliquid funding rate for {hl_token}"))?;
+    let funding_interval_hours = funding_info
+        .iter()
+        .find(|info| info.symbol == quote.pair(&b_token))
+        .map(|info| info.funding_interval_hours)
+        .unwrap_or(8.0);
+
+    let (b_avg, hl_avg) = average_hourly_funding(&b_daily, &hl_daily);
+
+    let (current, average) = match long {
+        Platform::Binance => (
+            calculate_directional_spread(b_rate, hl_rate),
+            calculate_directional_spread(b_avg, hl_avg),
+        ),
+        Platform::Hyperliquid => (
+            calculate_directional_spread(hl_rate, b_rate),
+            calculate_directional_spread(hl_avg, b_avg),
+        ),
+    };
+
+    Ok(FundingSpread {
+        current,
+        average,
+        funding_interval_hours,
+    })
+}
+
+/// `round_trip_cost` is decimal form relative to the notional of one leg
+/// what the break-even table shows, for json output
+#[derive(Debug, Serialize)]
+pub struct BreakEven {
+    pub hours_at_current: Option<f64>, // none when the spread doesn't pay
+    pub hours_at_average: Option<f64>,
+    pub net_apr: Vec<HoldingPeriodApr>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldingPeriodApr {
+    pub holding_days: f64,
+    pub at_current: f64, // decimal form
+    pub at_average: f64, // decimal form
+}
+
+pub fn calculate_break_even(round_trip_cost: f64, spread: FundingSpread) -> BreakEven {
+    BreakEven {
+        hours_at_current: calculate_break_even_hours(round_trip_cost, spread.current),
+        hours_at_average: calculate_break_even_hours(round_trip_cost, spread.average),
+        net_apr: HOLDING_PERIODS
+            .iter()
+            .map(|&days| HoldingPeriodApr {
+                holding_days: days,
+                at_current: calculate_net_apr(round_trip_cost, spread.current, days),
+                at_average: calculate_net_apr(round_trip_cost, spread.average, days),
+            })
+            .collect(),
+    }
+}
+
+pub fn build_break_even_table(round_trip_cost: f64, spread: FundingSpread) -> String {
+    let mut t = Table::new();
+
+    t.add_row(Row::new(vec![
+        Cell::new("Break-even"),
+        Cell::new("Current"),
+        Cell::new(&format!("Avg ({AVG_FUNDING_PAST_DAYS}d)")),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Funding spread (apr)"),
+        Cell::new(&format!("{:.2}%", spread.current * 24.0 * 365.0 * 100.0)),
+        Cell::new(&format!("{:.2}%", spread.average * 24.0 * 365.0 * 100.0)),
+    ]));
+
+    let fmt_hours = |hourly: f64| match calculate_break_even_hours(round_trip_cost, hourly) {
+        Some(h) => format!(
+            "{h:.1}h ({:.1} intervals)",
+            h / spread.funding_interval_hours
+        ),
+        None => "never".to_string(),
+    };
+    t.add_row(Row::new(vec![
+        Cell::new("Hours to recoup"),
+        Cell::new(&fmt_hours(spread.current)),
+        Cell::new(&fmt_hours(spread.average)),
+    ]));
+
+    for days in HOLDING_PERIODS {
+        t.add_row(Row::new(vec![
+            Cell::new(&format!("Net APR ({days}d)")),
+            Cell::new(&format!(
+                "{:.2}%",
+                calculate_net_apr(round_trip_cost, spread.current, days) * 100.0
+            )),
+            Cell::new(&format!(
+                "{:.2}%",
+                calculate_net_apr(round_trip_cost, spread.average, days) * 100.0
+            )),
+        ]));
+    }
+
+    t.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_directional_spread() {
+        // long pays 0.01%, short receives 0.03%
+        assert_relative_eq!(calculate_directional_spread(0.0001, 0.0003), 0.0002);
+        // wrong way around
+        assert_relative_eq!(calculate_directional_spread(0.0003, 0.0001), -0.0002);
+    }
+
+    #[test]
+    fn test_break_even_hours() {
+        // 20 bps round trip at 1 bp per hour
+        let hours = calculate_break_even_hours(0.002, 0.0001).unwrap();
+        assert_relative_eq!(hours, 20.0, epsilon = 1e-9);
+
+        assert!(calculate_break_even_hours(0.002, 0.0).is_none());
+        assert!(calculate_break_even_hours(0.002, -0.0001).is_none());
+    }
+
+    #[test]
+    fn test_average_over_common_days() {
+        // binance listed two days ago, hyperliquid has a week
+        let b_daily = [0.0024, 0.0048];
+        let hl_daily = [0.0024, 0.0024, 0.1, 0.1, 0.1, 0.1, 0.1];
+
+        let (b_avg, hl_avg) = average_hourly_funding(&b_daily, &hl_daily);
+        assert_relative_eq!(b_avg, 0.00015, epsilon = 1e-12);
+        assert_relative_eq!(hl_avg, 0.0001, epsilon = 1e-12);
+
+        assert_eq!(average_hourly_funding(&[], &hl_daily), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_net_apr() {
+        // 1 bp per hour for a day pays 24 bps, minus 20 bps of costs
+        let apr = calculate_net_apr(0.002, 0.0001, 1.0);
+        assert_relative_eq!(apr, 0.0004 * 365.0, epsilon = 1e-9);
+
+        // costs get amortized over longer holding periods
+        let apr_30 = calculate_net_apr(0.002, 0.0001, 30.0);
+        assert!(apr_30 > apr);
+        assert_relative_eq!(apr_30, (0.072 - 0.002) * 365.0 / 30.0, epsilon = 1e-9);
+    }
+}
@@ -1,66 +1,239 @@
-use crate::{constants::MAX_DAYS_QUERY_FUNDING_HISTORY, util::Platform};
-use anyhow::{bail, Ok, Result};
-use clap::{value_parser, Parser, Subcommand};
+use crate::output::OutputFormat;
+use anyhow::{Ok, Result};
+use clap::{value_parser, ArgGroup, Parser, Subcommand};
+use funding_rate_arbitrage::{
+    compare_funding_rates::{SortBy, DEFAULT_HOLDING_DAYS, DEFAULT_SCREENER_NOTIONAL},
+    constants::MAX_DAYS_QUERY_FUNDING_HISTORY,
+    util::{Platform, QuoteAsset},
+};
+use std::{net::SocketAddr, path::PathBuf};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// how to print the results
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+    /// toml file with the profiles, funding-arb.toml is read when present
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// profile of the config file to run with
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+    /// named account of the profile to query and trade with
+    #[arg(long, global = true)]
+    pub account: Option<String>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// retrieves the current balances and open positions
-    Balance,
+    Balance {
+        /// sums the balances of every account of the profile
+        #[arg(long)]
+        all_accounts: bool,
+    },
     /// retrieves the current best funding rates
-    FundingRates,
+    FundingRates {
+        /// keep the table updated from the websocket streams
+        #[arg(short, long)]
+        watch: bool,
+        /// how many tokens to show
+        #[arg(long, default_value_t = 6)]
+        top: usize,
+        /// minimum funding rate difference (apr, percent)
+        #[arg(long)]
+        min_apr: Option<f64>,
+        /// minimum open interest on each venue (USD)
+        #[arg(long)]
+        min_oi: Option<f64>,
+        /// minimum depth on every side of both orderbooks (USD)
+        #[arg(long)]
+        min_depth: Option<f64>,
+        /// tokens to leave out, comma separated
+        #[arg(long, value_delimiter = ',', value_parser = |s: &str| Ok(s.to_uppercase()))]
+        exclude: Vec<String>,
+        /// only these tokens, comma separated
+        #[arg(long, value_delimiter = ',', value_parser = |s: &str| Ok(s.to_uppercase()))]
+        only: Vec<String>,
+        /// diff, oi (of the thinner venue), cost or net-apr
+        #[arg(long, value_parser = validate_sort_by, default_value = "diff")]
+        sort_by: SortBy,
+        /// round trip quoted for the cost column (USD across both legs)
+        #[arg(long, default_value_t = DEFAULT_SCREENER_NOTIONAL)]
+        notional: f64,
+        /// days over which the net apr pays back the round trip
+        #[arg(long, default_value_t = DEFAULT_HOLDING_DAYS)]
+        holding_days: f64,
+    },
+    /// forecasts the funding of the best current spreads over the next day and week
+    Forecast {
+        /// how many of the best forecasts to show, picked among three times as many current spreads
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// live view of funding spreads, open hedges, margin and depth
+    Dashboard,
     /// retrieves the funding history of a token
     FundingHistory {
         /// name of the token
         #[arg(value_parser = |s: &str| Ok(s.to_uppercase()))]
         token: String,
         /// how many days in the past do you want to inquire? (max 7 days)
-        #[arg(short, long, default_value = "7" , value_parser = value_parser!(u8).range(1..=MAX_DAYS_QUERY_FUNDING_HISTORY))]
-        past_days: u8,
+        #[arg(short, long, value_parser = value_parser!(u8).range(1..=MAX_DAYS_QUERY_FUNDING_HISTORY))]
+        past_days: Option<u8>,
+        /// binance perpetual, usdt or usdc (the profile's quote otherwise)
+        #[arg(long, value_parser = validate_quote)]
+        quote: Option<QuoteAsset>,
     },
     /// find out funding rate arbitrage cost to enter/exit into a token
+    #[command(group(ArgGroup::new("amount").required(true).args(["usd", "size", "exit_position"])))]
     Quote {
         /// name of the token
         #[arg(value_parser = |s: &str| Ok(s.to_uppercase()))]
         token: String,
-        /// amount to quote (USD)
-        amount: f64,
+        /// amount to quote across both legs (USD)
+        #[arg(long)]
+        usd: Option<f64>,
+        /// amount of tokens to quote across both legs
+        #[arg(long)]
+        size: Option<f64>,
+        /// quote closing the position currently open on the account
+        #[arg(long)]
+        exit_position: bool,
         /// name of the platform of where to long (binance or hyperliquid)
-        #[arg(long, value_parser = validate_platform)]
-        long: Platform,
+        #[arg(long, value_parser = validate_platform, required_unless_present = "exit_position", conflicts_with = "exit_position")]
+        long: Option<Platform>,
+        /// binance perpetual, usdt or usdc (the profile's quote otherwise, the position's when exiting)
+        #[arg(long, value_parser = validate_quote, conflicts_with = "exit_position")]
+        quote: Option<QuoteAsset>,
     },
     /// bid_ask depth of the orderbook for a token in both platforms
     OrderbookDepth {
         /// name of the token
         #[arg(value_parser = |s: &str| Ok(s.to_uppercase()))]
         token: String,
+        /// keep streaming the depth over websockets
+        #[arg(short, long)]
+        watch: bool,
+        /// binance perpetual, usdt or usdc (the profile's quote otherwise)
+        #[arg(long, value_parser = validate_quote)]
+        quote: Option<QuoteAsset>,
     },
     /// Executes a funding rate operation
+    #[command(group(ArgGroup::new("amount").required(true).args(["usd", "size"])))]
     Execute {
         /// Name of the token
         #[arg(value_parser = |s: &str| Ok(s.to_uppercase()))]
         token: String,
-        /// Amount of tokens
-        size: f64,
+        /// Amount across both legs (USD)
+        #[arg(long)]
+        usd: Option<f64>,
+        /// Amount of tokens across both legs
+        #[arg(long)]
+        size: Option<f64>,
         /// Name of the platform of where to long (Binance or Hyperliquid)
         #[arg(long, value_parser = validate_platform)]
         long: Platform,
-        // Maximum slippage based on the quote (expressed in basis points)
-        #[arg(short, long, default_value = "5")]
-        max_slippage: f64,
+        // Maximum slippage based on the quote (expressed in basis points), 5 unless the profile sets it
+        #[arg(short, long)]
+        max_slippage: Option<f64>,
+        /// leverage set on both platforms before opening, the profile's or current one is kept otherwise
+        #[arg(long, value_parser = value_parser!(u32).range(1..))]
+        leverage: Option<u32>,
+        /// isolated margin, the profile's margin mode otherwise
+        #[arg(long, conflicts_with = "cross")]
+        isolated: bool,
+        /// cross margin, even if the profile sets isolated
+        #[arg(long)]
+        cross: bool,
+        /// binance perpetual, usdt or usdc (the profile's quote otherwise)
+        #[arg(long, value_parser = validate_quote)]
+        quote: Option<QuoteAsset>,
+    },
+    /// trades the size difference between both legs of a token on the cheaper venue
+    Hedge {
+        /// Name of the token
+        #[arg(value_parser = |s: &str| Ok(s.to_uppercase()))]
+        token: String,
+        /// only quotes the adjustment
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// watches funding spreads, open hedges and leverage, posting alerts to webhooks
+    Alerts {
+        /// url to post alerts to (slack, discord, telegram sendMessage or any json endpoint), replaces the profile's
+        #[arg(long = "webhook")]
+        webhooks: Vec<String>,
+        /// alert when a token's funding rate difference exceeds this apr (%)
+        #[arg(long)]
+        min_spread_apr: Option<f64>,
+        /// alert when leverage on either platform exceeds this
+        #[arg(long)]
+        max_leverage: Option<f64>,
+        /// alert when a hedged leg is within this move (%) of its liquidation price
+        #[arg(long)]
+        min_liquidation_distance: Option<f64>,
+        /// seconds between checks (default 60)
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+    /// plans margin transfers so each venue holds what its positions need, optionally executing them
+    Rebalance {
+        /// carries out the transfers after confirmation
+        #[arg(long)]
+        execute: bool,
+        /// skips the confirmation prompt
+        #[arg(long, requires = "execute")]
+        yes: bool,
+        /// smallest imbalance worth transferring (usd, default 50)
+        #[arg(long)]
+        min_transfer: Option<f64>,
+    },
+    /// creates a hyperliquid agent wallet, approves it with the master key and stores it encrypted
+    ApproveAgent {
+        /// where to write the agent keystore, then set as credentials.agent_keystore
+        keystore: PathBuf,
+    },
+    /// serves funding rates, quotes, balances and execution as a local http/json api, plus /metrics
+    Serve {
+        /// address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: SocketAddr,
+    },
+    /// inspects the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// validates the selected profile: credentials of enabled venues, urls and parameter ranges
+    Check,
+    /// encrypts the binance keys and mnemonic currently configured into a secrets file
+    EncryptSecrets {
+        /// where to write the encrypted file
+        path: PathBuf,
+    },
+    /// encrypts the hyperliquid signer into a json keystore
+    EncryptWallet {
+        /// where to write the keystore
+        path: PathBuf,
     },
 }
 
 fn validate_platform(s: &str) -> Result<Platform> {
-    match s.to_lowercase().as_str() {
-        "binance" | "b" => Ok(Platform::Binance),
-        "hyperliquid" | "hl" | "h" => Ok(Platform::Hyperliquid),
-        _ => bail!("Invalid platform. Use 'binance' (or 'b') for Binance, or 'hyperliquid' (or 'hl', 'h') for Hyperliquid")
-    }
+    s.parse()
+}
+
+fn validate_quote(s: &str) -> Result<QuoteAsset> {
+    s.parse()
+}
+
+fn validate_sort_by(s: &str) -> Result<SortBy> {
+    s.parse()
 }
@@ -1,45 +1,101 @@
-use crate::binance::funding_rates::retrieve_binance_hourly_funding_rates;
-use crate::hyperliquid::funding_rates::retrieve_hl_hourly_funding_rates;
-use crate::util::calculate_effective_rate;
+use crate::binance::funding_rates::{retrieve_binance_hourly_funding_rates, BinanceFundingRate};
+use crate::constants::hyperliquid_base_url;
+use crate::hyperliquid::funding_rates::{retrieve_hl_hourly_funding_rates, HyperliquidToken};
+use crate::symbols::SymbolRegistry;
+use crate::util::{calculate_effective_rate, Platform, QuoteAsset};
 use anyhow::Result;
 use hyperliquid_rust_sdk::InfoClient;
+use serde::Serialize;
 use tokio::try_join;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct JointFundingRate {
-    pub name: String,
-    pub binance_funding_rate: f64, // hourly fr decimal form
-    pub binance_mark_price: f64,
+    pub name: String,                   // asset, e.g. PEPE for 1000PEPE and kPEPE
+    pub binance_quote: QuoteAsset,      // binance contract with the better difference
+    pub binance_funding_rate: f64,      // hourly fr decimal form
+    pub binance_mark_price: f64,        // per unit of the asset
     pub hyperliquid_funding_rate: f64,  // hourly fr decimal form
     pub hyperliquid_open_interest: f64, // expressed in USD
+    pub hyperliquid_mark_price: f64,    // per unit of the asset
     pub funding_rate_difference: f64,
+    pub binance_next_funding_rate: f64, // predicted rate of the next payment, per funding interval
+    pub binance_next_funding_time: i64, // unix ms
+    pub binance_funding_interval: f64,  // hours between payments
+    pub binance_interest_rate: f64,     // per funding interval, decimal form
+    pub binance_premium: f64,           // mark price over the index, decimal form
+    pub hyperliquid_next_funding_rate: f64, // predicted rate of the next payment, hourly
+    pub hyperliquid_next_funding_time: i64, // unix ms
+    pub hyperliquid_premium: f64,       // impact price over the oracle, decimal form
 }
 
+/// current funding rates of tokens listed on both platforms, best difference first
 pub async fn compare_funding_rates() -> Result<Vec<JointFundingRate>> {
-    let info_client = InfoClient::new(None, None).await?;
+    let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
 
     let (binance_tokens, hyperliquid_tokens) = try_join!(
         retrieve_binance_hourly_funding_rates(),
         retrieve_hl_hourly_funding_rates(&info_client)
     )?;
 
+    Ok(join_funding_rates(&binance_tokens, &hyperliquid_tokens))
+}
+
+/// assets listed on both platforms, best funding rate difference first.
+/// venue symbols are matched through their asset so `1000SHIB` pairs with `kSHIB`,
+/// an asset with both USDT and USDC perpetuals on binance keeps the one with the larger difference
+pub fn join_funding_rates(
+    binance_tokens: &[BinanceFundingRate],
+    hyperliquid_tokens: &[HyperliquidToken],
+) -> Vec<JointFundingRate> {
+    let registry = SymbolRegistry::new(
+        binance_tokens.iter().map(|t| t.name.as_str()),
+        hyperliquid_tokens.iter().map(|t| t.name.as_str()),
+    );
+
     let mut token_vec: Vec<JointFundingRate> = vec![];
 
-    for b_token in binance_tokens {
-        if let Some(hl_token) = hyperliquid_tokens.iter().find(|t| t.name == b_token.name) {
-            let token_comparison = JointFundingRate {
-                name: b_token.name.clone(),
-                hyperliquid_open_interest: hl_token.open_interest,
-                binance_funding_rate: b_token.hourly_funding_rate,
-                binance_mark_price: b_token.mark_price,
-                hyperliquid_funding_rate: hl_token.hourly_funding_rate,
-                funding_rate_difference: calculate_effective_rate(
-                    b_token.hourly_funding_rate,
-                    hl_token.hourly_funding_rate,
-                ),
-            };
-            token_vec.push(token_comparison);
+    for asset in registry.joint_assets() {
+        let b_symbol = asset.symbol(Platform::Binance);
+        let hl_symbol = asset.symbol(Platform::Hyperliquid);
+        let Some(hl_token) = hyperliquid_tokens.iter().find(|t| t.name == hl_symbol) else {
+            continue;
         };
+        let Some(b_token) = binance_tokens
+            .iter()
+            .filter(|t| t.name == b_symbol)
+            .max_by(|a, b| {
+                calculate_effective_rate(a.hourly_funding_rate, hl_token.hourly_funding_rate)
+                    .total_cmp(&calculate_effective_rate(
+                        b.hourly_funding_rate,
+                        hl_token.hourly_funding_rate,
+                    ))
+            })
+        else {
+            continue;
+        };
+
+        token_vec.push(JointFundingRate {
+            name: asset.name.clone(),
+            binance_quote: b_token.quote,
+            hyperliquid_open_interest: hl_token.open_interest,
+            binance_funding_rate: b_token.hourly_funding_rate,
+            binance_mark_price: b_token.mark_price / asset.scale(Platform::Binance),
+            hyperliquid_funding_rate: hl_token.hourly_funding_rate,
+            hyperliquid_mark_price: hl_token.mark_price / asset.scale(Platform::Hyperliquid),
+            funding_rate_difference: calculate_effective_rate(
+                b_token.hourly_funding_rate,
+                hl_token.hourly_funding_rate,
+            ),
+            binance_next_funding_rate: b_token.next_funding_rate,
+            binance_next_funding_time: b_token.next_funding_time,
+            binance_funding_interval: b_token.funding_interval,
+            binance_interest_rate: b_token.interest_rate,
+            binance_premium: b_token.premium,
+            // hyperliquid's current rate is the one paid at the top of the hour
+            hyperliquid_next_funding_rate: hl_token.hourly_funding_rate,
+            hyperliquid_next_funding_time: hl_token.next_funding_time,
+            hyperliquid_premium: hl_token.premium,
+        });
     }
 
     token_vec.sort_by(|a, b| {
@@ -48,13 +104,107 @@ pub async fn compare_funding_rates() -> Result<Vec<JointFundingRate>> {
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    Ok(token_vec)
+    token_vec
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_join_matches_contract_prefixes() {
+        let binance = |name: &str, mark_price: f64| BinanceFundingRate {
+            name: name.to_string(),
+            quote: QuoteAsset::Usdt,
+            hourly_funding_rate: 0.0001,
+            max_leverage: 20.0,
+            mark_price,
+            next_funding_rate: 0.0008,
+            next_funding_time: 0,
+            funding_interval: 8.0,
+            interest_rate: 0.0001,
+            premium: 0.0,
+        };
+        let hyperliquid = |name: &str, mark_price: f64| HyperliquidToken {
+            name: name.to_string(),
+            hourly_funding_rate: -0.0001,
+            open_interest: 1_000_000.0,
+            mark_price,
+            max_leverage: 10.0,
+            premium: 0.0,
+            next_funding_time: 0,
+        };
+
+        let joint = join_funding_rates(
+            &[
+                binance("1000SHIB", 0.012),
+                binance("DOGS", 0.0005),
+                binance("ETH", 2500.0),
+            ],
+            &[
+                hyperliquid("kSHIB", 0.012),
+                hyperliquid("kDOGS", 0.5),
+                hyperliquid("BTC", 60_000.0),
+            ],
+        );
+
+        let names: Vec<_> = joint.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["DOGS", "SHIB"]);
+
+        // kDOGS holds a thousand binance contracts
+        let dogs = &joint[0];
+        assert_eq!(dogs.binance_mark_price, 0.0005);
+        assert_eq!(dogs.hyperliquid_mark_price, 0.0005);
+        assert_eq!(joint[1].hyperliquid_mark_price, 0.012);
+    }
+
+    #[test]
+    fn test_join_picks_better_quote() {
+        let binance = |quote, hourly_funding_rate| BinanceFundingRate {
+            name: "ETH".to_string(),
+            quote,
+            hourly_funding_rate,
+            max_leverage: 20.0,
+            mark_price: 2500.0,
+            next_funding_rate: hourly_funding_rate * 8.0,
+            next_funding_time: 1_704_110_400_000,
+            funding_interval: 8.0,
+            interest_rate: 0.0001,
+            premium: 0.0002,
+        };
+        let hl = HyperliquidToken {
+            name: "ETH".to_string(),
+            hourly_funding_rate: 0.00001,
+            open_interest: 1_000_000.0,
+            mark_price: 2500.0,
+            max_leverage: 25.0,
+            premium: -0.0001,
+            next_funding_time: 1_704_106_800_000,
+        };
+
+        let joint = join_funding_rates(
+            &[
+                binance(QuoteAsset::Usdt, 0.00002),
+                binance(QuoteAsset::Usdc, 0.00008),
+            ],
+            &[hl],
+        );
+
+        assert_eq!(joint.len(), 1);
+        assert_eq!(joint[0].binance_quote, QuoteAsset::Usdc);
+        assert_eq!(joint[0].binance_funding_rate, 0.00008);
+
+        // next payment and basis of the chosen contract on each venue
+        assert_eq!(joint[0].binance_next_funding_rate, 0.00064);
+        assert_eq!(joint[0].binance_next_funding_time, 1_704_110_400_000);
+        assert_eq!(joint[0].binance_funding_interval, 8.0);
+        assert_eq!(joint[0].binance_interest_rate, 0.0001);
+        assert_eq!(joint[0].binance_premium, 0.0002);
+        assert_eq!(joint[0].hyperliquid_next_funding_rate, 0.00001);
+        assert_eq!(joint[0].hyperliquid_next_funding_time, 1_704_106_800_000);
+        assert_eq!(joint[0].hyperliquid_premium, -0.0001);
+    }
+
     #[tokio::test]
     async fn test_compare_funding_rates() {
         let fr = compare_funding_rates().await.unwrap();
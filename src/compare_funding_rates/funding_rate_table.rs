@@ -1,58 +1,157 @@
-use super::compare_funding_rate::compare_funding_rates;
-use crate::binance::retrieve_token_open_interest;
+use super::compare_funding_rate::{compare_funding_rates, JointFundingRate};
+use super::screener::{screen_funding_rates, ScreenedFundingRate, Screener, ScreenerCache};
 use anyhow::Result;
-use futures::future::try_join_all;
 use numfmt::Formatter;
 use prettytable::{Cell, Row, Table};
 
-pub async fn build_funding_rate_table() -> Result<String> {
+pub async fn build_funding_rate_table(screener: &Screener) -> Result<String> {
     let fr = compare_funding_rates().await?;
-    let top_fr = fr.into_iter().take(6).collect::<Vec<_>>();
-
-    let all_oi = top_fr
-        .iter()
-        .map(|jfr| retrieve_token_open_interest(jfr.name.clone()))
-        .collect::<Vec<_>>();
-    let all_oi = try_join_all(all_oi).await?;
-    let all_oi_usd: Vec<_> = all_oi
-        .iter()
-        .enumerate()
-        .map(|(idx, oi)| Ok(oi.open_interest.parse::<f64>()? * top_fr[idx].binance_mark_price))
-        .collect::<Result<Vec<_>>>()?;
+    build_funding_rate_table_from(fr, screener, &ScreenerCache::default()).await
+}
+
+/// `cache` carries open interest and orderbooks over from the previous refresh
+pub async fn build_funding_rate_table_from(
+    fr: Vec<JointFundingRate>,
+    screener: &Screener,
+    cache: &ScreenerCache,
+) -> Result<String> {
+    let rows = screen_funding_rates(fr, screener, cache).await?;
+    format_funding_rate_table(&rows, screener)
+}
 
+pub fn format_funding_rate_table(
+    rows: &[ScreenedFundingRate],
+    screener: &Screener,
+) -> Result<String> {
     let mut table = Table::new();
     let mut f = Formatter::new()
         .separator(',')?
         .prefix("$")?
         .precision(numfmt::Precision::Decimals(0));
+    // make them yearly and round two decimals
+    let apr = |hourly: f64| format!("{:.2}%", hourly * 24.0 * 365.0 * 100.0);
+    let pct = |decimal: f64| format!("{:.4}%", decimal * 100.0);
 
     table.add_row(Row::new(vec![Cell::new("FR APY")]));
     table.add_row(Row::new(vec![
         Cell::new("Coin"),
+        Cell::new("Binance Quote"),
+        Cell::new("Long"),
+        Cell::new("Short"),
         Cell::new("Binance Fr"),
+        Cell::new("Binance Next"),
+        Cell::new("Binance Premium"),
+        Cell::new("Binance Interest"),
         Cell::new("Binance OI"),
+        Cell::new("Binance Mark"),
         Cell::new("Hl Fr"),
+        Cell::new("Hl Next"),
+        Cell::new("Hl Premium"),
         Cell::new("Hl OI"),
+        Cell::new("Hl Mark"),
+        Cell::new("Basis"),
         Cell::new("Fr Diff"),
+        Cell::new(&format!("Cost ({})", f.fmt2(screener.notional))),
+        Cell::new(&format!("Net APR ({}d)", screener.holding_days)),
     ]));
 
-    for (i, jfr) in top_fr.into_iter().enumerate() {
-        // make them yearly and round two decimals
-        let b_fr = jfr.binance_funding_rate * 24.0 * 365.0 * 100.0;
-        let b_fr = format!("{:.2}%", b_fr);
-        let hl_fr = jfr.hyperliquid_funding_rate * 24.0 * 365.0 * 100.0;
-        let hl_fr = format!("{:.2}%", hl_fr);
-        let fr_diff = jfr.funding_rate_difference * 24.0 * 365.0 * 100.0;
-        let fr_diff = format!("{:.2}%", fr_diff);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    for row in rows {
         table.add_row(Row::new(vec![
-            Cell::new(jfr.name.as_str()),
-            Cell::new(b_fr.as_str()),
-            Cell::new(f.fmt2(all_oi_usd[i])),
-            Cell::new(hl_fr.as_str()),
-            Cell::new(f.fmt2(jfr.hyperliquid_open_interest)),
-            Cell::new(fr_diff.as_str()),
+            Cell::new(&row.name),
+            Cell::new(&row.binance_quote.to_string()),
+            Cell::new(&format!("{:?}", row.long)),
+            Cell::new(&format!("{:?}", row.short)),
+            Cell::new(&apr(row.binance_funding_rate)),
+            Cell::new(&fmt_next_funding(
+                row.binance_next_funding_rate,
+                row.binance_funding_interval,
+                row.binance_next_funding_time,
+                now,
+            )),
+            Cell::new(&pct(row.binance_premium)),
+            Cell::new(&format!(
+                "{}/{}h",
+                pct(row.binance_interest_rate),
+                row.binance_funding_interval
+            )),
+            Cell::new(
+                &row.binance_open_interest
+                    .map_or("-".to_string(), |oi| f.fmt2(oi).to_string()),
+            ),
+            Cell::new(&fmt_price(row.binance_mark_price)),
+            Cell::new(&apr(row.hyperliquid_funding_rate)),
+            Cell::new(&fmt_next_funding(
+                row.hyperliquid_next_funding_rate,
+                1.0,
+                row.hyperliquid_next_funding_time,
+                now,
+            )),
+            Cell::new(&pct(row.hyperliquid_premium)),
+            Cell::new(f.fmt2(row.hyperliquid_open_interest)),
+            Cell::new(&fmt_price(row.hyperliquid_mark_price)),
+            Cell::new(&pct(row.basis)),
+            Cell::new(&apr(row.funding_rate_difference)),
+            Cell::new(&row.round_trip_cost.map_or("-".to_string(), |cost| {
+                format!("{:.2} bps", cost * 10_000.0)
+            })),
+            Cell::new(&row.net_apr.map_or("-".to_string(), |net_apr| {
+                format!("{:.2}%", net_apr * 100.0)
+            })),
         ]));
     }
 
     Ok(table.to_string())
 }
+
+/// six significant digits, mark prices run from fractions of a cent to tens of thousands
+fn fmt_price(price: f64) -> String {
+    let decimals = if price > 0.0 {
+        (5 - price.log10().floor() as i32).max(0) as usize
+    } else {
+        2
+    };
+    format!("{price:.decimals$}")
+}
+
+/// predicted payment over its `interval` of hours and the time left until it, e.g. `0.0100%/8h in 2h05m`
+fn fmt_next_funding(rate: f64, interval: f64, next_funding_time: i64, now: i64) -> String {
+    let minutes = (next_funding_time - now).max(0) / 60_000;
+    format!(
+        "{:.4}%/{interval}h in {}h{:02}m",
+        rate * 100.0,
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_next_funding() {
+        let now = 1_704_104_100_000; // 10:15 UTC
+        assert_eq!(
+            fmt_next_funding(0.0001, 8.0, 1_704_124_800_000, now),
+            "0.0100%/8h in 5h45m"
+        );
+        assert_eq!(
+            fmt_next_funding(-0.00002, 1.0, 1_704_106_800_000, now),
+            "-0.0020%/1h in 0h45m"
+        );
+        // a payment already settled shows no time left
+        assert_eq!(
+            fmt_next_funding(0.0001, 4.0, now - 1, now),
+            "0.0100%/4h in 0h00m"
+        );
+    }
+
+    #[test]
+    fn test_fmt_price() {
+        assert_eq!(fmt_price(2510.5), "2510.50");
+        assert_eq!(fmt_price(0.0123456789), "0.0123457");
+        assert_eq!(fmt_price(65_000.0), "65000.0");
+    }
+}
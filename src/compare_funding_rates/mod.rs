@@ -1,5 +1,12 @@
 mod compare_funding_rate;
 mod funding_rate_table;
+mod screener;
 
-pub use compare_funding_rate::{compare_funding_rates, JointFundingRate};
-pub use funding_rate_table::build_funding_rate_table;
+pub use compare_funding_rate::{compare_funding_rates, join_funding_rates, JointFundingRate};
+pub use funding_rate_table::{
+    build_funding_rate_table, build_funding_rate_table_from, format_funding_rate_table,
+};
+pub use screener::{
+    screen_funding_rates, screen_row, sort_screened, ScreenedFundingRate, Screener, ScreenerCache,
+    SortBy, DEFAULT_HOLDING_DAYS, DEFAULT_SCREENER_NOTIONAL,
+};
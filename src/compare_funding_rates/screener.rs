@@ -0,0 +1,494 @@
+use super::compare_funding_rate::JointFundingRate;
+use crate::binance::retrieve_token_open_interest;
+use crate::break_even::calculate_net_apr;
+use crate::fees::{retrieve_fee_schedule, FeeSchedule};
+use crate::orderbook::latest_orderbooks;
+use crate::quote::{quote_round_trip, FillTarget};
+use crate::symbols::{load_symbols, Asset};
+use crate::util::{
+    calculate_premium, determine_short_based_on_fr, Orderbook, Platform, QuoteAsset,
+};
+use anyhow::{bail, Error, Result};
+use futures::future::join_all;
+use serde::Serialize;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::join;
+
+/// round trip size quoted by the screener, USD across both legs
+pub const DEFAULT_SCREENER_NOTIONAL: f64 = 10_000.0;
+/// holding period (days) over which the net apr pays back the round trip
+pub const DEFAULT_HOLDING_DAYS: f64 = 7.0;
+/// open interest and orderbooks are refetched after this long when the table is watched
+pub const SCREENER_CACHE_TTL: Duration = Duration::from_secs(60);
+// tokens evaluated at once, each reads both orderbooks and the binance open interest
+const SCREENER_BATCH: usize = 10;
+// best differences looked at, the rest never make the table
+const MAX_SCREENER_CANDIDATES: usize = 40;
+
+/// order of the funding rate table, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortBy {
+    #[default]
+    Diff,
+    OpenInterest, // of the venue with less of it
+    Cost,
+    NetApr,
+}
+
+impl FromStr for SortBy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "diff" => Ok(SortBy::Diff),
+            "oi" => Ok(SortBy::OpenInterest),
+            "cost" => Ok(SortBy::Cost),
+            "net-apr" => Ok(SortBy::NetApr),
+            _ => bail!("Invalid sort. Use 'diff', 'oi', 'cost' or 'net-apr'"),
+        }
+    }
+}
+
+/// filters and order of the funding rate table
+#[derive(Debug, Clone)]
+pub struct Screener {
+    pub top: usize,
+    pub min_apr: Option<f64>,   // percent, of the funding rate difference
+    pub min_oi: Option<f64>,    // USD, on each venue
+    pub min_depth: Option<f64>, // USD, on every side of both orderbooks
+    pub only: Vec<String>,      // assets, every one when empty
+    pub exclude: Vec<String>,
+    pub sort_by: SortBy,
+    pub notional: f64, // USD across both legs, quoted for the round trip cost
+    pub holding_days: f64,
+}
+
+impl Default for Screener {
+    fn default() -> Self {
+        Self {
+            top: 6,
+            min_apr: None,
+            min_oi: None,
+            min_depth: None,
+            only: vec![],
+            exclude: vec![],
+            sort_by: SortBy::default(),
+            notional: DEFAULT_SCREENER_NOTIONAL,
+            holding_days: DEFAULT_HOLDING_DAYS,
+        }
+    }
+}
+
+impl Screener {
+    /// filters that only need the funding rates
+    pub fn keeps(&self, jfr: &JointFundingRate) -> bool {
+        let apr = jfr.funding_rate_difference * 24.0 * 365.0 * 100.0;
+
+        (self.only.is_empty() || self.only.contains(&jfr.name))
+            && !self.exclude.contains(&jfr.name)
+            && self.min_apr.is_none_or(|min| apr >= min)
+            && self
+                .min_oi
+                .is_none_or(|min| jfr.hyperliquid_open_interest >= min)
+    }
+
+    /// filters on the open interest and orderbooks of a screened row
+    pub fn passes(&self, row: &ScreenedFundingRate) -> bool {
+        self.min_oi
+            .is_none_or(|min| row.binance_open_interest.is_some_and(|oi| oi >= min))
+            && self
+                .min_depth
+                .is_none_or(|min| row.depth.is_some_and(|depth| depth >= min))
+    }
+}
+
+/// a joint funding rate with what it takes to trade it
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenedFundingRate {
+    pub name: String,
+    pub binance_quote: QuoteAsset,
+    pub long: Platform, // venue paying less funding
+    pub short: Platform,
+    pub binance_funding_rate: f64,     // hourly fr decimal form
+    pub hyperliquid_funding_rate: f64, // hourly fr decimal form
+    pub funding_rate_difference: f64,
+    pub binance_next_funding_rate: f64, // per funding interval
+    pub binance_next_funding_time: i64, // unix ms
+    pub binance_funding_interval: f64,  // hours between payments
+    pub binance_interest_rate: f64,     // per funding interval
+    pub binance_premium: f64,
+    pub hyperliquid_next_funding_rate: f64,
+    pub hyperliquid_next_funding_time: i64,
+    pub hyperliquid_premium: f64,
+    pub binance_open_interest: Option<f64>, // USD, none when it failed to load
+    pub hyperliquid_open_interest: f64,     // USD
+    pub binance_mark_price: f64,            // per unit of the asset
+    pub hyperliquid_mark_price: f64,        // per unit of the asset
+    pub basis: f64,                         // binance mark over hyperliquid's, decimal form
+    pub depth: Option<f64>,                 // USD, thinnest side of both books, none without them
+    pub round_trip_cost: Option<f64>, // decimal form of one leg, none when the books can't fill the notional
+    pub net_apr: Option<f64>, // decimal form, after paying the round trip over the holding period
+}
+
+/// `books` are both orderbooks in units of the asset, binance first
+pub fn screen_row(
+    jfr: JointFundingRate,
+    binance_open_interest: Option<f64>,
+    books: Option<&(Orderbook, Orderbook)>,
+    fees: &FeeSchedule,
+    screener: &Screener,
+) -> ScreenedFundingRate {
+    let short = determine_short_based_on_fr(&jfr);
+    let long = match short {
+        Platform::Binance => Platform::Hyperliquid,
+        Platform::Hyperliquid => Platform::Binance,
+    };
+
+    let depth = books.map(|(b, hl)| {
+        let (b_bids, b_asks) = b.get_total_depth();
+        let (hl_bids, hl_asks) = hl.get_total_depth();
+        [b_bids, b_asks, hl_bids, hl_asks]
+            .into_iter()
+            .fold(f64::INFINITY, f64::min)
+    });
+    let round_trip_cost = books
+        .and_then(|(b, hl)| {
+            quote_round_trip(b, hl, long, FillTarget::Usd(screener.notional), fees).ok()
+        })
+        .map(|round_trip| round_trip.round_trip_cost);
+    let net_apr = round_trip_cost
+        .map(|cost| calculate_net_apr(cost, jfr.funding_rate_difference, screener.holding_days));
+
+    ScreenedFundingRate {
+        basis: calculate_premium(jfr.binance_mark_price, jfr.hyperliquid_mark_price),
+        name: jfr.name,
+        binance_quote: jfr.binance_quote,
+        long,
+        short,
+        binance_funding_rate: jfr.binance_funding_rate,
+        hyperliquid_funding_rate: jfr.hyperliquid_funding_rate,
+        funding_rate_difference: jfr.funding_rate_difference,
+        binance_next_funding_rate: jfr.binance_next_funding_rate,
+        binance_next_funding_time: jfr.binance_next_funding_time,
+        binance_funding_interval: jfr.binance_funding_interval,
+        binance_interest_rate: jfr.binance_interest_rate,
+        binance_premium: jfr.binance_premium,
+        hyperliquid_next_funding_rate: jfr.hyperliquid_next_funding_rate,
+        hyperliquid_next_funding_time: jfr.hyperliquid_next_funding_time,
+        hyperliquid_premium: jfr.hyperliquid_premium,
+        binance_open_interest,
+        hyperliquid_open_interest: jfr.hyperliquid_open_interest,
+        binance_mark_price: jfr.binance_mark_price,
+        hyperliquid_mark_price: jfr.hyperliquid_mark_price,
+        depth,
+        round_trip_cost,
+        net_apr,
+    }
+}
+
+/// best first, rows the books couldn't quote go last when sorting on cost
+pub fn sort_screened(rows: &mut [ScreenedFundingRate], sort_by: SortBy) {
+    let open_interest = |row: &ScreenedFundingRate| {
+        row.binance_open_interest
+            .unwrap_or(0.0)
+            .min(row.hyperliquid_open_interest)
+    };
+    let cost = |row: &ScreenedFundingRate| row.round_trip_cost.unwrap_or(f64::INFINITY);
+    let net_apr = |row: &ScreenedFundingRate| row.net_apr.unwrap_or(f64::NEG_INFINITY);
+
+    match sort_by {
+        SortBy::Diff => rows.sort_by(|a, b| {
+            b.funding_rate_difference
+                .total_cmp(&a.funding_rate_difference)
+        }),
+        SortBy::OpenInterest => rows.sort_by(|a, b| open_interest(b).total_cmp(&open_interest(a))),
+        SortBy::Cost => rows.sort_by(|a, b| cost(a).total_cmp(&cost(b))),
+        SortBy::NetApr => rows.sort_by(|a, b| net_apr(b).total_cmp(&net_apr(a))),
+    }
+}
+
+/// binance open interest (contracts) and both orderbooks of a token, none where a request failed
+#[derive(Debug, Clone)]
+struct MarketDepth {
+    fetched_at: Instant,
+    open_interest: Option<f64>,
+    books: Option<(Orderbook, Orderbook)>,
+}
+
+/// market data of screened tokens and fees per quote, reused across refreshes of a watched table
+#[derive(Debug, Default)]
+pub struct ScreenerCache {
+    markets: Mutex<HashMap<(String, QuoteAsset), MarketDepth>>,
+    fees: Mutex<HashMap<QuoteAsset, FeeSchedule>>,
+}
+
+impl ScreenerCache {
+    /// commission rates differ by margin asset rather than by symbol, one lookup per quote
+    async fn fees(&self, token: &str, quote: QuoteAsset) -> Result<FeeSchedule> {
+        if let Some(fees) = self.fees.lock().unwrap().get(&quote) {
+            return Ok(*fees);
+        }
+
+        let fees = retrieve_fee_schedule(token, quote).await?;
+        self.fees.lock().unwrap().insert(quote, fees);
+
+        Ok(fees)
+    }
+
+    /// fresh market data of `jfr`, the live books when they stream
+    async fn market(&self, asset: &Asset, jfr: &JointFundingRate) -> MarketDepth {
+        let key = (jfr.name.clone(), jfr.binance_quote);
+        if let Some(market) = self.markets.lock().unwrap().get(&key) {
+            if market.fetched_at.elapsed() < SCREENER_CACHE_TTL {
+                return market.clone();
+            }
+        }
+
+        let (open_interest, books) = join!(
+            retrieve_token_open_interest(asset.symbol(Platform::Binance), jfr.binance_quote),
+            latest_orderbooks(&jfr.name, jfr.binance_quote)
+        );
+        // failures are kept too, so a token the venue rejects isn't asked again every refresh
+        let market = MarketDepth {
+            fetched_at: Instant::now(),
+            open_interest: open_interest
+                .ok()
+                .and_then(|oi| oi.open_interest.parse().ok()),
+            books: books.ok(),
+        };
+        self.markets.lock().unwrap().insert(key, market.clone());
+
+        market
+    }
+}
+
+/// the `top` rows of `fr` that pass `screener`, in its order. `fr` comes best difference first.
+/// a token whose open interest or orderbooks fail to load keeps its row with those columns empty
+pub async fn screen_funding_rates(
+    fr: Vec<JointFundingRate>,
+    screener: &Screener,
+    cache: &ScreenerCache,
+) -> Result<Vec<ScreenedFundingRate>> {
+    let symbols = load_symbols().await?;
+    let candidates: Vec<_> = fr
+        .into_iter()
+        .filter(|jfr| screener.keeps(jfr))
+        .take(MAX_SCREENER_CANDIDATES)
+        .collect();
+
+    let mut fees = HashMap::new();
+    for jfr in &candidates {
+        if let Entry::Vacant(entry) = fees.entry(jfr.binance_quote) {
+            entry.insert(cache.fees(&jfr.name, jfr.binance_quote).await?);
+        }
+    }
+
+    let mut rows = vec![];
+    let mut candidates = candidates.into_iter();
+    loop {
+        // candidates already come by difference, other orders need every one of them
+        if screener.sort_by == SortBy::Diff && rows.len() >= screener.top {
+            break;
+        }
+        let batch: Vec<_> = candidates.by_ref().take(SCREENER_BATCH).collect();
+        if batch.is_empty() {
+            break;
+        }
+
+        let batch = batch.into_iter().map(|jfr| {
+            let fees = fees[&jfr.binance_quote];
+            async move {
+                let asset = symbols.asset(&jfr.name);
+                let market = cache.market(&asset, &jfr).await;
+                // open interest is in contracts, the mark price per unit of the asset
+                let oi_usd = market
+                    .open_interest
+                    .map(|oi| oi * jfr.binance_mark_price * asset.scale(Platform::Binance));
+
+                screen_row(jfr, oi_usd, market.books.as_ref(), &fees, screener)
+            }
+        });
+
+        let screened = join_all(batch).await;
+        rows.extend(screened.into_iter().filter(|row| screener.passes(row)));
+    }
+
+    sort_screened(&mut rows, screener.sort_by);
+    rows.truncate(screener.top);
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{BINANCE_FEE, HYPERLIQUID_FEE};
+    use crate::test_fixtures::joint_funding_rate;
+    use crate::util::LimitOrder;
+
+    /// marked around 100 with 2m open on hyperliquid
+    fn jfr(
+        name: &str,
+        binance_funding_rate: f64,
+        hyperliquid_funding_rate: f64,
+    ) -> JointFundingRate {
+        JointFundingRate {
+            binance_mark_price: 101.0,
+            hyperliquid_open_interest: 2_000_000.0,
+            hyperliquid_mark_price: 100.0,
+            ..joint_funding_rate(name, binance_funding_rate, hyperliquid_funding_rate)
+        }
+    }
+
+    /// one level each side around 100, `size` tokens deep
+    fn book(platform: Platform, size: f64) -> Orderbook {
+        Orderbook {
+            platform,
+            bids: vec![LimitOrder { price: 99.9, size }],
+            asks: vec![LimitOrder { price: 100.1, size }],
+        }
+    }
+
+    #[test]
+    fn test_sort_by_from_str() {
+        assert_eq!("net-apr".parse::<SortBy>().unwrap(), SortBy::NetApr);
+        assert_eq!("OI".parse::<SortBy>().unwrap(), SortBy::OpenInterest);
+        assert!("apr".parse::<SortBy>().is_err());
+    }
+
+    #[test]
+    fn test_screener_filters() {
+        let screener = Screener {
+            min_apr: Some(50.0),
+            min_oi: Some(1_000_000.0),
+            exclude: vec!["WIF".to_string()],
+            ..Screener::default()
+        };
+
+        // 0.0001 hourly is 87.6% apr
+        assert!(screener.keeps(&jfr("ETH", 0.0001, 0.0)));
+        assert!(!screener.keeps(&jfr("ETH", 0.00001, 0.0)));
+        assert!(!screener.keeps(&jfr("WIF", 0.0001, 0.0)));
+
+        let only = Screener {
+            only: vec!["BTC".to_string()],
+            ..Screener::default()
+        };
+        assert!(!only.keeps(&jfr("ETH", 0.0001, 0.0)));
+
+        let fees = FeeSchedule::default();
+        let books = (
+            book(Platform::Binance, 1000.0),
+            book(Platform::Hyperliquid, 1000.0),
+        );
+        let row = screen_row(
+            jfr("ETH", 0.0001, 0.0),
+            Some(500_000.0),
+            Some(&books),
+            &fees,
+            &screener,
+        );
+        assert!(!screener.passes(&row));
+
+        let deep = Screener {
+            min_depth: Some(50_000.0),
+            ..Screener::default()
+        };
+        assert!(deep.passes(&row));
+        let no_books = screen_row(jfr("ETH", 0.0001, 0.0), Some(500_000.0), None, &fees, &deep);
+        assert!(!deep.passes(&no_books));
+        // open interest that failed to load can't show it is above the minimum
+        let no_oi = screen_row(jfr("ETH", 0.0001, 0.0), None, Some(&books), &fees, &deep);
+        assert!(deep.passes(&no_oi));
+        assert!(!screener.passes(&no_oi));
+    }
+
+    #[test]
+    fn test_screen_row_quotes_recommended_hedge() {
+        let screener = Screener::default();
+        let fees = FeeSchedule::default();
+        let books = (
+            book(Platform::Binance, 1000.0),
+            book(Platform::Hyperliquid, 1000.0),
+        );
+
+        let row = screen_row(
+            jfr("ETH", 0.0001, -0.00002),
+            Some(5_000_000.0),
+            Some(&books),
+            &fees,
+            &screener,
+        );
+
+        // binance pays more, so it is the short leg
+        assert_eq!(
+            (row.long, row.short),
+            (Platform::Hyperliquid, Platform::Binance)
+        );
+        assert!((row.basis - 0.01).abs() < 1e-12);
+        assert_eq!(row.depth, Some(99.9 * 1000.0));
+
+        // both spreads and the fees of both venues, twice
+        let cost = row.round_trip_cost.unwrap();
+        assert!(cost > 2.0 * (BINANCE_FEE + HYPERLIQUID_FEE));
+        let net_apr = row.net_apr.unwrap();
+        assert!((net_apr - calculate_net_apr(cost, 0.00012, DEFAULT_HOLDING_DAYS)).abs() < 1e-12);
+
+        // a book too thin for the notional can't be quoted
+        let thin = (
+            book(Platform::Binance, 1.0),
+            book(Platform::Hyperliquid, 1.0),
+        );
+        let row = screen_row(jfr("ETH", 0.0001, 0.0), None, Some(&thin), &fees, &screener);
+        assert_eq!((row.round_trip_cost, row.net_apr), (None, None));
+    }
+
+    #[test]
+    fn test_sort_screened() {
+        let fees = FeeSchedule::default();
+        let screener = Screener::default();
+        let deep = (
+            book(Platform::Binance, 1000.0),
+            book(Platform::Hyperliquid, 1000.0),
+        );
+
+        let mut rows = vec![
+            screen_row(jfr("HIGH", 0.0003, 0.0), None, None, &fees, &screener),
+            screen_row(
+                jfr("LIQUID", 0.0001, 0.0),
+                Some(9_000_000.0),
+                Some(&deep),
+                &fees,
+                &screener,
+            ),
+            screen_row(
+                jfr("MID", 0.0002, 0.0),
+                Some(500_000.0),
+                Some(&deep),
+                &fees,
+                &screener,
+            ),
+        ];
+        let names =
+            |rows: &[ScreenedFundingRate]| rows.iter().map(|r| r.name.clone()).collect::<Vec<_>>();
+
+        sort_screened(&mut rows, SortBy::Diff);
+        assert_eq!(names(&rows), ["HIGH", "MID", "LIQUID"]);
+
+        // hyperliquid holds 2m on every row, the thinner venue decides and a missing one goes last
+        sort_screened(&mut rows, SortBy::OpenInterest);
+        assert_eq!(names(&rows), ["LIQUID", "MID", "HIGH"]);
+
+        // same books, unquoted rows last
+        sort_screened(&mut rows, SortBy::Cost);
+        assert_eq!(names(&rows)[2], "HIGH");
+
+        sort_screened(&mut rows, SortBy::NetApr);
+        assert_eq!(names(&rows), ["MID", "LIQUID", "HIGH"]);
+    }
+}
@@ -0,0 +1,249 @@
+use super::{decrypt_secrets, profile};
+use anyhow::{bail, Context, Result};
+use ethers::{signers::LocalWallet, types::H160};
+use serde::Deserialize;
+use std::{future::Future, path::PathBuf, str::FromStr};
+
+tokio::task_local! {
+    static ACCOUNT: Credentials;
+}
+
+/// keys of one account, missing ones are taken from the profile's, then the environment.
+/// the hyperliquid wallet can act for a sub-account or vault, queries and orders then target that address
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Credentials {
+    pub binance_api_key: Option<String>,
+    pub binance_secret_key: Option<String>,
+    pub mnemonic: Option<String>,
+    pub keystore: Option<PathBuf>, // encrypted json keystore of the hyperliquid signer
+    pub secrets: Option<PathBuf>,  // encrypted file with the keys above
+    pub agent_keystore: Option<PathBuf>, // approved agent, signs orders instead of the master key
+    pub hyperliquid_master_address: Option<String>, // queried when only the agent key is here
+    pub hyperliquid_sub_account: Option<String>,
+    pub hyperliquid_vault: Option<String>,
+    #[serde(skip)]
+    pub wallet: Option<LocalWallet>, // decrypted from `keystore`
+    #[serde(skip)]
+    pub agent: Option<LocalWallet>, // decrypted from `agent_keystore`
+}
+
+impl Credentials {
+    pub fn is_encrypted(&self) -> bool {
+        self.keystore.is_some() || self.secrets.is_some() || self.agent_keystore.is_some()
+    }
+
+    /// encrypted files that are set but don't exist, by field name
+    pub fn missing_files(&self) -> Vec<(&'static str, &PathBuf)> {
+        [
+            ("keystore", &self.keystore),
+            ("secrets", &self.secrets),
+            ("agent_keystore", &self.agent_keystore),
+        ]
+        .into_iter()
+        .filter_map(|(field, path)| Some((field, path.as_ref()?)))
+        .filter(|(_, path)| !path.exists())
+        .collect()
+    }
+
+    /// decrypts the keystore and secrets file, keys set in plain text are kept
+    pub fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        if let Some(path) = &self.secrets {
+            let secrets = decrypt_secrets(path, passphrase)?;
+            self.binance_api_key = self.binance_api_key.take().or(secrets.binance_api_key);
+            self.binance_secret_key = self
+                .binance_secret_key
+                .take()
+                .or(secrets.binance_secret_key);
+            self.mnemonic = self.mnemonic.take().or(secrets.mnemonic);
+        }
+        if let Some(path) = &self.keystore {
+            let wallet = LocalWallet::decrypt_keystore(path, passphrase)
+                .with_context(|| format!("Could not unlock {}", path.display()))?;
+            self.wallet = Some(wallet);
+        }
+        if let Some(path) = &self.agent_keystore {
+            let agent = LocalWallet::decrypt_keystore(path, passphrase)
+                .with_context(|| format!("Could not unlock {}", path.display()))?;
+            self.agent = Some(agent);
+        }
+
+        Ok(())
+    }
+
+    /// sub-account or vault the hyperliquid wallet trades for
+    pub fn hyperliquid_address(&self) -> Result<Option<H160>> {
+        match (&self.hyperliquid_sub_account, &self.hyperliquid_vault) {
+            (Some(_), Some(_)) => bail!("Set either hyperliquid_sub_account or hyperliquid_vault"),
+            (Some(address), None) | (None, Some(address)) => {
+                Ok(Some(H160::from_str(address).with_context(|| {
+                    format!("Invalid hyperliquid address {address}")
+                })?))
+            }
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// master address given for agent only setups
+    pub fn master_address(&self) -> Result<Option<H160>> {
+        self.hyperliquid_master_address
+            .as_deref()
+            .map(|address| {
+                H160::from_str(address)
+                    .with_context(|| format!("Invalid hyperliquid master address {address}"))
+            })
+            .transpose()
+    }
+
+    fn has_signer(&self) -> bool {
+        self.mnemonic.is_some()
+            || self.keystore.is_some()
+            || self.agent_keystore.is_some()
+            || self.wallet.is_some()
+            || self.agent.is_some()
+    }
+
+    /// false when the binance account is the one of the profile
+    pub fn owns_binance(&self) -> bool {
+        self.binance_api_key.is_some()
+    }
+
+    /// false when the hyperliquid account is the one of the profile
+    pub fn owns_hyperliquid(&self) -> bool {
+        self.has_signer()
+            || self.hyperliquid_sub_account.is_some()
+            || self.hyperliquid_vault.is_some()
+    }
+}
+
+/// runs `fut` against `credentials` instead of the selected account, concurrent runs don't interfere
+pub async fn in_account<F: Future>(credentials: Credentials, fut: F) -> F::Output {
+    ACCOUNT.scope(credentials, fut).await
+}
+
+/// scoped account, else the one picked with `--account`
+fn active_account() -> Option<Credentials> {
+    ACCOUNT.try_with(Clone::clone).ok().or_else(|| {
+        let profile = profile();
+        let name = profile.selected_account.as_ref()?;
+        profile.accounts.get(name).cloned()
+    })
+}
+
+/// active account, else the profile's credentials, else the environment
+fn credential(field: fn(&Credentials) -> &Option<String>, key: &str) -> Result<String> {
+    active_account()
+        .and_then(|account| field(&account).clone())
+        .or_else(|| field(&profile().credentials).clone())
+        .or_else(|| std::env::var(key).ok())
+        .with_context(|| format!("{key} is not set"))
+}
+
+pub fn binance_api_key() -> Result<String> {
+    credential(|c| &c.binance_api_key, "BINANCE_API_KEY")
+}
+
+pub fn binance_secret_key() -> Result<String> {
+    credential(|c| &c.binance_secret_key, "BINANCE_SECRET_KEY")
+}
+
+pub fn mnemonic() -> Result<String> {
+    credential(|c| &c.mnemonic, "MNEMONIC")
+}
+
+/// account whose hyperliquid keys are used, a named account without any signs with the profile's
+fn signing_account() -> Credentials {
+    match active_account() {
+        Some(account) if account.has_signer() => account,
+        _ => profile().credentials.clone(),
+    }
+}
+
+/// master signer unlocked from a keystore, `None` when it comes from a mnemonic
+pub fn unlocked_wallet() -> Option<LocalWallet> {
+    signing_account().wallet
+}
+
+/// agent approved to place orders for the master wallet
+pub fn unlocked_agent() -> Option<LocalWallet> {
+    signing_account().agent
+}
+
+pub fn hyperliquid_master_address() -> Result<Option<H160>> {
+    signing_account().master_address()
+}
+
+/// hyperliquid sub-account or vault of the active account, the profile's without one
+pub fn hyperliquid_account_address() -> Result<Option<H160>> {
+    match active_account() {
+        Some(account) => account.hyperliquid_address(),
+        None => profile().credentials.hyperliquid_address(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{core::rand::thread_rng, signers::Signer};
+
+    const SUB_ACCOUNT: &str = "0x1111111111111111111111111111111111111111";
+
+    #[tokio::test]
+    async fn test_scoped_account_credentials() -> Result<()> {
+        let sub = Credentials {
+            hyperliquid_sub_account: Some(SUB_ACCOUNT.to_string()),
+            ..Default::default()
+        };
+        let keyed = Credentials {
+            binance_secret_key: Some("from-account".to_string()),
+            ..Default::default()
+        };
+
+        let sub_address = in_account(sub, async { hyperliquid_account_address() }).await?;
+        assert_eq!(sub_address, Some(H160::from_str(SUB_ACCOUNT)?));
+        assert_eq!(hyperliquid_account_address()?, None);
+
+        let keyed_secret = in_account(keyed, async { binance_secret_key() }).await?;
+        assert_eq!(keyed_secret, "from-account");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_agent_signs_for_master_address() -> Result<()> {
+        let agent = LocalWallet::new(&mut thread_rng());
+        let account = Credentials {
+            agent: Some(agent.clone()),
+            hyperliquid_master_address: Some(SUB_ACCOUNT.to_string()),
+            ..Default::default()
+        };
+        assert!(account.owns_hyperliquid());
+
+        let (signer, master, wallet) = in_account(account, async {
+            (
+                unlocked_agent(),
+                hyperliquid_master_address(),
+                unlocked_wallet(),
+            )
+        })
+        .await;
+        assert_eq!(signer.map(|s| s.address()), Some(agent.address()));
+        assert_eq!(master?, Some(H160::from_str(SUB_ACCOUNT)?));
+        // the master key isn't on the box
+        assert!(wallet.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_account_and_vault_are_exclusive() {
+        let both = Credentials {
+            hyperliquid_sub_account: Some(SUB_ACCOUNT.to_string()),
+            hyperliquid_vault: Some(SUB_ACCOUNT.to_string()),
+            ..Default::default()
+        };
+        assert!(both.hyperliquid_address().is_err());
+        assert!(both.owns_hyperliquid());
+        assert!(!both.owns_binance());
+    }
+}
@@ -0,0 +1,13 @@
+mod account;
+mod profile;
+mod secrets;
+
+pub use account::{
+    binance_api_key, binance_secret_key, hyperliquid_account_address, hyperliquid_master_address,
+    in_account, mnemonic, unlocked_agent, unlocked_wallet, Credentials,
+};
+pub use profile::{
+    in_profile, profile, AlertsConfig, BinanceConfig, ConfigFile, ExecutionConfig,
+    HyperliquidConfig, Profile, StrategyConfig, DEFAULT_CONFIG_PATH,
+};
+pub use secrets::{decrypt_secrets, encrypt_secrets, encrypt_wallet, Secrets};
@@ -0,0 +1,518 @@
+use super::Credentials;
+use crate::{
+    constants::{
+        sdk_base_url, ALERT_INTERVAL, DEFAULT_MAX_SLIPPAGE, EXECUTION_SLIPPAGE,
+        MAX_DAYS_QUERY_FUNDING_HISTORY, MIN_REBALANCE_TRANSFER,
+    },
+    execution::LeverageSettings,
+    fees::TradingFees,
+    util::QuoteAsset,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use ethers::signers::{coins_bip39::English, MnemonicBuilder};
+use serde::Deserialize;
+use std::{collections::BTreeMap, future::Future, path::Path, sync::OnceLock};
+
+/// read when `--config` is not given and the file exists
+pub const DEFAULT_CONFIG_PATH: &str = "funding-arb.toml";
+
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+
+tokio::task_local! {
+    static SCOPED_PROFILE: &'static Profile;
+}
+
+/// profile of the current `in_profile` scope, else the one the process runs with,
+/// the defaults until one is installed
+pub fn profile() -> &'static Profile {
+    SCOPED_PROFILE
+        .try_with(|profile| *profile)
+        .unwrap_or_else(|_| PROFILE.get_or_init(Profile::default))
+}
+
+/// runs `fut` against `profile` instead of the process one, concurrent runs don't interfere
+pub async fn in_profile<F: Future>(profile: &'static Profile, fut: F) -> F::Output {
+    SCOPED_PROFILE.scope(profile, fut).await
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// one set of credentials, venues and parameters, every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub credentials: Credentials, // the environment fills in what is not set here
+    pub accounts: BTreeMap<String, Credentials>,
+    #[serde(skip)]
+    pub selected_account: Option<String>,
+    pub binance: BinanceConfig,
+    pub hyperliquid: HyperliquidConfig,
+    pub execution: ExecutionConfig,
+    pub strategy: StrategyConfig,
+    pub alerts: AlertsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BinanceConfig {
+    pub enabled: bool, // account commands only
+    pub api_url: Option<String>,
+    pub spot_api_url: Option<String>,
+    pub fees: Option<TradingFees>, // skips the commission rate lookup
+    pub quote: QuoteAsset,         // perpetuals traded when a command doesn't pick one
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HyperliquidConfig {
+    pub enabled: bool,           // account commands only
+    pub api_url: Option<String>, // mainnet, testnet or localhost:3001, orders follow it through the sdk
+    pub arbitrum_rpc_url: Option<String>,
+    pub fees: Option<TradingFees>, // skips the user fees lookup
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExecutionConfig {
+    pub max_slippage: Option<f64>, // bps, quoted slippage allowed when opening
+    pub execution_slippage: Option<f64>, // decimal form, bound of hyperliquid market orders
+    pub leverage: Option<u32>,
+    pub isolated: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategyConfig {
+    pub min_spread_apr: Option<f64>, // %
+    pub max_leverage: Option<f64>,
+    pub min_liquidation_distance: Option<f64>, // %
+    pub min_rebalance_transfer: Option<f64>,   // usd
+    pub funding_history_days: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsConfig {
+    pub webhooks: Vec<String>,
+    pub interval: Option<u64>, // seconds
+}
+
+impl Default for BinanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            api_url: None,
+            spot_api_url: None,
+            fees: None,
+            quote: QuoteAsset::default(),
+        }
+    }
+}
+
+impl Default for HyperliquidConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            api_url: None,
+            arbitrum_rpc_url: None,
+            fees: None,
+        }
+    }
+}
+
+impl ConfigFile {
+    pub fn parse(text: &str) -> Result<ConfigFile> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn load(path: &Path) -> Result<ConfigFile> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+
+        ConfigFile::parse(&text).with_context(|| format!("Invalid config in {}", path.display()))
+    }
+
+    /// `name`, else `default_profile`, else the profile called `default`.
+    /// a file without profiles runs on the defaults
+    pub fn profile(&self, name: Option<&str>) -> Result<(String, Profile)> {
+        let name = name
+            .or(self.default_profile.as_deref())
+            .unwrap_or("default");
+
+        match self.profiles.get(name) {
+            Some(profile) => Ok((name.to_string(), profile.clone())),
+            None if self.profiles.is_empty() => Ok((name.to_string(), Profile::default())),
+            None => bail!(
+                "No profile named {name}, available: {}",
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+impl Profile {
+    /// makes this the process profile. unlocked credentials stay in memory, they are never exported
+    pub fn install(self) -> Result<()> {
+        PROFILE
+            .set(self)
+            .map_err(|_| anyhow!("A profile is already installed"))
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.credentials.is_encrypted() || self.accounts.values().any(Credentials::is_encrypted)
+    }
+
+    /// keystores and secrets files that don't exist, checked before asking for the passphrase
+    pub fn missing_credential_files(&self) -> Vec<String> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|(name, account)| (format!("accounts.{name}"), account));
+
+        std::iter::once(("credentials".to_string(), &self.credentials))
+            .chain(accounts)
+            .flat_map(|(section, credentials)| {
+                credentials
+                    .missing_files()
+                    .into_iter()
+                    .map(move |(field, path)| {
+                        format!("{section}.{field}: {} does not exist", path.display())
+                    })
+            })
+            .collect()
+    }
+
+    /// decrypts the keystores and secrets files of the profile and its accounts, all share one passphrase
+    pub fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        self.credentials.unlock(passphrase)?;
+        for (name, account) in &mut self.accounts {
+            account
+                .unlock(passphrase)
+                .with_context(|| format!("Could not unlock account {name}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// runs account and trading commands against the named account instead of the profile's credentials
+    pub fn select_account(&mut self, name: &str) -> Result<()> {
+        if !self.accounts.contains_key(name) {
+            bail!(
+                "No account named {name}, available: {}",
+                self.accounts.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        }
+        self.selected_account = Some(name.to_string());
+
+        Ok(())
+    }
+
+    pub fn max_slippage(&self) -> f64 {
+        self.execution.max_slippage.unwrap_or(DEFAULT_MAX_SLIPPAGE)
+    }
+
+    pub fn execution_slippage(&self) -> f64 {
+        self.execution
+            .execution_slippage
+            .unwrap_or(EXECUTION_SLIPPAGE)
+    }
+
+    /// leverage and margin mode given on the command or request, else the profile's. without a leverage the account settings are kept
+    pub fn leverage_settings(
+        &self,
+        leverage: Option<u32>,
+        isolated: Option<bool>,
+    ) -> Option<LeverageSettings> {
+        leverage
+            .or(self.execution.leverage)
+            .map(|leverage| LeverageSettings {
+                leverage,
+                isolated: isolated.unwrap_or(self.execution.isolated),
+            })
+    }
+
+    pub fn min_rebalance_transfer(&self) -> f64 {
+        self.strategy
+            .min_rebalance_transfer
+            .unwrap_or(MIN_REBALANCE_TRANSFER)
+    }
+
+    pub fn funding_history_days(&self) -> u8 {
+        self.strategy
+            .funding_history_days
+            .unwrap_or(MAX_DAYS_QUERY_FUNDING_HISTORY as u8)
+    }
+
+    pub fn alert_interval(&self) -> u64 {
+        self.alerts.interval.unwrap_or(ALERT_INTERVAL)
+    }
+
+    /// commands that read the accounts or trade need both venues.
+    /// `enabled` gates only those, public market data is still compared on both venues
+    pub fn ensure_venues_enabled(&self) -> Result<()> {
+        match (self.binance.enabled, self.hyperliquid.enabled) {
+            (true, true) => Ok(()),
+            (false, _) => bail!("Binance is disabled in this profile"),
+            (_, false) => bail!("Hyperliquid is disabled in this profile"),
+        }
+    }
+
+    /// problems that would make commands fail once unlocked, `env` looks up credentials set outside the file
+    pub fn validate(&self, env: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        let mut problems = vec![];
+
+        let credential = |value: &Option<String>, key: &str| value.clone().or_else(|| env(key));
+
+        if self.binance.enabled {
+            for (value, key) in [
+                (&self.credentials.binance_api_key, "BINANCE_API_KEY"),
+                (&self.credentials.binance_secret_key, "BINANCE_SECRET_KEY"),
+            ] {
+                if credential(value, key).is_none() {
+                    problems.push(format!("{key} is missing from the profile and environment"));
+                }
+            }
+        }
+        // an agent can trade without the master key, queries then need the master address
+        let agent_only = self.credentials.agent.is_some()
+            && self.credentials.hyperliquid_master_address.is_some();
+        if self.hyperliquid.enabled && self.credentials.wallet.is_none() && !agent_only {
+            match credential(&self.credentials.mnemonic, "MNEMONIC") {
+                None => {
+                    problems.push("MNEMONIC is missing from the profile and environment".into())
+                }
+                Some(mnemonic) => {
+                    if let Err(e) = MnemonicBuilder::<English>::default()
+                        .phrase(mnemonic.as_str())
+                        .build()
+                    {
+                        problems.push(format!("MNEMONIC is not a valid wallet phrase: {e}"));
+                    }
+                }
+            }
+        }
+        if let Err(e) = self.credentials.hyperliquid_address() {
+            problems.push(format!("credentials: {e}"));
+        }
+        if let Err(e) = self.credentials.master_address() {
+            problems.push(format!("credentials: {e}"));
+        }
+
+        for (name, account) in &self.accounts {
+            // the profile's own credentials are reported as the default account
+            if name == "default" {
+                problems.push("accounts.default is reserved for the profile's credentials".into());
+            }
+            if account.binance_api_key.is_some() != account.binance_secret_key.is_some() {
+                problems.push(format!(
+                    "accounts.{name} needs both binance_api_key and binance_secret_key"
+                ));
+            }
+            if let Some(mnemonic) = &account.mnemonic {
+                if let Err(e) = MnemonicBuilder::<English>::default()
+                    .phrase(mnemonic.as_str())
+                    .build()
+                {
+                    problems.push(format!(
+                        "accounts.{name}.mnemonic is not a valid wallet phrase: {e}"
+                    ));
+                }
+            }
+            if let Err(e) = account.hyperliquid_address() {
+                problems.push(format!("accounts.{name}: {e}"));
+            }
+            if let Err(e) = account.master_address() {
+                problems.push(format!("accounts.{name}: {e}"));
+            }
+        }
+
+        let urls = [
+            ("binance.api_url", &self.binance.api_url),
+            ("binance.spot_api_url", &self.binance.spot_api_url),
+            ("hyperliquid.api_url", &self.hyperliquid.api_url),
+            (
+                "hyperliquid.arbitrum_rpc_url",
+                &self.hyperliquid.arbitrum_rpc_url,
+            ),
+        ];
+        for (field, url) in urls {
+            if let Some(url) = url {
+                if reqwest::Url::parse(url).is_err() {
+                    problems.push(format!("{field} is not a valid url: {url}"));
+                }
+            }
+        }
+        // orders and sdk queries go to the network of a valid url
+        let hl_url = self.hyperliquid.api_url.as_ref();
+        if let Some(url) = hl_url.filter(|url| reqwest::Url::parse(url).is_ok()) {
+            if let Err(e) = sdk_base_url(url) {
+                problems.push(format!("hyperliquid.api_url: {e}"));
+            }
+        }
+        for url in &self.alerts.webhooks {
+            if reqwest::Url::parse(url).is_err() {
+                problems.push(format!("alerts.webhooks has an invalid url: {url}"));
+            }
+        }
+
+        for (venue, fees) in [
+            ("binance", self.binance.fees),
+            ("hyperliquid", self.hyperliquid.fees),
+        ] {
+            // decimal form, a percent written as 0.05 would be a 5% fee
+            if let Some(fees) = fees {
+                if !(-0.001..0.01).contains(&fees.maker) || !(0.0..0.01).contains(&fees.taker) {
+                    problems.push(format!(
+                        "{venue}.fees look off, expected decimal form like 0.0002"
+                    ));
+                }
+            }
+        }
+
+        if self.max_slippage() <= 0.0 {
+            problems.push("execution.max_slippage must be positive".into());
+        }
+        if !(0.0..0.1).contains(&self.execution_slippage()) {
+            problems.push("execution.execution_slippage must be in decimal form below 0.1".into());
+        }
+        if self.alerts.interval == Some(0) {
+            problems.push("alerts.interval must be at least 1 second".into());
+        }
+        if self.execution.leverage == Some(0) {
+            problems.push("execution.leverage must be at least 1".into());
+        }
+        if !(1..=MAX_DAYS_QUERY_FUNDING_HISTORY).contains(&self.funding_history_days().into()) {
+            problems.push(format!(
+                "strategy.funding_history_days must be between 1 and {MAX_DAYS_QUERY_FUNDING_HISTORY}"
+            ));
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_config_is_valid() -> Result<()> {
+        let config = ConfigFile::parse(include_str!("../../funding-arb.example.toml"))?;
+
+        let (name, profile) = config.profile(None)?;
+        assert_eq!(name, "main");
+        assert_eq!(profile.max_slippage(), 3.0);
+        assert_eq!(profile.strategy.min_spread_apr, Some(15.0));
+        assert_eq!(profile.binance.quote, QuoteAsset::Usdc);
+        assert_eq!(profile.validate(|_| None), Vec::<String>::new());
+
+        let mut accounts = profile.clone();
+        accounts.select_account("vault")?;
+        assert_eq!(accounts.selected_account.as_deref(), Some("vault"));
+        assert!(accounts.select_account("missing").is_err());
+
+        let (_, profile) = config.profile(Some("readonly"))?;
+        assert!(profile.ensure_venues_enabled().is_err());
+        // defaults where the profile is silent
+        assert_eq!(profile.max_slippage(), DEFAULT_MAX_SLIPPAGE);
+        assert_eq!(profile.execution_slippage(), EXECUTION_SLIPPAGE);
+        assert_eq!(profile.binance.quote, QuoteAsset::Usdt);
+
+        assert!(config.profile(Some("missing")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_reports_problems() -> Result<()> {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.default.binance]
+            fees = { maker = 0.02, taker = 0.05 }
+
+            [profiles.default.hyperliquid]
+            api_url = "not a url"
+
+            [profiles.default.strategy]
+            funding_history_days = 30
+
+            [profiles.default.accounts.sub]
+            hyperliquid_sub_account = "0x12"
+            "#,
+        )?;
+        let (_, profile) = config.profile(None)?;
+
+        let problems = profile.validate(|key| (key == "MNEMONIC").then(|| "one two".to_string()));
+        assert_eq!(problems.len(), 7, "{problems:#?}");
+        assert!(problems[0].starts_with("BINANCE_API_KEY"));
+        assert!(problems[2].starts_with("MNEMONIC is not a valid"));
+        assert!(problems[3].starts_with("accounts.sub: Invalid hyperliquid address"));
+
+        // the sdk can't be pointed at a mock, orders would still go to mainnet
+        let (_, profile) = ConfigFile::parse(
+            "[profiles.default.hyperliquid]\nenabled = false\napi_url = \"http://127.0.0.1:9000\"",
+        )?
+        .profile(None)?;
+        let problems = profile.validate(|_| None);
+        assert!(problems
+            .iter()
+            .any(|p| p.starts_with("hyperliquid.api_url: The hyperliquid sdk")));
+
+        // typos are rejected rather than ignored
+        assert!(ConfigFile::parse("[profiles.default.strategy]\nmin_spred_apr = 10").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_leverage_settings_prefer_the_command() -> Result<()> {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.default.execution]
+            leverage = 3
+            isolated = true
+            "#,
+        )?;
+        let (_, profile) = config.profile(None)?;
+
+        let settings = |leverage, isolated| Some(LeverageSettings { leverage, isolated });
+        assert_eq!(profile.leverage_settings(None, None), settings(3, true));
+        // --cross switches an isolated profile back
+        assert_eq!(
+            profile.leverage_settings(Some(5), Some(false)),
+            settings(5, false)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_credential_files() -> Result<()> {
+        let config = ConfigFile::parse(
+            r#"
+            [profiles.default.credentials]
+            keystore = "no-such-keystore.json"
+
+            [profiles.default.accounts.sub]
+            secrets = "no-such-secrets.json"
+            "#,
+        )?;
+        let (_, profile) = config.profile(None)?;
+
+        assert_eq!(
+            profile.missing_credential_files(),
+            [
+                "credentials.keystore: no-such-keystore.json does not exist",
+                "accounts.sub.secrets: no-such-secrets.json does not exist",
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use ethers::{core::rand::thread_rng, signers::LocalWallet};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// exchange keys kept in an encrypted secrets file, same scrypt and aes format as json keystores
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Secrets {
+    pub binance_api_key: Option<String>,
+    pub binance_secret_key: Option<String>,
+    pub mnemonic: Option<String>,
+}
+
+/// keystores are written as a directory and file name
+fn split_path(path: &Path) -> Result<(&Path, &str)> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .with_context(|| format!("{} is not a file path", path.display()))?;
+
+    Ok((dir, name))
+}
+
+pub fn encrypt_secrets(path: &Path, secrets: &Secrets, passphrase: &str) -> Result<()> {
+    let (dir, name) = split_path(path)?;
+
+    eth_keystore::encrypt_key(
+        dir,
+        &mut thread_rng(),
+        serde_json::to_vec(secrets)?,
+        passphrase,
+        Some(name),
+    )
+    .with_context(|| format!("Could not write {}", path.display()))?;
+
+    Ok(())
+}
+
+/// json keystore of `wallet`, readable by `LocalWallet::decrypt_keystore` and other ethereum tooling
+pub fn encrypt_wallet(path: &Path, wallet: &LocalWallet, passphrase: &str) -> Result<()> {
+    let (dir, name) = split_path(path)?;
+
+    LocalWallet::encrypt_keystore(
+        dir,
+        &mut thread_rng(),
+        wallet.signer().to_bytes(),
+        passphrase,
+        Some(name),
+    )
+    .with_context(|| format!("Could not write {}", path.display()))?;
+
+    Ok(())
+}
+
+pub fn decrypt_secrets(path: &Path, passphrase: &str) -> Result<Secrets> {
+    let plaintext = eth_keystore::decrypt_key(path, passphrase)
+        .with_context(|| format!("Could not unlock {}", path.display()))?;
+
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Credentials;
+    use ethers::signers::Signer;
+
+    #[test]
+    fn test_secrets_round_trip() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("secrets-{}.json", std::process::id()));
+        let secrets = Secrets {
+            binance_api_key: Some("key".to_string()),
+            binance_secret_key: Some("secret".to_string()),
+            mnemonic: None,
+        };
+
+        encrypt_secrets(&path, &secrets, "correct horse")?;
+        assert!(!std::fs::read_to_string(&path)?.contains("secret"));
+
+        assert_eq!(decrypt_secrets(&path, "correct horse")?, secrets);
+        assert!(decrypt_secrets(&path, "wrong").is_err());
+
+        std::fs::remove_file(path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlock_credentials() -> Result<()> {
+        let dir = std::env::temp_dir();
+        let keystore = dir.join(format!("keystore-{}.json", std::process::id()));
+        let secrets = dir.join(format!("unlock-secrets-{}.json", std::process::id()));
+
+        let wallet = LocalWallet::new(&mut thread_rng());
+        encrypt_wallet(&keystore, &wallet, "pass")?;
+        let stored = Secrets {
+            binance_api_key: Some("stored-key".to_string()),
+            binance_secret_key: Some("stored-secret".to_string()),
+            mnemonic: None,
+        };
+        encrypt_secrets(&secrets, &stored, "pass")?;
+
+        let mut credentials = Credentials {
+            binance_api_key: Some("plain-key".to_string()),
+            keystore: Some(keystore.clone()),
+            secrets: Some(secrets.clone()),
+            ..Default::default()
+        };
+        assert!(credentials.is_encrypted());
+        assert!(credentials.clone().unlock("wrong").is_err());
+
+        credentials.unlock("pass")?;
+        assert_eq!(
+            credentials.wallet.map(|w| w.address()),
+            Some(wallet.address())
+        );
+        // keys set in plain text win over the file
+        assert_eq!(credentials.binance_api_key.as_deref(), Some("plain-key"));
+        assert_eq!(
+            credentials.binance_secret_key.as_deref(),
+            Some("stored-secret")
+        );
+
+        std::fs::remove_file(keystore)?;
+        std::fs::remove_file(secrets)?;
+
+        Ok(())
+    }
+}
@@ -1,4 +1,79 @@
+use crate::config::profile;
+use anyhow::{bail, Result};
+use hyperliquid_rust_sdk::BaseUrl;
+
 pub const MAX_DAYS_QUERY_FUNDING_HISTORY: i64 = 7;
-pub const HYPERLIQUID_FEE: f64 = 0.00035; // decimal form
-pub const BINANCE_FEE: f64 = 0.0005; // decimal form
+pub const HYPERLIQUID_FEE: f64 = 0.00035; // decimal form, base tier taker
+pub const BINANCE_FEE: f64 = 0.0005; // decimal form, base tier taker
+pub const HYPERLIQUID_MAKER_FEE: f64 = 0.0001; // decimal form, base tier
+pub const BINANCE_MAKER_FEE: f64 = 0.0002; // decimal form, base tier
 pub const EXECUTION_SLIPPAGE: f64 = 0.005; // decimal form
+pub const DEFAULT_MAX_SLIPPAGE: f64 = 5.0; // bps over the quote
+pub const LIQUIDATION_RISK_DISTANCE: f64 = 0.15; // decimal form, move that puts a hedged leg at risk
+pub const MIN_DELTA_DRIFT_USD: f64 = 10.0; // hyperliquid's minimum order value
+pub const ALERT_INTERVAL: u64 = 60; // seconds between alert checks
+pub const MIN_REBALANCE_TRANSFER: f64 = 50.0; // usd, smaller imbalances aren't worth the fees
+pub const HYPERLIQUID_WITHDRAW_FEE: f64 = 1.0; // usdc
+pub const ARBITRUM_USDC: &str = "0xaf88d065e77c8cC2239327C5EDb3A432268e5831";
+pub const HYPERLIQUID_BRIDGE: &str = "0x2Df1c51E09aECF9cacB7bc98cB1742757f163dF7";
+
+/// the profile's url, else `key` from the environment, else `default`
+fn endpoint(key: &str, configured: &Option<String>, default: &str) -> String {
+    configured
+        .clone()
+        .or_else(|| std::env::var(key).ok())
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// binance futures rest endpoint, `BINANCE_API_URL` points it at a local mock exchange
+pub fn binance_api_url() -> String {
+    endpoint(
+        "BINANCE_API_URL",
+        &profile().binance.api_url,
+        "https://fapi.binance.com",
+    )
+}
+
+/// binance spot and wallet rest endpoint, `BINANCE_SPOT_API_URL` points it at a local mock exchange
+pub fn binance_spot_api_url() -> String {
+    endpoint(
+        "BINANCE_SPOT_API_URL",
+        &profile().binance.spot_api_url,
+        "https://api.binance.com",
+    )
+}
+
+/// hyperliquid rest endpoint, `HYPERLIQUID_API_URL` points it at a local mock exchange
+pub fn hyperliquid_api_url() -> String {
+    endpoint(
+        "HYPERLIQUID_API_URL",
+        &profile().hyperliquid.api_url,
+        "https://api.hyperliquid.xyz",
+    )
+}
+
+/// network the hyperliquid sdk signs orders and queries on, the one `hyperliquid_api_url` points at
+pub fn hyperliquid_base_url() -> Result<BaseUrl> {
+    sdk_base_url(&hyperliquid_api_url())
+}
+
+/// the sdk only knows mainnet, testnet and a local node, any other url would silently hit mainnet
+pub fn sdk_base_url(url: &str) -> Result<BaseUrl> {
+    match url.trim_end_matches('/') {
+        "https://api.hyperliquid.xyz" => Ok(BaseUrl::Mainnet),
+        "https://api.hyperliquid-testnet.xyz" => Ok(BaseUrl::Testnet),
+        "http://localhost:3001" => Ok(BaseUrl::Localhost),
+        url => {
+            bail!("The hyperliquid sdk only reaches mainnet, testnet or localhost:3001, not {url}")
+        }
+    }
+}
+
+/// arbitrum json rpc, where usdc moves between binance and the hyperliquid bridge
+pub fn arbitrum_rpc_url() -> String {
+    endpoint(
+        "ARBITRUM_RPC_URL",
+        &profile().hyperliquid.arbitrum_rpc_url,
+        "https://arb1.arbitrum.io/rpc",
+    )
+}
@@ -0,0 +1,170 @@
+use funding_rate_arbitrage::{
+    balances::{Balance, HedgeSummary, OpenHedge},
+    compare_funding_rates::JointFundingRate,
+};
+use ratatui::crossterm::event::KeyCode;
+
+/// what the event loop has to do after a key press
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    None,
+    Quote(String, OpenHedge),
+    Close(String, OpenHedge),
+}
+
+#[derive(Default)]
+pub struct App {
+    pub funding_rates: Vec<JointFundingRate>,
+    pub hedges: Vec<HedgeSummary>,
+    pub balance: Option<Balance>,
+    pub selected: usize,
+    pub pending_close: Option<String>, // token waiting for confirmation
+    pub status: String,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn selected_hedge(&self) -> Option<&HedgeSummary> {
+        self.hedges.get(self.selected)
+    }
+
+    pub fn set_hedges(&mut self, hedges: Vec<HedgeSummary>) {
+        self.hedges = hedges;
+        self.selected = self.selected.min(self.hedges.len().saturating_sub(1));
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> Action {
+        if let Some(token) = self.pending_close.take() {
+            if key != KeyCode::Char('y') {
+                self.status = format!("Close of {token} cancelled");
+                return Action::None;
+            }
+
+            return match self.hedges.iter().find(|h| h.token == token) {
+                Some(HedgeSummary {
+                    hedge: Some(hedge), ..
+                }) => {
+                    self.status = format!("Closing {token}...");
+                    Action::Close(token, hedge.clone())
+                }
+                _ => {
+                    self.status = format!("{token} is no longer hedged");
+                    Action::None
+                }
+            };
+        }
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') if !self.hedges.is_empty() => {
+                self.selected = (self.selected + 1) % self.hedges.len();
+            }
+            KeyCode::Up | KeyCode::Char('k') if !self.hedges.is_empty() => {
+                self.selected = (self.selected + self.hedges.len() - 1) % self.hedges.len();
+            }
+            KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char('c') => {
+                let Some(summary) = self.selected_hedge().cloned() else {
+                    self.status = "No open positions".to_string();
+                    return Action::None;
+                };
+                let Some(hedge) = summary.hedge else {
+                    self.status = format!("{} is not hedged", summary.token);
+                    return Action::None;
+                };
+
+                if key != KeyCode::Char('c') {
+                    return Action::Quote(summary.token, hedge);
+                }
+
+                self.status = format!("Close {}? press y to confirm", summary.token);
+                self.pending_close = Some(summary.token);
+            }
+            _ => {}
+        }
+
+        Action::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use funding_rate_arbitrage::util::{Platform, QuoteAsset};
+
+    fn app() -> App {
+        let summary = |token: &str, hedge| HedgeSummary {
+            token: token.to_string(),
+            hedge,
+            notional: 0.0,
+            pnl: 0.0,
+            funding: 0.0,
+            liquidation_distance: None,
+        };
+        let hedge = OpenHedge {
+            long: Platform::Binance,
+            size: 10.0,
+            binance_quote: QuoteAsset::Usdt,
+        };
+
+        let mut app = App::default();
+        app.set_hedges(vec![
+            summary("ETH", Some(hedge.clone())),
+            summary("TIA", None),
+            summary("WIF", Some(hedge)),
+        ]);
+        app
+    }
+
+    #[test]
+    fn test_selection_wraps() {
+        let mut app = app();
+
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.selected_hedge().unwrap().token, "WIF");
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char('j'));
+        assert_eq!(app.selected_hedge().unwrap().token, "TIA");
+
+        // shrinking the list keeps the selection in range
+        app.selected = 2;
+        app.set_hedges(vec![]);
+        assert!(app.selected_hedge().is_none());
+        assert_eq!(app.handle_key(KeyCode::Down), Action::None);
+    }
+
+    #[test]
+    fn test_quote_selected_hedge() {
+        let mut app = app();
+
+        assert!(matches!(
+            app.handle_key(KeyCode::Enter),
+            Action::Quote(token, _) if token == "ETH"
+        ));
+
+        // unhedged positions can't be quoted as a hedge
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.handle_key(KeyCode::Enter), Action::None);
+        assert_eq!(app.status, "TIA is not hedged");
+    }
+
+    #[test]
+    fn test_close_requires_confirmation() {
+        let mut app = app();
+
+        assert_eq!(app.handle_key(KeyCode::Char('c')), Action::None);
+        assert_eq!(app.pending_close.as_deref(), Some("ETH"));
+        assert!(matches!(
+            app.handle_key(KeyCode::Char('y')),
+            Action::Close(token, _) if token == "ETH"
+        ));
+
+        // anything other than `y` cancels
+        app.handle_key(KeyCode::Char('c'));
+        assert_eq!(app.handle_key(KeyCode::Char('q')), Action::None);
+        assert!(!app.should_quit);
+        assert!(app.pending_close.is_none());
+
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.should_quit);
+    }
+}
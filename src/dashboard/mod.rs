@@ -0,0 +1,177 @@
+mod app;
+mod ui;
+
+use anyhow::Result;
+use app::{Action, App};
+use funding_rate_arbitrage::{
+    balances::{
+        retrieve_account_balance, retrieve_account_open_positions, summarize_hedges, Balance,
+        HedgeSummary,
+    },
+    config::profile,
+    execution::close_hedge,
+    fees::retrieve_fee_schedule,
+    live_funding_rates::LiveFundingRates,
+    live_orderbooks::{live_orderbooks, LiveOrderbooks},
+    quote::{calculate_hedge_cost, quote_hedge_exit},
+    util::QuoteAsset,
+};
+use futures::StreamExt;
+use ratatui::{
+    crossterm::event::{Event, EventStream, KeyEventKind},
+    DefaultTerminal,
+};
+use std::{collections::HashSet, time::Duration};
+use tokio::{
+    select,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    try_join,
+};
+
+const ACCOUNT_REFRESH: Duration = Duration::from_secs(15);
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+enum Update {
+    Account {
+        balance: Balance,
+        hedges: Vec<HedgeSummary>,
+    },
+    Status(String),
+}
+
+pub async fn run_dashboard() -> Result<()> {
+    let live_funding_rates = LiveFundingRates::start().await?;
+    let live_orderbooks = live_orderbooks().clone();
+
+    let (tx, rx) = unbounded_channel();
+    tokio::spawn(refresh_account(tx.clone()));
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, live_funding_rates, live_orderbooks, tx, rx).await;
+    ratatui::restore();
+
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    live_funding_rates: LiveFundingRates,
+    live_orderbooks: LiveOrderbooks,
+    tx: UnboundedSender<Update>,
+    mut rx: UnboundedReceiver<Update>,
+) -> Result<()> {
+    let mut app = App::default();
+    let mut subscribed = HashSet::new();
+    let mut events = EventStream::new();
+    let mut redraw = tokio::time::interval(REDRAW_INTERVAL);
+
+    while !app.should_quit {
+        for hedge in &app.hedges {
+            let quote = hedge_quote(hedge);
+            if subscribed.insert((hedge.token.clone(), quote)) {
+                live_orderbooks.subscribe(&hedge.token, quote);
+            }
+        }
+
+        app.funding_rates = live_funding_rates.joint_funding_rates();
+        terminal.draw(|frame| ui::render(frame, &app, &live_orderbooks))?;
+
+        // live books and funding rates change without an event, redraw them on a tick
+        let key = select! {
+            _ = redraw.tick() => continue,
+            Some(update) = rx.recv() => {
+                match update {
+                    Update::Account { balance, hedges } => {
+                        app.balance = Some(balance);
+                        app.set_hedges(hedges);
+                    }
+                    Update::Status(status) => app.status = status,
+                }
+                continue;
+            }
+            event = events.next() => match event.transpose()? {
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                Some(_) => continue,
+                None => break,
+            },
+        };
+
+        match app.handle_key(key.code) {
+            Action::None => {}
+            Action::Quote(token, hedge) => {
+                let quote = hedge.binance_quote;
+                let Some((b, hl)) = live_orderbooks.orderbooks(&token, quote) else {
+                    app.status = format!("Orderbooks for {token} are not ready yet");
+                    continue;
+                };
+
+                // fee lookup hits both venues, keep the ui responsive meanwhile
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let quote = async {
+                        let fees = retrieve_fee_schedule(&token, quote).await?;
+                        quote_hedge_exit(&b, &hl, hedge.long, hedge.size, &fees)
+                    };
+                    let status = match quote.await {
+                        Ok((exit_long, exit_short)) => {
+                            let cost = calculate_hedge_cost(&exit_long, &exit_short);
+                            format!(
+                                "Exit {token}: {:.4} bps (${:.2})",
+                                cost * 10_000.0,
+                                cost * exit_long.notional
+                            )
+                        }
+                        Err(e) => format!("Could not quote {token}. {e}"),
+                    };
+                    tx.send(Update::Status(status)).ok();
+                });
+            }
+            Action::Close(token, hedge) => {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let status = match close_hedge(&token, &hedge).await {
+                        Ok((b, hl)) => format!(
+                            "Closed {token}: binance {:.4} @ {} / hyperliquid {:.4} @ {}",
+                            b.size, b.avg_price, hl.size, hl.avg_price
+                        ),
+                        Err(e) => format!("Could not close {token}. {e}"),
+                    };
+                    tx.send(Update::Status(status)).ok();
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// binance perpetual of the hedge, the profile's when a leg is missing
+fn hedge_quote(summary: &HedgeSummary) -> QuoteAsset {
+    summary
+        .hedge
+        .as_ref()
+        .map_or(profile().binance.quote, |hedge| hedge.binance_quote)
+}
+
+async fn refresh_account(tx: UnboundedSender<Update>) {
+    let mut interval = tokio::time::interval(ACCOUNT_REFRESH);
+
+    loop {
+        interval.tick().await;
+
+        let update = match try_join!(
+            retrieve_account_balance(),
+            retrieve_account_open_positions()
+        ) {
+            Ok((balance, positions)) => Update::Account {
+                balance,
+                hedges: summarize_hedges(&positions),
+            },
+            Err(e) => Update::Status(format!("Could not refresh account. {e}")),
+        };
+
+        if tx.send(update).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use super::{app::App, hedge_quote};
+use funding_rate_arbitrage::{
+    constants::LIQUIDATION_RISK_DISTANCE, live_orderbooks::LiveOrderbooks,
+};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+
+const TOP_FUNDING_RATES: usize = 8;
+
+fn fmt_apr(hourly_rate: f64) -> String {
+    format!("{:.2}%", hourly_rate * 24.0 * 365.0 * 100.0)
+}
+
+fn fmt_usd(amount: f64) -> String {
+    format!("${amount:.2}")
+}
+
+pub fn render(frame: &mut Frame, app: &App, orderbooks: &LiveOrderbooks) {
+    let [top, middle, bottom, footer] = Layout::vertical([
+        Constraint::Length(TOP_FUNDING_RATES as u16 + 3),
+        Constraint::Min(6),
+        Constraint::Length(8),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+    let [funding_area, balance_area] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+
+    let funding_rows = app.funding_rates.iter().take(TOP_FUNDING_RATES).map(|jfr| {
+        Row::new(vec![
+            jfr.name.clone(),
+            fmt_apr(jfr.binance_funding_rate),
+            fmt_apr(jfr.hyperliquid_funding_rate),
+            fmt_apr(jfr.funding_rate_difference),
+        ])
+    });
+    let funding_table = Table::new(funding_rows, [Constraint::Ratio(1, 4); 4])
+        .header(Row::new(vec!["Coin", "Binance Fr", "Hl Fr", "Fr Diff"]).bold())
+        .block(Block::bordered().title("Funding spreads (apr)"));
+    frame.render_widget(funding_table, funding_area);
+
+    let balance_rows = app.balance.iter().flat_map(|b| {
+        let total = b.binance_balance + b.hyperliquid_balance;
+        let total_open = b.binance_open_positions + b.hyperliquid_open_positions;
+        vec![
+            Row::new(vec![
+                "Binance".to_string(),
+                fmt_usd(b.binance_balance),
+                format!("{:.2}", b.binance_open_positions / b.binance_balance),
+            ]),
+            Row::new(vec![
+                "Hyperliquid".to_string(),
+                fmt_usd(b.hyperliquid_balance),
+                format!(
+                    "{:.2}",
+                    b.hyperliquid_open_positions / b.hyperliquid_balance
+                ),
+            ]),
+            Row::new(vec![
+                "Total".to_string(),
+                fmt_usd(total),
+                format!("{:.2}", total_open / total),
+            ]),
+        ]
+    });
+    let balance_table = Table::new(balance_rows, [Constraint::Ratio(1, 3); 3])
+        .header(Row::new(vec!["Venue", "Margin", "Leverage"]).bold())
+        .block(Block::bordered().title("Accounts"));
+    frame.render_widget(balance_table, balance_area);
+
+    let hedge_rows = app.hedges.iter().map(|h| {
+        let long = match &h.hedge {
+            Some(hedge) => format!("{:?}", hedge.long),
+            None => "unhedged".to_string(),
+        };
+        let liquidation = match h.liquidation_distance {
+            Some(distance) if distance < LIQUIDATION_RISK_DISTANCE => {
+                Cell::from(format!("{:.2}%", distance * 100.0)).red()
+            }
+            Some(distance) => Cell::from(format!("{:.2}%", distance * 100.0)),
+            None => Cell::from("-"),
+        };
+        Row::new(vec![
+            Cell::from(h.token.clone()),
+            Cell::from(long),
+            Cell::from(fmt_usd(h.notional)),
+            Cell::from(fmt_usd(h.pnl)),
+            Cell::from(fmt_usd(h.funding)),
+            Cell::from(fmt_usd(h.pnl + h.funding)),
+            liquidation,
+        ])
+    });
+    let hedge_table = Table::new(hedge_rows, [Constraint::Ratio(1, 7); 7])
+        .header(
+            Row::new(vec![
+                "Token", "Long", "Notional", "Pnl", "Funding", "Net", "Liq.",
+            ])
+            .bold(),
+        )
+        .block(Block::bordered().title("Open hedges"))
+        .highlight_style(Style::new().reversed());
+    let mut hedge_state = TableState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(hedge_table, middle, &mut hedge_state);
+
+    let depth_rows = app.hedges.iter().filter_map(|h| {
+        let (b, hl) = orderbooks.orderbooks(&h.token, hedge_quote(h))?;
+        let (b_bid, b_ask) = b.get_total_depth();
+        let (hl_bid, hl_ask) = hl.get_total_depth();
+        Some(Row::new(vec![
+            h.token.clone(),
+            fmt_usd(b_bid),
+            fmt_usd(b_ask),
+            fmt_usd(hl_bid),
+            fmt_usd(hl_ask),
+        ]))
+    });
+    let depth_table = Table::new(depth_rows, [Constraint::Ratio(1, 5); 5])
+        .header(
+            Row::new(vec![
+                "Token",
+                "Binance Bids",
+                "Binance Asks",
+                "Hl Bids",
+                "Hl Asks",
+            ])
+            .bold(),
+        )
+        .block(Block::bordered().title("Depth"));
+    frame.render_widget(depth_table, bottom);
+
+    let help = "q quit • ↑/↓ select • enter quote exit • c close hedge";
+    frame.render_widget(
+        Paragraph::new(vec![app.status.clone().into(), help.dim().into()]),
+        footer,
+    );
+}
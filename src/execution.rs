@@ -0,0 +1,406 @@
+use crate::{
+    balances::{calculate_delta_drifts, retrieve_account_open_positions, DeltaDrift, OpenHedge},
+    binance::{
+        self, funding_rates::retrieve_binance_hourly_funding_rates, get_floored_quantity,
+        get_trimmed_quantity, retrieve_step_size, set_binance_leverage, set_binance_margin_type,
+    },
+    config::profile,
+    constants::{hyperliquid_base_url, MIN_DELTA_DRIFT_USD},
+    fees::retrieve_fee_schedule,
+    hyperliquid::{self, funding_rates::retrieve_hl_hourly_funding_rates, set_hl_leverage},
+    metrics::metrics,
+    orderbook::latest_orderbooks,
+    quote::{quote_delta_adjustment, quote_hedge, FillTarget, Quote},
+    symbols::{load_symbols, Asset},
+    token_price::get_mid_price,
+    util::{calculate_pct_difference, OrderFilled, Platform, QuoteAsset, Side},
+};
+use anyhow::{bail, Context, Result};
+use hyperliquid_rust_sdk::InfoClient;
+use serde::Serialize;
+use tokio::try_join;
+
+/// fills of a newly opened hedge next to what the quote expected
+#[derive(Debug, Serialize)]
+pub struct HedgeExecution {
+    pub orders: [OrderFilled; 2], // binance first
+    pub quote_slippage: f64,      // bps
+    pub real_slippage: f64,       // bps
+    pub quote_spread: f64,        // bps
+    pub real_spread: f64,         // bps
+}
+
+/// leverage and margin mode set on both venues before opening
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LeverageSettings {
+    pub leverage: u32,
+    pub isolated: bool, // cross margin otherwise
+}
+
+/// market opens both legs of a hedge on `token`, longing on `long` and trading the `quote` perpetual on binance.
+/// fails before sending any order when the quoted slippage exceeds `max_slippage` (bps).
+/// without `leverage` both accounts keep their current settings for `token`
+pub async fn open_hedge(
+    token: &str,
+    quote: QuoteAsset,
+    target: FillTarget,
+    long: Platform,
+    max_slippage: f64,
+    leverage: Option<LeverageSettings>,
+) -> Result<HedgeExecution> {
+    let asset = load_symbols().await?.asset(token);
+
+    let ((b, hl), step_size, fees) = try_join!(
+        latest_orderbooks(token, quote),
+        retrieve_step_size(asset.symbol(Platform::Binance), quote),
+        retrieve_fee_schedule(token, quote)
+    )?;
+
+    let b_mp = get_mid_price(&b)?;
+    let hl_mp = get_mid_price(&hl)?;
+
+    let (buy_quote, sell_quote) = quote_hedge(&b, &hl, long, target, &fees)?;
+
+    let total_slippage_bps = (buy_quote.slippage + sell_quote.slippage) * 10_000.0;
+
+    if total_slippage_bps > max_slippage {
+        bail!(
+            "Total slippage of {:.4} exceeds maximum slippage of {:.4}.",
+            total_slippage_bps,
+            max_slippage
+        )
+    }
+
+    if let Some(settings) = leverage {
+        set_leverage(token, quote, settings).await?;
+    }
+
+    // both legs trade the quoted size, rounded to what both venues accept
+    let leg_size = trim_to_lot_size(&asset, buy_quote.size, step_size);
+    if leg_size == 0.0 {
+        bail!(
+            "{:.6} {token} is below the lot size of both venues",
+            buy_quote.size
+        )
+    }
+
+    let (b, h) = execute_both_legs(&asset, quote, leg_size, long == Platform::Binance).await?;
+
+    // quote costs (bps)
+    let quote_slippage = total_slippage_bps;
+    let quote_spread = -(((sell_quote.expected_execution_price
+        - buy_quote.expected_execution_price)
+        / buy_quote.expected_execution_price)
+        * 10_000.0);
+
+    // real costs
+    let b_slippage = calculate_pct_difference(b.avg_price, b_mp);
+    let hl_slippage = calculate_pct_difference(h.avg_price, hl_mp);
+    let real_slippage = (b_slippage + hl_slippage) * 10_000.0; // bps
+    let real_spread = if b.side == Side::Buy {
+        -(((h.avg_price - b.avg_price) / b.avg_price) * 10_000.0)
+    } else {
+        -(((b.avg_price - h.avg_price) / h.avg_price) * 10_000.0)
+    };
+
+    Ok(HedgeExecution {
+        orders: [b, h],
+        quote_slippage,
+        real_slippage,
+        quote_spread,
+        real_spread,
+    })
+}
+
+/// applies `settings` to `token` on both venues, binance on its `quote` perpetual,
+/// failing before any change when the leverage is above what either venue allows
+pub async fn set_leverage(
+    token: &str,
+    quote: QuoteAsset,
+    settings: LeverageSettings,
+) -> Result<()> {
+    let (b_token, hl_token) = load_symbols().await?.venue_symbols(token);
+
+    let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
+    let (b_rates, hl_rates) = try_join!(
+        retrieve_binance_hourly_funding_rates(),
+        retrieve_hl_hourly_funding_rates(&info_client)
+    )?;
+
+    let b_max_leverage = b_rates
+        .iter()
+        .find(|t| t.name == b_token && t.quote == quote)
+        .map(|t| t.max_leverage)
+        .with_context(|| format!("{} is not listed on Binance", quote.pair(&b_token)))?;
+    let hl_max_leverage = hl_rates
+        .iter()
+        .find(|t| t.name == hl_token)
+        .map(|t| t.max_leverage)
+        .with_context(|| format!("{hl_token} is not listed on Hyperliquid"))?;
+
+    validate_leverage(settings.leverage, b_max_leverage, hl_max_leverage)?;
+
+    try_join!(
+        async {
+            set_binance_margin_type(&b_token, quote, settings.isolated).await?;
+            set_binance_leverage(&b_token, quote, settings.leverage).await
+        },
+        set_hl_leverage(&hl_token, settings.leverage, settings.isolated)
+    )?;
+
+    Ok(())
+}
+
+fn validate_leverage(leverage: u32, b_max_leverage: f64, hl_max_leverage: f64) -> Result<()> {
+    let max_leverage = b_max_leverage.min(hl_max_leverage).floor();
+
+    if leverage == 0 {
+        bail!("Leverage must be at least 1x")
+    }
+    if f64::from(leverage) > max_leverage {
+        bail!(
+            "Leverage of {leverage}x exceeds the maximum of {max_leverage}x \
+             (Binance {b_max_leverage}x, Hyperliquid {hl_max_leverage}x)"
+        )
+    }
+
+    Ok(())
+}
+
+/// market closes both legs of `hedge`, first element is the binance fill
+pub async fn close_hedge(token: &str, hedge: &OpenHedge) -> Result<(OrderFilled, OrderFilled)> {
+    let asset = load_symbols().await?.asset(token);
+
+    let quote = hedge.binance_quote;
+    let step_size = retrieve_step_size(asset.symbol(Platform::Binance), quote).await?;
+    // never more than is open, rounding up would open a sliver the other way
+    let size = get_floored_quantity(hedge.size, lot_size(&asset, step_size));
+
+    // buy back the short leg and sell the long one
+    let is_binance_buy = hedge.long == Platform::Hyperliquid;
+
+    execute_both_legs(&asset, quote, size, is_binance_buy).await
+}
+
+/// single order bringing both legs of a token back to the same size
+#[derive(Debug, Serialize)]
+pub struct HedgeAdjustment {
+    pub drift: DeltaDrift,
+    pub quote: Quote,
+    pub order: Option<OrderFilled>, // none on a dry run
+}
+
+/// trades the net size of `token` on the cheaper venue, only quoting it when `dry_run`
+pub async fn adjust_hedge(token: &str, dry_run: bool) -> Result<HedgeAdjustment> {
+    let asset = load_symbols().await?.asset(token);
+
+    let positions = retrieve_account_open_positions().await?;
+    // adjust the binance leg on the perpetual it is open on
+    let b_quote = positions
+        .iter()
+        .find(|p| p.platform == Platform::Binance && p.coin == asset.symbol(Platform::Binance))
+        .map_or(profile().binance.quote, |p| p.quote);
+
+    let ((b, hl), step_size, fees) = try_join!(
+        latest_orderbooks(token, b_quote),
+        retrieve_step_size(asset.symbol(Platform::Binance), b_quote),
+        retrieve_fee_schedule(token, b_quote)
+    )?;
+
+    let Some(drift) = calculate_delta_drifts(&positions)
+        .into_iter()
+        .find(|drift| drift.token == token)
+    else {
+        bail!("No open position for {token}")
+    };
+
+    if drift.net_usd.abs() < MIN_DELTA_DRIFT_USD {
+        bail!(
+            "{token} is already delta neutral, net exposure is ${:.2}",
+            drift.net_usd
+        )
+    }
+
+    // each venue trades the net size trimmed to its own lot
+    let b_size = get_trimmed_quantity(drift.net_size, step_size * asset.scale(Platform::Binance));
+    let hl_size = hl_lot_size(&asset).map_or(drift.net_size, |lot| {
+        get_trimmed_quantity(drift.net_size, lot)
+    });
+    let quote = quote_delta_adjustment(&b, &hl, (b_size, hl_size), &fees)
+        .with_context(|| format!("Net size of {:.6} {token}", drift.net_size))?;
+    let size = quote.size;
+    if dry_run {
+        return Ok(HedgeAdjustment {
+            drift,
+            quote,
+            order: None,
+        });
+    }
+
+    // buy when the short side is larger
+    let is_buy = drift.net_size < 0.0;
+    let symbol = asset.symbol(quote.platform);
+    let scale = asset.scale(quote.platform);
+    let order = match quote.platform {
+        Platform::Binance => {
+            metrics()
+                .track(
+                    "binance_order",
+                    binance::execute_mkt_order(symbol, b_quote, size / scale, is_buy),
+                )
+                .await?
+        }
+        Platform::Hyperliquid => {
+            metrics()
+                .track(
+                    "hyperliquid_order",
+                    hyperliquid::execute_mkt_order(symbol, hl_contracts(&asset, size), is_buy),
+                )
+                .await?
+        }
+    };
+
+    Ok(HedgeAdjustment {
+        drift,
+        quote,
+        order: Some(order.to_asset_units(scale)),
+    })
+}
+
+/// `size` units of `asset` rounded to the nearest lot both venues accept
+fn trim_to_lot_size(asset: &Asset, size: f64, step_size: f64) -> f64 {
+    get_trimmed_quantity(size, lot_size(asset, step_size))
+}
+
+/// lot both venues accept in units of `asset`, whole steps of binance contracts
+/// and no more decimals than hyperliquid allows for its contracts
+fn lot_size(asset: &Asset, step_size: f64) -> f64 {
+    let b_lot = step_size * asset.scale(Platform::Binance);
+    match hl_lot_size(asset) {
+        Some(hl_lot) => common_lot(b_lot, hl_lot),
+        None => b_lot,
+    }
+}
+
+/// smallest hyperliquid order in units of `asset`, none when its size decimals are unknown
+fn hl_lot_size(asset: &Asset) -> Option<f64> {
+    let sz_decimals = asset.hyperliquid.as_ref()?.sz_decimals?;
+    Some(10f64.powi(-(sz_decimals as i32)) * asset.scale(Platform::Hyperliquid))
+}
+
+/// smallest size that is a whole number of both lots
+fn common_lot(a: f64, b: f64) -> f64 {
+    // in billionths so lots from 0.00000001 to millions are integers
+    let (a, b) = ((a * 1e9).round() as u64, (b * 1e9).round() as u64);
+    let gcd = |mut x: u64, mut y: u64| {
+        while y != 0 {
+            (x, y) = (y, x % y);
+        }
+        x
+    };
+
+    (a / gcd(a, b) * b) as f64 / 1e9
+}
+
+/// `size` units of `asset` in hyperliquid contracts, without float noise past its size decimals
+fn hl_contracts(asset: &Asset, size: f64) -> f64 {
+    let contracts = size / asset.scale(Platform::Hyperliquid);
+
+    match asset
+        .hyperliquid
+        .as_ref()
+        .and_then(|venue| venue.sz_decimals)
+    {
+        Some(sz_decimals) => get_trimmed_quantity(contracts, 10f64.powi(-(sz_decimals as i32))),
+        None => contracts,
+    }
+}
+
+/// market orders in opposite directions on both platforms for `size` units of `asset`,
+/// timed for `/metrics`. fills come back in units of the asset
+async fn execute_both_legs(
+    asset: &Asset,
+    quote: QuoteAsset,
+    size: f64,
+    is_binance_buy: bool,
+) -> Result<(OrderFilled, OrderFilled)> {
+    let b_scale = asset.scale(Platform::Binance);
+    let hl_scale = asset.scale(Platform::Hyperliquid);
+
+    let (b, hl) = try_join!(
+        metrics().track(
+            "binance_order",
+            binance::execute_mkt_order(
+                asset.symbol(Platform::Binance),
+                quote,
+                size / b_scale,
+                is_binance_buy
+            )
+        ),
+        metrics().track(
+            "hyperliquid_order",
+            hyperliquid::execute_mkt_order(
+                asset.symbol(Platform::Hyperliquid),
+                hl_contracts(asset, size),
+                !is_binance_buy
+            )
+        )
+    )?;
+
+    Ok((b.to_asset_units(b_scale), hl.to_asset_units(hl_scale)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_leverage() {
+        assert!(validate_leverage(5, 20.0, 10.0).is_ok());
+        assert!(validate_leverage(10, 20.0, 10.0).is_ok());
+        // bounded by the venue allowing less
+        assert!(validate_leverage(11, 20.0, 10.0).is_err());
+        assert!(validate_leverage(3, 2.5, 50.0).is_err());
+        assert!(validate_leverage(0, 20.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_trim_to_step_size_in_binance_contracts() {
+        use crate::symbols::SymbolRegistry;
+
+        // binance lists 1000MOG contracts against plain MOG on hyperliquid
+        let registry = SymbolRegistry::new(["1000MOG", "ETH"], ["MOG", "ETH"]);
+
+        let mog = registry.asset("MOG");
+        assert_eq!(trim_to_lot_size(&mog, 12_345.0, 1.0), 12_000.0);
+
+        let eth = registry.asset("ETH");
+        assert_eq!(trim_to_lot_size(&eth, 1.234, 0.01), 1.23);
+    }
+
+    #[test]
+    fn test_trim_to_lot_size_of_both_venues() {
+        use crate::symbols::SymbolRegistry;
+
+        let registry = SymbolRegistry::with_sz_decimals(
+            ["DOGS", "ETH", "1000PEPE"],
+            [("kDOGS", Some(0)), ("ETH", Some(4)), ("kPEPE", Some(0))],
+        );
+
+        // whole kDOGS contracts are a thousand binance DOGS
+        let dogs = registry.asset("DOGS");
+        assert_eq!(trim_to_lot_size(&dogs, 12_345.0, 1.0), 12_000.0);
+        assert_eq!(hl_contracts(&dogs, 12_000.0), 12.0);
+
+        // binance is the coarser venue
+        let eth = registry.asset("ETH");
+        assert_eq!(trim_to_lot_size(&eth, 1.2345, 0.001), 1.235);
+        assert_eq!(trim_to_lot_size(&eth, 1.2345, 0.01), 1.23);
+
+        let pepe = registry.asset("PEPE");
+        assert_eq!(trim_to_lot_size(&pepe, 1234.56, 1.0), 1235.0);
+
+        assert_eq!(common_lot(0.001, 0.0001), 0.001);
+        assert_eq!(common_lot(0.4, 0.3), 1.2);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    binance::retrieve_binance_commission_rate,
+    config::{binance_api_key, profile},
+    constants::{BINANCE_FEE, BINANCE_MAKER_FEE, HYPERLIQUID_FEE, HYPERLIQUID_MAKER_FEE},
+    hyperliquid::{get_account_address, retrieve_hl_user_fees},
+    symbols::load_symbols,
+    util::{Platform, QuoteAsset},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::try_join;
+
+/// commission of one venue, decimal form
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TradingFees {
+    pub maker: f64, // negative for rebates
+    pub taker: f64,
+}
+
+/// what both accounts pay per trade, quotes cross the book so they use the taker side
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FeeSchedule {
+    pub binance: TradingFees,
+    pub hyperliquid: TradingFees,
+}
+
+impl FeeSchedule {
+    pub fn venue(&self, platform: Platform) -> TradingFees {
+        match platform {
+            Platform::Binance => self.binance,
+            Platform::Hyperliquid => self.hyperliquid,
+        }
+    }
+
+    pub fn taker(&self, platform: Platform) -> f64 {
+        self.venue(platform).taker
+    }
+}
+
+/// base tier of both venues
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self {
+            binance: TradingFees {
+                maker: BINANCE_MAKER_FEE,
+                taker: BINANCE_FEE,
+            },
+            hyperliquid: TradingFees {
+                maker: HYPERLIQUID_MAKER_FEE,
+                taker: HYPERLIQUID_FEE,
+            },
+        }
+    }
+}
+
+/// commission rates of our accounts for `token`, binance on its `quote` perpetual.
+/// fees set in the profile win, a venue without credentials or an account address falls back to its base tier
+pub async fn retrieve_fee_schedule(token: &str, quote: QuoteAsset) -> Result<FeeSchedule> {
+    let (b_token, _) = load_symbols().await?.venue_symbols(token);
+    let base = FeeSchedule::default();
+
+    let binance = async {
+        if let Some(fees) = profile().binance.fees {
+            return Ok(fees);
+        }
+        if binance_api_key().is_err() {
+            return Ok(base.binance);
+        }
+        retrieve_binance_commission_rate(&b_token, quote).await
+    };
+    let hyperliquid = async {
+        if let Some(fees) = profile().hyperliquid.fees {
+            return Ok(fees);
+        }
+        if get_account_address().is_err() {
+            return Ok(base.hyperliquid);
+        }
+        retrieve_hl_user_fees().await
+    };
+
+    let (binance, hyperliquid) = try_join!(binance, hyperliquid)?;
+
+    Ok(FeeSchedule {
+        binance,
+        hyperliquid,
+    })
+}
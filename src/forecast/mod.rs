@@ -0,0 +1,8 @@
+mod model;
+mod ranking;
+
+pub use model::{backtest_spread, implied_hourly_funding, ForecastAccuracy, MeanReversion};
+pub use ranking::{
+    build_forecast_table, forecast_funding, rank_by_forecast, retrieve_funding_forecasts,
+    FundingForecast, FIT_WINDOW_HOURS, FORECAST_HISTORY_HOURS, HORIZON_24H, HORIZON_7D,
+};
@@ -0,0 +1,223 @@
+use serde::Serialize;
+
+/// interest component of the funding formula on both venues, per 8 hours
+const INTEREST_RATE_8H: f64 = 0.0001;
+/// funding strays at most this far from the premium, per funding interval
+const PREMIUM_CLAMP: f64 = 0.0005;
+/// a fitted persistence of 1 would never revert, keep forecasts bounded
+const MAX_PERSISTENCE: f64 = 0.999;
+
+/// hourly funding implied by a premium index sample of a contract paying every `interval_hours`.
+/// hyperliquid computes an 8h rate and pays an eighth of it every hour, so it uses 8
+pub fn implied_hourly_funding(premium: f64, interval_hours: f64) -> f64 {
+    let interest = INTEREST_RATE_8H * interval_hours / 8.0;
+    let rate = premium + (interest - premium).clamp(-PREMIUM_CLAMP, PREMIUM_CLAMP);
+    rate / interval_hours
+}
+
+/// first order autoregression of hourly funding around its mean
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MeanReversion {
+    pub mean: f64,        // hourly decimal form
+    pub persistence: f64, // share of a deviation from the mean left after one hour
+    pub last: f64,        // hourly decimal form, where the forecast starts
+}
+
+impl MeanReversion {
+    /// no reversion, `rate` forever
+    pub fn flat(rate: f64) -> Self {
+        Self {
+            mean: rate,
+            persistence: 0.0,
+            last: rate,
+        }
+    }
+
+    /// least squares fit over an hourly `series`, oldest first. none without at least two samples
+    pub fn fit(series: &[f64]) -> Option<Self> {
+        if series.len() < 2 {
+            return None;
+        }
+        let last = series[series.len() - 1];
+
+        let mean = series.iter().sum::<f64>() / series.len() as f64;
+        let (covariance, variance) = series
+            .windows(2)
+            .map(|w| (w[0] - mean, w[1] - mean))
+            .fold((0.0, 0.0), |(cov, var), (prev, next)| {
+                (cov + prev * next, var + prev * prev)
+            });
+
+        // a flat series has nothing to revert from
+        let persistence = if variance > 0.0 {
+            (covariance / variance).clamp(0.0, MAX_PERSISTENCE)
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            mean,
+            persistence,
+            last,
+        })
+    }
+
+    /// expected average hourly funding over the next `hours`
+    pub fn forecast(&self, hours: usize) -> f64 {
+        if hours == 0 {
+            return self.last;
+        }
+
+        let phi = self.persistence;
+        // deviations decay by `phi` every hour: phi + phi^2 + .. + phi^hours
+        let decay = if phi == 0.0 {
+            0.0
+        } else {
+            phi * (1.0 - phi.powi(hours as i32)) / (1.0 - phi)
+        };
+
+        self.mean + (self.last - self.mean) * decay / hours as f64
+    }
+}
+
+/// walk forward accuracy of forecasting a funding spread, hourly decimal form
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ForecastAccuracy {
+    pub samples: usize,
+    pub mae: f64,
+    pub rmse: f64,
+    pub naive_mae: f64, // holding the last spread, what ranking on the current spread assumes
+}
+
+/// fits both legs on `window` hours at every `step` hours of history and compares the forecast
+/// spread (`short` minus `long`) with the average realized over the next `hours`.
+/// both series are hourly, oldest first, and aligned on their latest sample.
+/// none when the history doesn't cover a single window and horizon
+pub fn backtest_spread(
+    long: &[f64],
+    short: &[f64],
+    window: usize,
+    hours: usize,
+    step: usize,
+) -> Option<ForecastAccuracy> {
+    let n = long.len().min(short.len());
+    let long = &long[long.len() - n..];
+    let short = &short[short.len() - n..];
+    let average = |series: &[f64]| series.iter().sum::<f64>() / series.len() as f64;
+
+    let mut errors = vec![];
+    let mut naive_errors = vec![];
+
+    let mut origin = window.max(2);
+    while hours > 0 && origin + hours <= n {
+        let history = origin - window.max(2)..origin;
+        let future = origin..origin + hours;
+
+        let forecast = MeanReversion::fit(&short[history.clone()])?.forecast(hours)
+            - MeanReversion::fit(&long[history])?.forecast(hours);
+        let realized = average(&short[future.clone()]) - average(&long[future]);
+        let naive = short[origin - 1] - long[origin - 1];
+
+        errors.push(forecast - realized);
+        naive_errors.push(naive - realized);
+        origin += step.max(1);
+    }
+
+    if errors.is_empty() {
+        return None;
+    }
+
+    let samples = errors.len();
+    let mean_abs = |errors: &[f64]| errors.iter().map(|e| e.abs()).sum::<f64>() / samples as f64;
+
+    Some(ForecastAccuracy {
+        samples,
+        mae: mean_abs(&errors),
+        rmse: (errors.iter().map(|e| e * e).sum::<f64>() / samples as f64).sqrt(),
+        naive_mae: mean_abs(&naive_errors),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    /// mean reverting series with deterministic noise
+    fn ar1(mean: f64, persistence: f64, start: f64, len: usize, seed: u64) -> Vec<f64> {
+        let mut state = seed;
+        let mut x = start;
+        let mut series = vec![];
+
+        for _ in 0..len {
+            series.push(x);
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let noise = ((state >> 33) as f64 / (1u64 << 31) as f64 - 0.5) * 0.00002;
+            x = mean + persistence * (x - mean) + noise;
+        }
+
+        series
+    }
+
+    #[test]
+    fn test_implied_hourly_funding() {
+        // premium within the clamp pays the interest rate
+        assert_relative_eq!(implied_hourly_funding(0.0002, 8.0), 0.0001 / 8.0);
+        // past the clamp the premium shows through
+        assert_relative_eq!(implied_hourly_funding(0.001, 8.0), 0.0005 / 8.0);
+        assert_relative_eq!(implied_hourly_funding(-0.001, 8.0), -0.0005 / 8.0);
+        // 4h contracts accrue half the interest per payment
+        assert_relative_eq!(implied_hourly_funding(0.0, 4.0), 0.00005 / 4.0);
+    }
+
+    #[test]
+    fn test_fit_recovers_mean_reversion() {
+        let series = ar1(0.00001, 0.9, 0.0002, 5000, 7);
+        let model = MeanReversion::fit(&series).unwrap();
+
+        assert_relative_eq!(model.mean, 0.00001, epsilon = 0.000005);
+        assert_relative_eq!(model.persistence, 0.9, epsilon = 0.05);
+        assert_eq!(model.last, *series.last().unwrap());
+
+        assert!(MeanReversion::fit(&[0.0001]).is_none());
+        // hyperliquid sits on the interest rate for days
+        let flat = MeanReversion::fit(&[0.0000125; 48]).unwrap();
+        assert_relative_eq!(flat.forecast(24), 0.0000125);
+    }
+
+    #[test]
+    fn test_forecast_decays_to_mean() {
+        let model = MeanReversion {
+            mean: 0.00001,
+            persistence: 0.5,
+            last: 0.00011,
+        };
+
+        // one hour keeps half the deviation
+        assert_relative_eq!(model.forecast(1), 0.00006);
+        // two hours average 0.5 and 0.25 of it
+        assert_relative_eq!(model.forecast(2), 0.00001 + 0.0001 * 0.375);
+        assert_relative_eq!(model.forecast(24 * 7), 0.00001, epsilon = 0.000001);
+        assert_eq!(model.forecast(0), 0.00011);
+
+        let flat = MeanReversion::flat(0.00003);
+        assert_eq!(flat.forecast(24), 0.00003);
+    }
+
+    #[test]
+    fn test_backtest_beats_snapshot_on_mean_reverting_spread() {
+        let long = ar1(0.0, 0.9, 0.0, 2000, 1);
+        let short = ar1(0.00002, 0.9, 0.0003, 2000, 2);
+
+        let accuracy = backtest_spread(&long, &short, 14 * 24, 24, 24).unwrap();
+
+        assert_eq!(accuracy.samples, (2000 - 14 * 24 - 24) / 24 + 1);
+        assert!(accuracy.mae < accuracy.naive_mae);
+        assert!(accuracy.rmse >= accuracy.mae);
+
+        // not enough history for one window and horizon
+        assert!(backtest_spread(&long[..100], &short, 14 * 24, 24, 24).is_none());
+    }
+}
@@ -0,0 +1,261 @@
+use super::model::{backtest_spread, implied_hourly_funding, MeanReversion};
+use crate::binance::{retrieve_binance_funding_info, retrieve_binance_hourly_premiums};
+use crate::break_even::calculate_directional_spread;
+use crate::compare_funding_rates::{compare_funding_rates, JointFundingRate};
+use crate::hyperliquid::retrieve_hl_hourly_premiums;
+use crate::symbols::load_symbols;
+use crate::util::{Platform, QuoteAsset};
+use anyhow::Result;
+use futures::future::try_join_all;
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+use tokio::try_join;
+
+/// hours of premium history behind every forecast
+pub const FORECAST_HISTORY_HOURS: u16 = 30 * 24;
+/// hours the model is fitted on, live and in the backtest
+pub const FIT_WINDOW_HOURS: usize = 14 * 24;
+pub const HORIZON_24H: usize = 24;
+pub const HORIZON_7D: usize = 7 * 24;
+const BACKTEST_STEP_HOURS: usize = 24;
+// current spreads forecast per row kept, a spread fading now can still lead the week
+const CANDIDATES_PER_FORECAST: usize = 3;
+// hyperliquid computes an 8h rate and pays an eighth of it every hour
+const HL_FUNDING_INTERVAL_HOURS: f64 = 8.0;
+
+/// expected funding of a hedge entered now, longing the venue that pays less at the moment
+#[derive(Debug, Clone, Serialize)]
+pub struct FundingForecast {
+    pub name: String,
+    pub binance_quote: QuoteAsset,
+    pub long: Platform,
+    pub current_rank: usize,  // by current spread, 1 is the best
+    pub forecast_rank: usize, // by 7d forecast spread
+    pub current_spread: f64,  // hourly decimal form
+    pub binance_24h: f64,     // expected average hourly funding
+    pub binance_7d: f64,
+    pub hyperliquid_24h: f64,
+    pub hyperliquid_7d: f64,
+    pub spread_24h: f64, // short leg minus long leg, hourly
+    pub spread_7d: f64,
+    pub mae_24h: Option<f64>, // backtest of the spread forecast, none without enough history
+    pub naive_mae_24h: Option<f64>, // same backtest holding the spread of the moment
+    pub mae_7d: Option<f64>,
+    pub naive_mae_7d: Option<f64>,
+}
+
+/// forecast of `jfr` from the hourly implied funding of both venues, oldest first.
+/// a venue without history keeps its current rate
+pub fn forecast_funding(
+    jfr: &JointFundingRate,
+    current_rank: usize,
+    binance_series: &[f64],
+    hyperliquid_series: &[f64],
+) -> FundingForecast {
+    let fit = |series: &[f64], current: f64| {
+        MeanReversion::fit(&series[series.len().saturating_sub(FIT_WINDOW_HOURS)..])
+            .unwrap_or(MeanReversion::flat(current))
+    };
+    let b_model = fit(binance_series, jfr.binance_funding_rate);
+    let hl_model = fit(hyperliquid_series, jfr.hyperliquid_funding_rate);
+
+    let long = if jfr.binance_funding_rate > jfr.hyperliquid_funding_rate {
+        Platform::Hyperliquid
+    } else {
+        Platform::Binance
+    };
+    let (long_series, short_series) = match long {
+        Platform::Binance => (binance_series, hyperliquid_series),
+        Platform::Hyperliquid => (hyperliquid_series, binance_series),
+    };
+
+    let spread = |hours: usize| {
+        let (b, hl) = (b_model.forecast(hours), hl_model.forecast(hours));
+        match long {
+            Platform::Binance => calculate_directional_spread(b, hl),
+            Platform::Hyperliquid => calculate_directional_spread(hl, b),
+        }
+    };
+    let backtest = |hours: usize| {
+        backtest_spread(
+            long_series,
+            short_series,
+            FIT_WINDOW_HOURS,
+            hours,
+            BACKTEST_STEP_HOURS,
+        )
+    };
+    let (accuracy_24h, accuracy_7d) = (backtest(HORIZON_24H), backtest(HORIZON_7D));
+
+    FundingForecast {
+        name: jfr.name.clone(),
+        binance_quote: jfr.binance_quote,
+        long,
+        current_rank,
+        forecast_rank: current_rank,
+        current_spread: jfr.funding_rate_difference,
+        binance_24h: b_model.forecast(HORIZON_24H),
+        binance_7d: b_model.forecast(HORIZON_7D),
+        hyperliquid_24h: hl_model.forecast(HORIZON_24H),
+        hyperliquid_7d: hl_model.forecast(HORIZON_7D),
+        spread_24h: spread(HORIZON_24H),
+        spread_7d: spread(HORIZON_7D),
+        mae_24h: accuracy_24h.map(|a| a.mae),
+        naive_mae_24h: accuracy_24h.map(|a| a.naive_mae),
+        mae_7d: accuracy_7d.map(|a| a.mae),
+        naive_mae_7d: accuracy_7d.map(|a| a.naive_mae),
+    }
+}
+
+/// best 7d forecast spread first, filling in `forecast_rank`
+pub fn rank_by_forecast(forecasts: &mut [FundingForecast]) {
+    forecasts.sort_by(|a, b| b.spread_7d.total_cmp(&a.spread_7d));
+
+    for (i, forecast) in forecasts.iter_mut().enumerate() {
+        forecast.forecast_rank = i + 1;
+    }
+}
+
+/// forecasts a wider set of the best current spreads and keeps the `top` 7d forecasts
+pub async fn retrieve_funding_forecasts(top: usize) -> Result<Vec<FundingForecast>> {
+    let (funding_rates, funding_info, symbols) = try_join!(
+        compare_funding_rates(),
+        retrieve_binance_funding_info(),
+        load_symbols()
+    )?;
+
+    let candidates = top.saturating_mul(CANDIDATES_PER_FORECAST);
+    let forecasts = funding_rates
+        .iter()
+        .take(candidates)
+        .enumerate()
+        .map(|(i, jfr)| {
+            let (b_token, hl_token) = symbols.venue_symbols(&jfr.name);
+            let interval = funding_info
+                .iter()
+                .find(|info| info.symbol == jfr.binance_quote.pair(&b_token))
+                .map_or(8.0, |info| info.funding_interval_hours);
+
+            async move {
+                let (mut b_premiums, hl_premiums) = try_join!(
+                    retrieve_binance_hourly_premiums(
+                        b_token,
+                        jfr.binance_quote,
+                        FORECAST_HISTORY_HOURS
+                    ),
+                    retrieve_hl_hourly_premiums(hl_token, FORECAST_HISTORY_HOURS)
+                )?;
+                // hyperliquid's history stops at the last settled hour, binance's includes the one in progress
+                b_premiums.pop();
+
+                let b_series: Vec<_> = b_premiums
+                    .iter()
+                    .map(|&premium| implied_hourly_funding(premium, interval))
+                    .collect();
+                let hl_series: Vec<_> = hl_premiums
+                    .iter()
+                    .map(|&premium| implied_hourly_funding(premium, HL_FUNDING_INTERVAL_HOURS))
+                    .collect();
+
+                anyhow::Ok(forecast_funding(jfr, i + 1, &b_series, &hl_series))
+            }
+        });
+
+    let mut forecasts = try_join_all(forecasts).await?;
+    rank_by_forecast(&mut forecasts);
+    forecasts.truncate(top);
+
+    Ok(forecasts)
+}
+
+pub fn build_forecast_table(forecasts: &[FundingForecast]) -> String {
+    // make them yearly and round two decimals
+    let apr = |hourly: f64| format!("{:.2}%", hourly * 24.0 * 365.0 * 100.0);
+    let accuracy = |mae: Option<f64>, naive_mae: Option<f64>| match (mae, naive_mae) {
+        (Some(mae), Some(naive_mae)) => format!("{} / {}", apr(mae), apr(naive_mae)),
+        _ => "-".to_string(),
+    };
+
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![Cell::new("Forecast APY")]));
+    table.add_row(Row::new(vec![
+        Cell::new("Coin"),
+        Cell::new("Long"),
+        Cell::new("Current"),
+        Cell::new("Binance 24h"),
+        Cell::new("Binance 7d"),
+        Cell::new("Hl 24h"),
+        Cell::new("Hl 7d"),
+        Cell::new("Spread 24h"),
+        Cell::new("Spread 7d"),
+        Cell::new("MAE 24h (naive)"),
+        Cell::new("MAE 7d (naive)"),
+        Cell::new("Rank (current)"),
+    ]));
+
+    for forecast in forecasts {
+        table.add_row(Row::new(vec![
+            Cell::new(&forecast.name),
+            Cell::new(&format!("{:?}", forecast.long)),
+            Cell::new(&apr(forecast.current_spread)),
+            Cell::new(&apr(forecast.binance_24h)),
+            Cell::new(&apr(forecast.binance_7d)),
+            Cell::new(&apr(forecast.hyperliquid_24h)),
+            Cell::new(&apr(forecast.hyperliquid_7d)),
+            Cell::new(&apr(forecast.spread_24h)),
+            Cell::new(&apr(forecast.spread_7d)),
+            Cell::new(&accuracy(forecast.mae_24h, forecast.naive_mae_24h)),
+            Cell::new(&accuracy(forecast.mae_7d, forecast.naive_mae_7d)),
+            Cell::new(&format!(
+                "{} ({})",
+                forecast.forecast_rank, forecast.current_rank
+            )),
+        ]));
+    }
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::joint_funding_rate as jfr;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_forecast_without_history_holds_current_rates() {
+        let forecast = forecast_funding(&jfr("ETH", 0.00005, -0.00001), 1, &[], &[]);
+
+        assert_eq!(forecast.long, Platform::Hyperliquid);
+        assert_relative_eq!(forecast.spread_24h, 0.00006);
+        assert_relative_eq!(forecast.spread_7d, forecast.current_spread);
+        assert_eq!(forecast.mae_24h, None);
+    }
+
+    #[test]
+    fn test_spike_reverts_in_forecast_ranking() {
+        // binance spiked in the last hour after two weeks around the interest rate
+        let wave = |h: usize| (h as f64 * 0.5).sin() * 0.000002;
+        let mut spiked: Vec<f64> = (0..FIT_WINDOW_HOURS).map(|h| 0.0000125 + wave(h)).collect();
+        spiked.push(0.0003);
+        // a steady spread on another token
+        let steady: Vec<f64> = (0..=FIT_WINDOW_HOURS).map(|h| 0.00008 + wave(h)).collect();
+        let interest = vec![0.0000125; FIT_WINDOW_HOURS + 1];
+
+        let mut forecasts = vec![
+            forecast_funding(&jfr("SPIKE", 0.0003, 0.0000125), 1, &spiked, &interest),
+            forecast_funding(&jfr("STEADY", 0.00008, 0.0000125), 2, &steady, &interest),
+        ];
+        rank_by_forecast(&mut forecasts);
+
+        // the spike wins on the snapshot but not over a week
+        assert_eq!(forecasts[0].name, "STEADY");
+        assert_eq!(
+            (forecasts[0].forecast_rank, forecasts[0].current_rank),
+            (1, 2)
+        );
+        assert!(forecasts[1].spread_7d < forecasts[1].current_spread / 2.0);
+        assert!(forecasts[1].spread_24h > forecasts[1].spread_7d);
+    }
+}
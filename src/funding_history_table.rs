@@ -2,6 +2,32 @@ use crate::util::calculate_effective_rate;
 use anyhow::Result;
 use numfmt::Formatter;
 use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DailyFundingRate {
+    pub day: usize,                    // 1 is the oldest day
+    pub binance_funding_rate: f64,     // daily fr decimal form
+    pub hyperliquid_funding_rate: f64, // daily fr decimal form
+    pub funding_rate_difference: f64,
+}
+
+pub fn join_daily_funding_rates(
+    binance_daily_rates: &[f64],
+    hl_daily_rates: &[f64],
+) -> Vec<DailyFundingRate> {
+    binance_daily_rates
+        .iter()
+        .zip(hl_daily_rates)
+        .enumerate()
+        .map(|(i, (&b, &h))| DailyFundingRate {
+            day: i + 1,
+            binance_funding_rate: b,
+            hyperliquid_funding_rate: h,
+            funding_rate_difference: calculate_effective_rate(b, h),
+        })
+        .collect()
+}
 
 pub fn build_past_fr_table(
     binance_daily_rates: Vec<f64>,
@@ -1,16 +1,19 @@
 use anyhow::Result;
-use ethers::signers::Signer;
 use reqwest::Client;
 use serde::Deserialize;
 use serde_aux::field_attributes::deserialize_number_from_string;
 use serde_json::json;
 
-use crate::hyperliquid::get_wallet;
+use crate::{constants::hyperliquid_api_url, hyperliquid::get_account_address};
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct HlAccountRes {
     pub cross_margin_summary: CrossMarginSummary,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub cross_maintenance_margin_used: f64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub withdrawable: f64,
     pub asset_positions: Vec<AssetPosition>,
 }
 
@@ -36,6 +39,7 @@ pub struct Position {
     #[allow(dead_code)]
     pub entry_px: String,
     pub unrealized_pnl: String,
+    pub liquidation_px: Option<String>, // null when the position can't be liquidated
     #[serde(
         deserialize_with = "deserialize_number_from_string",
         rename = "positionValue"
@@ -60,11 +64,11 @@ pub async fn retrieve_hl_account_info() -> Result<HlAccountRes> {
 
     let body = json!({
         "type": "clearinghouseState",
-        "user": get_wallet()?.address()
+        "user": get_account_address()?
     });
 
     let res = client
-        .post("https://api.hyperliquid.xyz/info".to_string())
+        .post(format!("{}/info", hyperliquid_api_url()))
         .header("Content-Type", "application/json")
         .body(body.to_string())
         .send()
@@ -0,0 +1,25 @@
+use super::get_wallet;
+use crate::constants::hyperliquid_base_url;
+use anyhow::{bail, Context, Result};
+use ethers::signers::LocalWallet;
+use hyperliquid_rust_sdk::{ExchangeClient, ExchangeResponseStatus};
+
+/// generates an agent wallet and approves it with the master key to trade on its behalf.
+/// approving a new agent revokes the previous one
+pub async fn approve_hl_agent() -> Result<LocalWallet> {
+    let exchange_client = ExchangeClient::new(
+        None,
+        get_wallet()?,
+        Some(hyperliquid_base_url()?),
+        None,
+        None,
+    )
+    .await?;
+
+    match exchange_client.approve_agent(None).await? {
+        (key, ExchangeResponseStatus::Ok(_)) => key
+            .parse::<LocalWallet>()
+            .context("Hyperliquid returned an invalid agent key"),
+        (_, ExchangeResponseStatus::Err(e)) => bail!("Hyperliquid agent approval failed. {e}"),
+    }
+}
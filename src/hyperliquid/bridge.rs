@@ -0,0 +1,89 @@
+use super::get_wallet;
+use crate::{
+    config::hyperliquid_account_address,
+    constants::{arbitrum_rpc_url, hyperliquid_base_url, ARBITRUM_USDC, HYPERLIQUID_BRIDGE},
+    util::fmt_transfer_amount,
+};
+use anyhow::{bail, Context, Result};
+use ethers::{
+    contract::abigen,
+    middleware::SignerMiddleware,
+    providers::{Http, Middleware, Provider},
+    signers::Signer,
+    types::{Address, U256},
+};
+use hyperliquid_rust_sdk::{ExchangeClient, ExchangeResponseStatus};
+use std::sync::Arc;
+
+abigen!(
+    Erc20,
+    r#"[
+        function transfer(address to, uint256 amount) external returns (bool)
+        function balanceOf(address account) external view returns (uint256)
+    ]"#
+);
+
+const USDC_DECIMALS: f64 = 1_000_000.0;
+
+/// withdraws `amount` usdc from hyperliquid to the wallet on arbitrum, minus the bridge fee
+pub async fn withdraw_from_bridge(amount: f64) -> Result<()> {
+    ensure_wallet_account()?;
+    let wallet = get_wallet()?;
+    let destination = format!("{:?}", wallet.address());
+
+    let exchange_client =
+        ExchangeClient::new(None, wallet, Some(hyperliquid_base_url()?), None, None).await?;
+
+    match exchange_client
+        .withdraw_from_bridge(&fmt_transfer_amount(amount), &destination, None)
+        .await?
+    {
+        ExchangeResponseStatus::Ok(_) => Ok(()),
+        ExchangeResponseStatus::Err(e) => bail!("Hyperliquid withdrawal failed. {e}"),
+    }
+}
+
+/// deposits `amount` usdc from the wallet on arbitrum, credited to the same hyperliquid account
+pub async fn deposit_to_bridge(amount: f64) -> Result<()> {
+    ensure_wallet_account()?;
+    transfer_arbitrum_usdc(amount, HYPERLIQUID_BRIDGE).await
+}
+
+/// the bridge only moves the wallet's own funds, not those of a sub-account or vault
+fn ensure_wallet_account() -> Result<()> {
+    if hyperliquid_account_address()?.is_some() {
+        bail!("Bridge transfers are not supported for Hyperliquid sub-accounts or vaults")
+    }
+
+    Ok(())
+}
+
+/// sends `amount` usdc from the wallet to `to` on arbitrum and waits for the receipt
+pub async fn transfer_arbitrum_usdc(amount: f64, to: &str) -> Result<()> {
+    let provider = Provider::<Http>::try_from(arbitrum_rpc_url())?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+    let wallet = get_wallet()?.with_chain_id(chain_id);
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let usdc = Erc20::new(ARBITRUM_USDC.parse::<Address>()?, client);
+    let to: Address = to.parse().context("invalid arbitrum address")?;
+    let amount = U256::from((amount * USDC_DECIMALS).floor() as u64);
+
+    usdc.transfer(to, amount)
+        .send()
+        .await?
+        .await?
+        .context("usdc transfer dropped from the mempool")?;
+
+    Ok(())
+}
+
+/// usdc held by the wallet on arbitrum
+pub async fn retrieve_arbitrum_usdc_balance() -> Result<f64> {
+    let provider = Arc::new(Provider::<Http>::try_from(arbitrum_rpc_url())?);
+    let usdc = Erc20::new(ARBITRUM_USDC.parse::<Address>()?, provider);
+
+    let balance = usdc.balance_of(get_wallet()?.address()).call().await?;
+
+    Ok(balance.as_u128() as f64 / USDC_DECIMALS)
+}
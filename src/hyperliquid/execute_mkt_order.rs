@@ -1,15 +1,17 @@
 use crate::{
-    constants::EXECUTION_SLIPPAGE,
+    config::{hyperliquid_account_address, profile},
+    constants::hyperliquid_base_url,
     util::{OrderFilled, Platform, Side},
 };
 use anyhow::{bail, Result};
 use ethers::signers::{coins_bip39::English, LocalWallet, MnemonicBuilder};
 use hyperliquid_rust_sdk::{
-    BaseUrl, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, MarketOrderParams,
+    ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, MarketOrderParams,
 };
 
-use super::get_wallet;
+use super::get_signer;
 
+/// market order of `size` tokens, filled within the profile's execution slippage of the mid price
 pub async fn execute_mkt_order(token: String, size: f64, is_buy: bool) -> Result<OrderFilled> {
     let hl_client = setup_hl_client().await?;
 
@@ -18,7 +20,7 @@ pub async fn execute_mkt_order(token: String, size: f64, is_buy: bool) -> Result
         is_buy,
         sz: size,
         px: None,
-        slippage: Some(EXECUTION_SLIPPAGE),
+        slippage: Some(profile().execution_slippage()),
         cloid: None,
         wallet: None,
     };
@@ -43,10 +45,15 @@ pub async fn execute_mkt_order(token: String, size: f64, is_buy: bool) -> Result
 }
 
 async fn setup_hl_client() -> Result<ExchangeClient> {
-    let wallet = get_wallet()?;
-
-    let exchange_client =
-        ExchangeClient::new(None, wallet, Some(BaseUrl::Mainnet), None, None).await?;
+    // orders of a sub-account or vault are signed by the wallet on its behalf
+    let exchange_client = ExchangeClient::new(
+        None,
+        get_signer()?,
+        Some(hyperliquid_base_url()?),
+        None,
+        hyperliquid_account_address()?,
+    )
+    .await?;
 
     Ok(exchange_client)
 }
@@ -1,3 +1,4 @@
+use crate::constants::hyperliquid_api_url;
 use anyhow::Result;
 use chrono::{Duration, Utc};
 use reqwest::Client;
@@ -10,22 +11,29 @@ use serde_json::json;
 struct FundingHistory {
     #[serde(deserialize_with = "deserialize_number_from_string")]
     funding_rate: f64, // hourly
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    premium: f64, // average premium index of the hour
     time: u64, // ms timestamp
 }
 
-async fn retrieve_hl_funding_history(token: String) -> Result<Vec<FundingHistory>> {
-    let client = Client::new();
+// hyperliquid caps the entries of one request
+const FUNDING_HISTORY_PAGE: usize = 500;
 
-    let timestamp = (Utc::now() - Duration::days(15)).timestamp_millis();
+/// entries from `start_time` (ms timestamp) onwards, oldest first, at most one page
+async fn retrieve_hl_funding_history(
+    token: String,
+    start_time: i64,
+) -> Result<Vec<FundingHistory>> {
+    let client = Client::new();
 
     let body = json!({
         "type": "fundingHistory",
         "coin": token,
-        "startTime": timestamp,
+        "startTime": start_time,
     });
 
     let res = client
-        .post("https://api.hyperliquid.xyz/info")
+        .post(format!("{}/info", hyperliquid_api_url()))
         .header("Content-Type", "application/json")
         .body(body.to_string())
         .send()
@@ -37,7 +45,8 @@ async fn retrieve_hl_funding_history(token: String) -> Result<Vec<FundingHistory
 }
 
 pub async fn retrieve_hl_past_daily_fh(coin: String, past_days: u16) -> Result<Vec<f64>> {
-    let mut fh = retrieve_hl_funding_history(coin).await?;
+    let start_time = (Utc::now() - Duration::days(15)).timestamp_millis();
+    let mut fh = retrieve_hl_funding_history(coin, start_time).await?;
     fh.sort_by(|a, b| b.time.cmp(&a.time));
 
     let past_daily_fr: Vec<f64> = fh
@@ -49,13 +58,32 @@ pub async fn retrieve_hl_past_daily_fh(coin: String, past_days: u16) -> Result<V
     Ok(past_daily_fr)
 }
 
+/// premium of every hourly payment of `coin` over the past `hours`, oldest first
+pub async fn retrieve_hl_hourly_premiums(coin: String, hours: u16) -> Result<Vec<f64>> {
+    let mut start_time = (Utc::now() - Duration::hours(hours.into())).timestamp_millis();
+    let mut premiums = vec![];
+
+    loop {
+        let page = retrieve_hl_funding_history(coin.clone(), start_time).await?;
+        premiums.extend(page.iter().map(|fh| fh.premium));
+
+        match page.last() {
+            Some(last) if page.len() == FUNDING_HISTORY_PAGE => start_time = last.time as i64 + 1,
+            _ => break,
+        }
+    }
+
+    Ok(premiums)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
     async fn test_retrieve_hl_funding_history() -> Result<()> {
-        let funding_hist = retrieve_hl_funding_history("ETH".to_string()).await?;
+        let start_time = (Utc::now() - Duration::days(1)).timestamp_millis();
+        let funding_hist = retrieve_hl_funding_history("ETH".to_string(), start_time).await?;
 
         println!("{funding_hist:#?}");
 
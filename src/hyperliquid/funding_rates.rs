@@ -1,7 +1,9 @@
 use anyhow::Result;
 use hyperliquid_rust_sdk::InfoClient;
 use serde::{Deserialize, Serialize};
-use serde_aux::field_attributes::deserialize_number_from_string;
+use serde_aux::field_attributes::{
+    deserialize_number_from_string, deserialize_option_number_from_string,
+};
 use serde_json::json;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,15 +29,29 @@ struct FundingData {
     open_interest: f64,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     mark_px: f64,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    premium: Option<f64>, // null for assets without impact prices
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct HyperliquidToken {
     pub name: String,
     pub hourly_funding_rate: f64,
     pub open_interest: f64, // expressed in USD
+    pub mark_price: f64,
+    pub max_leverage: f64,
+    pub premium: f64,           // impact price over the oracle, decimal form
+    pub next_funding_time: i64, // unix ms
 }
 
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+/// hyperliquid pays funding every hour on the hour, `now` in unix ms
+pub fn next_hl_funding_time(now: i64) -> i64 {
+    (now.div_euclid(HOUR_MS) + 1) * HOUR_MS
+}
+
+/// every perpetual with its hourly funding rate and open interest in USD
 pub async fn retrieve_hl_hourly_funding_rates(
     info_client: &InfoClient,
 ) -> Result<Vec<HyperliquidToken>> {
@@ -53,6 +69,7 @@ pub async fn retrieve_hl_hourly_funding_rates(
     let fr = ds.1;
 
     let mut hyperliquid_tokens = vec![];
+    let next_funding_time = next_hl_funding_time(chrono::Utc::now().timestamp_millis());
 
     for (token, funding_data) in tokens.into_iter().zip(fr.into_iter()) {
         let funding_rate: f64 = funding_data.funding.parse()?;
@@ -61,6 +78,10 @@ pub async fn retrieve_hl_hourly_funding_rates(
             name: token.name,
             hourly_funding_rate: funding_rate,
             open_interest: funding_data.open_interest * funding_data.mark_px,
+            mark_price: funding_data.mark_px,
+            max_leverage: token.max_leverage.into(),
+            premium: funding_data.premium.unwrap_or(0.0),
+            next_funding_time,
         };
 
         hyperliquid_tokens.push(hyperliquid_token);
@@ -73,6 +94,14 @@ pub async fn retrieve_hl_hourly_funding_rates(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_next_hl_funding_time() {
+        // 2024-01-01 10:15:00 UTC pays at 11:00
+        assert_eq!(next_hl_funding_time(1_704_104_100_000), 1_704_106_800_000);
+        // a payment instant waits for the next hour
+        assert_eq!(next_hl_funding_time(1_704_106_800_000), 1_704_110_400_000);
+    }
+
     #[tokio::test]
     async fn get_all_hl_tokens() -> Result<()> {
         let info_client = InfoClient::new(None, None).await.unwrap();
@@ -1,8 +1,20 @@
+use crate::config::{
+    hyperliquid_account_address, hyperliquid_master_address, mnemonic, unlocked_agent,
+    unlocked_wallet,
+};
 use anyhow::Result;
-use ethers::signers::{coins_bip39::English, LocalWallet, MnemonicBuilder};
+use ethers::{
+    signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer},
+    types::H160,
+};
 
+/// master signer of the active account, its unlocked keystore or else its mnemonic
 pub fn get_wallet() -> Result<LocalWallet> {
-    let mnemonic = std::env::var("MNEMONIC")?;
+    if let Some(wallet) = unlocked_wallet() {
+        return Ok(wallet);
+    }
+
+    let mnemonic = mnemonic()?;
 
     let wallet: LocalWallet = MnemonicBuilder::<English>::default()
         .phrase(mnemonic.as_str())
@@ -10,3 +22,23 @@ pub fn get_wallet() -> Result<LocalWallet> {
 
     Ok(wallet)
 }
+
+/// signs orders and leverage updates, the approved agent when there is one.
+/// withdrawals and transfers need the master wallet
+pub fn get_signer() -> Result<LocalWallet> {
+    match unlocked_agent() {
+        Some(agent) => Ok(agent),
+        None => get_wallet(),
+    }
+}
+
+/// address queries and orders act for, the sub-account or vault when the account has one
+pub fn get_account_address() -> Result<H160> {
+    if let Some(address) = hyperliquid_account_address()? {
+        return Ok(address);
+    }
+    match hyperliquid_master_address()? {
+        Some(address) => Ok(address),
+        None => Ok(get_wallet()?.address()),
+    }
+}
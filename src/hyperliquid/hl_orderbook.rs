@@ -1,3 +1,4 @@
+use crate::constants::hyperliquid_api_url;
 use crate::util::{LimitOrder, Orderbook, Platform};
 use anyhow::{bail, Result};
 use reqwest::Client;
@@ -9,15 +10,16 @@ struct ResHyperliquidOrderBook {
 }
 
 #[derive(Debug, Deserialize)]
-struct HlBidAsk {
+pub struct HlBidAsk {
     px: String,
     sz: String,
 }
 
+/// snapshot of the book of `token`, already in hyperliquid naming (e.g. `kPEPE`)
 pub async fn retrieve_hl_order_book(token: &str) -> Result<Orderbook> {
     let client = Client::new();
 
-    let url = "https://api.hyperliquid.xyz/info";
+    let url = format!("{}/info", hyperliquid_api_url());
     let body = serde_json::json!({
         "type": "l2Book",
         "coin": format!("{token}")
@@ -51,7 +53,7 @@ pub async fn retrieve_hl_order_book(token: &str) -> Result<Orderbook> {
     })
 }
 
-fn parse_orders(orders: &[HlBidAsk]) -> Result<Vec<LimitOrder>> {
+pub fn parse_orders(orders: &[HlBidAsk]) -> Result<Vec<LimitOrder>> {
     orders
         .iter()
         .map(|ba| -> Result<LimitOrder> {
@@ -0,0 +1,24 @@
+use super::get_signer;
+use crate::{config::hyperliquid_account_address, constants::hyperliquid_base_url};
+use anyhow::{bail, Result};
+use hyperliquid_rust_sdk::{ExchangeClient, ExchangeResponseStatus};
+
+/// sets the leverage and margin mode of the perpetual of `token`
+pub async fn set_hl_leverage(token: &str, leverage: u32, isolated: bool) -> Result<()> {
+    let exchange_client = ExchangeClient::new(
+        None,
+        get_signer()?,
+        Some(hyperliquid_base_url()?),
+        None,
+        hyperliquid_account_address()?,
+    )
+    .await?;
+
+    match exchange_client
+        .update_leverage(leverage, token, !isolated, None)
+        .await?
+    {
+        ExchangeResponseStatus::Ok(_) => Ok(()),
+        ExchangeResponseStatus::Err(e) => bail!("Hyperliquid leverage update failed. {e}"),
+    }
+}
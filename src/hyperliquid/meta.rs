@@ -0,0 +1,43 @@
+use crate::constants::hyperliquid_api_url;
+use anyhow::{bail, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    universe: Vec<AssetMeta>,
+}
+
+/// a perpetual listed on hyperliquid
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetMeta {
+    pub name: String,     // e.g. kPEPE
+    pub sz_decimals: u32, // decimals allowed in order sizes, in contracts
+    #[serde(default)]
+    is_delisted: bool,
+}
+
+/// perpetuals that can still be traded
+pub async fn retrieve_hl_perp_symbols() -> Result<Vec<AssetMeta>> {
+    let client = Client::new();
+
+    let url = format!("{}/info", hyperliquid_api_url());
+    let body = serde_json::json!({
+        "type": "meta"
+    });
+
+    let response = client.post(url).json(&body).send().await?;
+
+    if !response.status().is_success() {
+        bail!("Could not retrieve the perpetuals listed on Hyperliquid.");
+    }
+
+    let meta: Meta = response.json().await?;
+
+    Ok(meta
+        .universe
+        .into_iter()
+        .filter(|asset| !asset.is_delisted)
+        .collect())
+}
@@ -1,11 +1,27 @@
 pub mod account_information;
+mod agent;
+mod bridge;
 mod execute_mkt_order;
 mod funding_history;
 pub mod funding_rates;
 mod get_wallet;
 mod hl_orderbook;
+mod leverage;
+mod meta;
+mod user_fees;
+mod ws_asset_ctx;
+mod ws_orderbook;
 
+pub use agent::approve_hl_agent;
+pub use bridge::{
+    deposit_to_bridge, retrieve_arbitrum_usdc_balance, transfer_arbitrum_usdc, withdraw_from_bridge,
+};
 pub use execute_mkt_order::execute_mkt_order;
-pub use funding_history::retrieve_hl_past_daily_fh;
+pub use funding_history::{retrieve_hl_hourly_premiums, retrieve_hl_past_daily_fh};
 pub use get_wallet::*;
 pub use hl_orderbook::retrieve_hl_order_book;
+pub use leverage::set_hl_leverage;
+pub use meta::{retrieve_hl_perp_symbols, AssetMeta};
+pub use user_fees::retrieve_hl_user_fees;
+pub use ws_asset_ctx::{stream_hl_asset_ctxs, ActiveAssetCtx};
+pub use ws_orderbook::stream_hl_order_book;
@@ -0,0 +1,43 @@
+use super::get_account_address;
+use crate::{constants::hyperliquid_api_url, fees::TradingFees};
+use anyhow::{bail, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+use serde_json::json;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct UserFeesRes {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    user_add_rate: f64, // maker
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    user_cross_rate: f64, // taker
+}
+
+/// maker and taker rate of the account, volume tier and staking discount included
+pub async fn retrieve_hl_user_fees() -> Result<TradingFees> {
+    let body = json!({
+        "type": "userFees",
+        "user": get_account_address()?
+    });
+
+    let res = Client::new()
+        .post(format!("{}/info", hyperliquid_api_url()))
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        let error = res.text().await?;
+        bail!("Could not retrieve Hyperliquid user fees. {error}")
+    }
+
+    let fees: UserFeesRes = res.json().await?;
+
+    Ok(TradingFees {
+        maker: fees.user_add_rate,
+        taker: fees.user_cross_rate,
+    })
+}
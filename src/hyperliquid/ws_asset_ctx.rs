@@ -0,0 +1,91 @@
+use crate::live_funding_rates::LiveFundingRates;
+use crate::live_orderbooks::RECONNECT_DELAY;
+use anyhow::{bail, Result};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_aux::field_attributes::{
+    deserialize_number_from_string, deserialize_option_number_from_string,
+};
+use serde_json::json;
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+// hyperliquid closes connections that stay silent for a minute
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct WsMessage {
+    channel: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActiveAssetCtx {
+    pub coin: String,
+    pub ctx: AssetCtx,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetCtx {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub funding: f64, // hourly
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub open_interest: f64, // expressed in tokens
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub mark_px: f64,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    pub premium: Option<f64>, // impact price over the oracle, decimal form
+}
+
+/// keeps `store` updated with the funding and mark price of `coins`, reconnecting forever
+pub async fn stream_hl_asset_ctxs(coins: Vec<String>, store: LiveFundingRates) {
+    loop {
+        if let Err(e) = run_asset_ctx_stream(&coins, &store).await {
+            log::warn!("Hyperliquid activeAssetCtx stream dropped. {e}");
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn run_asset_ctx_stream(coins: &[String], store: &LiveFundingRates) -> Result<()> {
+    let (mut ws, _) = connect_async("wss://api.hyperliquid.xyz/ws").await?;
+
+    for coin in coins {
+        let subscription = json!({
+            "method": "subscribe",
+            "subscription": { "type": "activeAssetCtx", "coin": coin }
+        });
+        ws.send(Message::Text(subscription.to_string())).await?;
+    }
+
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+
+    loop {
+        let message = tokio::select! {
+            message = ws.next() => message,
+            _ = ping.tick() => {
+                ws.send(Message::Text(json!({ "method": "ping" }).to_string())).await?;
+                continue;
+            }
+        };
+
+        let text = match message {
+            None => bail!("stream ended"),
+            Some(message) => match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => bail!("connection closed by hyperliquid"),
+                _ => continue,
+            },
+        };
+
+        let message: WsMessage = serde_json::from_str(&text)?;
+        if message.channel != "activeAssetCtx" {
+            continue;
+        }
+
+        store.apply_hl_asset_ctx(serde_json::from_value(message.data)?);
+    }
+}
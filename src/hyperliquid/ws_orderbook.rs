@@ -0,0 +1,182 @@
+use super::hl_orderbook::{parse_orders, HlBidAsk};
+use crate::live_orderbooks::{BookUpdate, LiveOrderbooks, RECONNECT_DELAY, STREAM_READ_TIMEOUT};
+use crate::util::{Orderbook, Platform};
+use anyhow::{bail, Result};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+// hyperliquid closes connections that stay silent for a minute
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct WsMessage {
+    channel: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct L2BookData {
+    pub coin: String,
+    pub time: u64, // ms timestamp
+    pub levels: Vec<Vec<HlBidAsk>>,
+}
+
+/// every `l2Book` message is a full snapshot, so syncing only has to discard stale ones
+#[derive(Default)]
+pub struct HlBookSync {
+    book: Option<Orderbook>,
+    last_time: u64,
+}
+
+impl HlBookSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn orderbook(&self) -> Option<&Orderbook> {
+        self.book.as_ref()
+    }
+
+    pub fn apply(&mut self, data: L2BookData) -> Result<BookUpdate> {
+        if data.time < self.last_time {
+            return Ok(BookUpdate::Dropped);
+        }
+
+        let (bids, asks) = match data.levels.as_slice() {
+            [bids, asks, ..] => (parse_orders(bids)?, parse_orders(asks)?),
+            _ => bail!("Invalid orderbook structure"),
+        };
+
+        self.book = Some(Orderbook {
+            platform: Platform::Hyperliquid,
+            bids,
+            asks,
+        });
+        self.last_time = data.time;
+
+        Ok(BookUpdate::Applied)
+    }
+}
+
+/// keeps `store` updated with the hyperliquid book of `token`, reconnecting forever
+pub async fn stream_hl_order_book(token: String, store: LiveOrderbooks) {
+    loop {
+        if let Err(e) = run_l2_book_stream(&token, &store).await {
+            log::warn!("Hyperliquid l2Book stream for {token} dropped. {e}");
+        }
+
+        store.remove(Platform::Hyperliquid, &token);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn run_l2_book_stream(token: &str, store: &LiveOrderbooks) -> Result<()> {
+    let (mut ws, _) = connect_async("wss://api.hyperliquid.xyz/ws").await?;
+
+    let subscription = json!({
+        "method": "subscribe",
+        "subscription": { "type": "l2Book", "coin": token }
+    });
+    ws.send(Message::Text(subscription.to_string())).await?;
+
+    let mut sync = HlBookSync::new();
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    // pongs keep the socket open, only book updates move the deadline
+    let mut deadline = tokio::time::Instant::now() + STREAM_READ_TIMEOUT;
+
+    loop {
+        let message = tokio::select! {
+            message = ws.next() => message,
+            _ = tokio::time::sleep_until(deadline) => {
+                bail!("no update for {}s", STREAM_READ_TIMEOUT.as_secs())
+            }
+            _ = ping.tick() => {
+                ws.send(Message::Text(json!({ "method": "ping" }).to_string())).await?;
+                continue;
+            }
+        };
+
+        let text = match message {
+            None => bail!("stream ended"),
+            Some(message) => match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => bail!("connection closed by hyperliquid"),
+                _ => continue,
+            },
+        };
+
+        let message: WsMessage = serde_json::from_str(&text)?;
+        if message.channel != "l2Book" {
+            continue;
+        }
+
+        let data: L2BookData = serde_json::from_value(message.data)?;
+        if data.coin != token {
+            continue;
+        }
+
+        deadline = tokio::time::Instant::now() + STREAM_READ_TIMEOUT;
+        if sync.apply(data)? == BookUpdate::Applied {
+            if let Some(book) = sync.orderbook() {
+                store.insert(token, book.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l2_book(text: &str) -> L2BookData {
+        let message: WsMessage = serde_json::from_str(text).unwrap();
+        assert_eq!(message.channel, "l2Book");
+        serde_json::from_value(message.data).unwrap()
+    }
+
+    const FIRST: &str = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1000,"levels":[[{"px":"2500.1","sz":"1.5","n":2}],[{"px":"2500.3","sz":"0.7","n":1}]]}}"#;
+    const SECOND: &str = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1500,"levels":[[{"px":"2500.2","sz":"3.0","n":4}],[{"px":"2500.4","sz":"1.0","n":1}]]}}"#;
+    const OUT_OF_ORDER: &str = r#"{"channel":"l2Book","data":{"coin":"ETH","time":1200,"levels":[[{"px":"2499.0","sz":"1.0","n":1}],[{"px":"2501.0","sz":"1.0","n":1}]]}}"#;
+
+    #[test]
+    fn test_hl_sync_discards_stale_snapshots() -> Result<()> {
+        let mut sync = HlBookSync::new();
+
+        assert_eq!(sync.apply(l2_book(FIRST))?, BookUpdate::Applied);
+        assert_eq!(sync.apply(l2_book(SECOND))?, BookUpdate::Applied);
+        assert_eq!(sync.apply(l2_book(OUT_OF_ORDER))?, BookUpdate::Dropped);
+
+        let book = sync.orderbook().unwrap();
+        assert_eq!(book.bids[0].price, 2500.2);
+        assert_eq!(book.asks[0].price, 2500.4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hl_sync_after_reconnect() -> Result<()> {
+        let mut sync = HlBookSync::new();
+        sync.apply(l2_book(SECOND))?;
+        assert_eq!(sync.orderbook().unwrap().bids[0].price, 2500.2);
+
+        // the new connection starts over with whatever the server sends first
+        let mut sync = HlBookSync::new();
+        assert!(sync.orderbook().is_none());
+        assert_eq!(sync.apply(l2_book(OUT_OF_ORDER))?, BookUpdate::Applied);
+        assert_eq!(sync.orderbook().unwrap().bids[0].price, 2499.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hl_sync_rejects_malformed_levels() {
+        let mut sync = HlBookSync::new();
+        let data = l2_book(r#"{"channel":"l2Book","data":{"coin":"ETH","time":1,"levels":[[]]}}"#);
+
+        assert!(sync.apply(data).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Funding rate arbitrage between Binance and Hyperliquid perpetuals.
+//!
+//! - [`binance`] and [`hyperliquid`]: venue adapters for orderbooks, funding, accounts and orders
+//! - [`compare_funding_rates`]: joins both venues into [`compare_funding_rates::JointFundingRate`]
+//! - [`quote`]: walks orderbooks to price entering or exiting a hedge
+//! - [`execution`]: opens and closes both legs of a hedge
+//! - [`fees`]: commission rates of both accounts, used by every quote
+//! - [`forecast`]: expected funding over the next day and week from premium history, with backtests
+//! - [`symbols`]: maps venue symbols such as `1000PEPE` and `kPEPE` to one asset and its contract sizes
+//! - [`live_funding_rates`] and [`live_orderbooks`]: websocket backed state
+//! - [`metrics`]: prometheus gauges and request timings
+//! - [`alerts`]: funding and risk rules delivered to webhooks
+//! - [`rebalance`]: plans and executes margin transfers between the venues
+//! - [`config`]: profiles of credentials, venues and strategy parameters read from toml
+//!
+//! Rates are hourly and in decimal form unless stated otherwise, amounts are USD.
+//! Signed requests read their keys from the active account, else from the profile made current with
+//! [`config::Profile::install`], which keeps unlocked keys in memory, else from `BINANCE_API_KEY`,
+//! `BINANCE_SECRET_KEY` and `MNEMONIC` in the environment. [`config::in_account`] runs a future
+//! against one of its named accounts. `BINANCE_API_URL` and `HYPERLIQUID_API_URL` point the REST
+//! calls at local mock exchanges when the profile sets no url.
+//!
+//! ```no_run
+//! use funding_rate_arbitrage::{
+//!     compare_funding_rates::compare_funding_rates,
+//!     fees::retrieve_fee_schedule,
+//!     orderbook::retrieve_orderbooks,
+//!     quote::{calculate_hedge_cost, quote_hedge, FillTarget},
+//!     util::Platform,
+//! };
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let best = &compare_funding_rates().await?[0];
+//! let (b, hl) = retrieve_orderbooks(&best.name, best.binance_quote).await?;
+//! let fees = retrieve_fee_schedule(&best.name, best.binance_quote).await?;
+//! let target = FillTarget::Usd(10_000.0);
+//! let (long, short) = quote_hedge(&b, &hl, Platform::Hyperliquid, target, &fees)?;
+//! println!("entry cost {:.4}", calculate_hedge_cost(&long, &short));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod alerts;
+pub mod balances;
+pub mod binance;
+pub mod break_even;
+pub mod compare_funding_rates;
+pub mod config;
+pub mod constants;
+pub mod execution;
+pub mod fees;
+pub mod forecast;
+pub mod funding_history_table;
+pub mod hyperliquid;
+pub mod live_funding_rates;
+pub mod live_orderbooks;
+pub mod metrics;
+pub mod orderbook;
+pub mod quote;
+pub mod rebalance;
+pub mod symbols;
+pub mod token_price;
+pub mod util;
+
+#[cfg(test)]
+mod test_fixtures;
@@ -0,0 +1,268 @@
+use crate::{
+    binance::{
+        funding_rates::{retrieve_binance_hourly_funding_rates, BinanceFundingRate},
+        retrieve_binance_funding_info, stream_binance_mark_prices, MarkPriceUpdate,
+    },
+    compare_funding_rates::{join_funding_rates, JointFundingRate},
+    constants::hyperliquid_base_url,
+    hyperliquid::{
+        funding_rates::{next_hl_funding_time, retrieve_hl_hourly_funding_rates, HyperliquidToken},
+        stream_hl_asset_ctxs, ActiveAssetCtx,
+    },
+    symbols::load_symbols,
+    util::{calculate_premium, Platform},
+};
+use anyhow::Result;
+use hyperliquid_rust_sdk::InfoClient;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use tokio::try_join;
+
+/// funding rates and mark prices of both platforms, kept current by the websocket streams
+#[derive(Clone, Default)]
+pub struct LiveFundingRates {
+    // keyed by pair, e.g. ETHUSDC
+    binance: Arc<RwLock<HashMap<String, BinanceFundingRate>>>,
+    hyperliquid: Arc<RwLock<HashMap<String, HyperliquidToken>>>,
+    // symbol => hours between payments, 8 when missing
+    funding_intervals: Arc<HashMap<String, f64>>,
+}
+
+impl LiveFundingRates {
+    /// seeds the tables over REST then keeps them updated in the background
+    pub async fn start() -> Result<Self> {
+        let info_client = InfoClient::new(None, Some(hyperliquid_base_url()?)).await?;
+
+        let (binance_tokens, hyperliquid_tokens, funding_info, symbols) = try_join!(
+            retrieve_binance_hourly_funding_rates(),
+            retrieve_hl_hourly_funding_rates(&info_client),
+            retrieve_binance_funding_info(),
+            load_symbols()
+        )?;
+
+        let live = Self::new(
+            binance_tokens,
+            hyperliquid_tokens,
+            funding_info
+                .into_iter()
+                .map(|info| (info.symbol, info.funding_interval_hours))
+                .collect(),
+        );
+
+        // only tokens listed on both platforms matter for the comparison
+        let coins: Vec<String> = live
+            .joint_funding_rates()
+            .into_iter()
+            .map(|jfr| symbols.asset(&jfr.name).symbol(Platform::Hyperliquid))
+            .collect();
+
+        tokio::spawn(stream_binance_mark_prices(live.clone()));
+        tokio::spawn(stream_hl_asset_ctxs(coins, live.clone()));
+
+        Ok(live)
+    }
+
+    pub fn new(
+        binance_tokens: Vec<BinanceFundingRate>,
+        hyperliquid_tokens: Vec<HyperliquidToken>,
+        funding_intervals: HashMap<String, f64>,
+    ) -> Self {
+        Self {
+            binance: Arc::new(RwLock::new(
+                binance_tokens
+                    .into_iter()
+                    .map(|t| (t.quote.pair(&t.name), t))
+                    .collect(),
+            )),
+            hyperliquid: Arc::new(RwLock::new(
+                hyperliquid_tokens
+                    .into_iter()
+                    .map(|t| (t.name.clone(), t))
+                    .collect(),
+            )),
+            funding_intervals: Arc::new(funding_intervals),
+        }
+    }
+
+    pub fn apply_binance_mark_prices(&self, updates: Vec<MarkPriceUpdate>) {
+        let mut binance = self.binance.write().expect("funding rates lock poisoned");
+
+        for update in updates {
+            let interval = self
+                .funding_intervals
+                .get(&update.symbol)
+                .copied()
+                .unwrap_or(8.0);
+
+            // tokens listed after start up won't have a max leverage, skip them
+            if let Some(token) = binance.get_mut(&update.symbol) {
+                token.hourly_funding_rate = update.funding_rate / interval;
+                token.mark_price = update.mark_price;
+                token.next_funding_rate = update.funding_rate;
+                token.next_funding_time = update.next_funding_time;
+                token.premium = calculate_premium(update.mark_price, update.index_price);
+            }
+        }
+    }
+
+    pub fn apply_hl_asset_ctx(&self, asset_ctx: ActiveAssetCtx) {
+        let mut hyperliquid = self
+            .hyperliquid
+            .write()
+            .expect("funding rates lock poisoned");
+
+        let token = hyperliquid
+            .entry(asset_ctx.coin.clone())
+            .or_insert_with(|| HyperliquidToken {
+                name: asset_ctx.coin.clone(),
+                hourly_funding_rate: 0.0,
+                open_interest: 0.0,
+                mark_price: 0.0,
+                max_leverage: 0.0, // unknown for tokens listed after start up
+                premium: 0.0,
+                next_funding_time: 0,
+            });
+
+        token.hourly_funding_rate = asset_ctx.ctx.funding;
+        token.open_interest = asset_ctx.ctx.open_interest * asset_ctx.ctx.mark_px;
+        token.mark_price = asset_ctx.ctx.mark_px;
+        token.premium = asset_ctx.ctx.premium.unwrap_or(0.0);
+        token.next_funding_time = next_hl_funding_time(chrono::Utc::now().timestamp_millis());
+    }
+
+    /// same output as `compare_funding_rates` without hitting the network
+    pub fn joint_funding_rates(&self) -> Vec<JointFundingRate> {
+        let binance: Vec<_> = self
+            .binance
+            .read()
+            .expect("funding rates lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        let hyperliquid: Vec<_> = self
+            .hyperliquid
+            .read()
+            .expect("funding rates lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+
+        join_funding_rates(&binance, &hyperliquid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::QuoteAsset;
+    use approx::assert_relative_eq;
+
+    fn live_funding_rates() -> LiveFundingRates {
+        let binance = vec![
+            BinanceFundingRate {
+                name: "ETH".to_string(),
+                quote: QuoteAsset::Usdt,
+                hourly_funding_rate: 0.0001 / 8.0,
+                max_leverage: 100.0,
+                mark_price: 2500.0,
+                next_funding_rate: 0.0001,
+                next_funding_time: 0,
+                funding_interval: 8.0,
+                interest_rate: 0.0001,
+                premium: 0.0,
+            },
+            BinanceFundingRate {
+                name: "WIF".to_string(),
+                quote: QuoteAsset::Usdt,
+                hourly_funding_rate: 0.0001 / 4.0,
+                max_leverage: 20.0,
+                mark_price: 2.0,
+                next_funding_rate: 0.0001,
+                next_funding_time: 0,
+                funding_interval: 4.0,
+                interest_rate: 0.0001,
+                premium: 0.0,
+            },
+        ];
+        let hyperliquid = vec![
+            HyperliquidToken {
+                name: "ETH".to_string(),
+                hourly_funding_rate: 0.0000125,
+                open_interest: 1_000_000.0,
+                mark_price: 2500.0,
+                max_leverage: 50.0,
+                premium: 0.0,
+                next_funding_time: 0,
+            },
+            HyperliquidToken {
+                name: "WIF".to_string(),
+                hourly_funding_rate: 0.0000125,
+                open_interest: 500_000.0,
+                mark_price: 2.0,
+                max_leverage: 5.0,
+                premium: 0.0,
+                next_funding_time: 0,
+            },
+        ];
+        let intervals = HashMap::from([("WIFUSDT".to_string(), 4.0)]);
+
+        LiveFundingRates::new(binance, hyperliquid, intervals)
+    }
+
+    #[test]
+    fn test_binance_mark_price_stream_updates_table() {
+        let live = live_funding_rates();
+
+        let updates: Vec<MarkPriceUpdate> = serde_json::from_str(
+            r#"[
+                {"e":"markPriceUpdate","E":1,"s":"ETHUSDT","p":"2510.5","i":"2500.0","P":"2511.0","r":"0.00080000","T":1704124800000},
+                {"e":"markPriceUpdate","E":1,"s":"WIFUSDT","p":"2.1","i":"2.1","P":"2.1","r":"-0.00040000","T":1},
+                {"e":"markPriceUpdate","E":1,"s":"ETHUSDC","p":"2510.0","i":"2510.1","P":"2511.0","r":"0.00500000","T":1},
+                {"e":"markPriceUpdate","E":1,"s":"NEWUSDT","p":"1.0","i":"1.0","P":"1.0","r":"0.00100000","T":1}
+            ]"#,
+        )
+        .unwrap();
+        live.apply_binance_mark_prices(updates);
+
+        let jfr = live.joint_funding_rates();
+        assert_eq!(jfr.len(), 2);
+
+        let eth = jfr.iter().find(|j| j.name == "ETH").unwrap();
+        assert_relative_eq!(eth.binance_funding_rate, 0.0008 / 8.0);
+        assert_relative_eq!(eth.binance_mark_price, 2510.5);
+        assert_relative_eq!(eth.binance_next_funding_rate, 0.0008);
+        assert_eq!(eth.binance_next_funding_time, 1_704_124_800_000);
+        assert_relative_eq!(eth.binance_premium, 0.0042);
+
+        // 4h interval and opposite signs
+        let wif = jfr.iter().find(|j| j.name == "WIF").unwrap();
+        assert_relative_eq!(wif.binance_funding_rate, -0.0001);
+        assert_relative_eq!(wif.funding_rate_difference, 0.0001 + 0.0000125);
+
+        // best difference first
+        assert_eq!(jfr[0].name, "WIF");
+    }
+
+    #[test]
+    fn test_hl_asset_ctx_stream_updates_table() {
+        let live = live_funding_rates();
+
+        let asset_ctx: ActiveAssetCtx = serde_json::from_str(
+            r#"{"coin":"ETH","ctx":{"dayNtlVlm":"1.0","prevDayPx":"2400.0","markPx":"2520.0","midPx":"2520.5","funding":"0.0005","openInterest":"1000.0","oraclePx":"2519.0","premium":"0.0003"}}"#,
+        )
+        .unwrap();
+        live.apply_hl_asset_ctx(asset_ctx);
+
+        let jfr = live.joint_funding_rates();
+        let eth = jfr.iter().find(|j| j.name == "ETH").unwrap();
+        assert_relative_eq!(eth.hyperliquid_funding_rate, 0.0005);
+        assert_relative_eq!(eth.hyperliquid_open_interest, 2_520_000.0);
+        assert_relative_eq!(eth.hyperliquid_mark_price, 2520.0);
+        assert_relative_eq!(eth.hyperliquid_next_funding_rate, 0.0005);
+        assert_relative_eq!(eth.hyperliquid_premium, 0.0003);
+        assert!(eth.hyperliquid_next_funding_time > chrono::Utc::now().timestamp_millis());
+        assert_eq!(jfr[0].name, "ETH");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    binance::stream_binance_order_book,
+    hyperliquid::stream_hl_order_book,
+    symbols::symbols,
+    util::{Orderbook, Platform, QuoteAsset},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, LazyLock, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+pub const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+/// a book not updated for this long is left to the rest snapshot
+pub const MAX_BOOK_AGE: Duration = Duration::from_secs(5);
+/// a stream without book updates for this long is reconnected and resynced
+pub const STREAM_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+static LIVE_ORDERBOOKS: LazyLock<LiveOrderbooks> = LazyLock::new(LiveOrderbooks::new);
+
+/// books streamed for the whole process, long running commands subscribe here
+/// so quotes and execution read them instead of a rest snapshot
+pub fn live_orderbooks() -> &'static LiveOrderbooks {
+    &LIVE_ORDERBOOKS
+}
+
+/// a book and when its stream last updated it
+type TimedOrderbook = (Orderbook, Instant);
+
+/// outcome of feeding one stream message into a local book
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BookUpdate {
+    /// kept until the snapshot arrives
+    Buffered,
+    Applied,
+    /// older than the current book
+    Dropped,
+    /// a message was missed, the book must be resynced
+    Gap,
+}
+
+/// orderbooks kept up to date by the websocket streams, keyed by platform and symbol.
+/// binance books are keyed by pair, e.g. ETHUSDC
+#[derive(Clone, Default)]
+pub struct LiveOrderbooks {
+    books: Arc<RwLock<HashMap<(Platform, String), TimedOrderbook>>>,
+    subscriptions: Arc<Mutex<HashSet<(String, QuoteAsset)>>>,
+}
+
+impl LiveOrderbooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// starts streaming both venues' books for `token` in the background, binance on its `quote` perpetual.
+    /// symbols must be loaded for assets with a contract multiplier, subscribing twice keeps one stream
+    pub fn subscribe(&self, token: &str, quote: QuoteAsset) {
+        let mut subscriptions = self
+            .subscriptions
+            .lock()
+            .expect("subscriptions lock poisoned");
+        if !subscriptions.insert((token.to_string(), quote)) {
+            return;
+        }
+
+        let (b_token, hl_token) = symbols().venue_symbols(token);
+
+        tokio::spawn(stream_binance_order_book(
+            quote.pair(&b_token),
+            self.clone(),
+        ));
+        tokio::spawn(stream_hl_order_book(hl_token, self.clone()));
+    }
+
+    pub fn insert(&self, token: &str, orderbook: Orderbook) {
+        self.insert_updated_at(token, orderbook, Instant::now());
+    }
+
+    fn insert_updated_at(&self, token: &str, orderbook: Orderbook, updated_at: Instant) {
+        let mut books = self.books.write().expect("orderbooks lock poisoned");
+        books.insert(
+            (orderbook.platform, token.to_string()),
+            (orderbook, updated_at),
+        );
+    }
+
+    pub fn remove(&self, platform: Platform, token: &str) {
+        let mut books = self.books.write().expect("orderbooks lock poisoned");
+        books.remove(&(platform, token.to_string()));
+    }
+
+    /// none once the book is older than `MAX_BOOK_AGE`, a stalled stream must not quote frozen prices
+    pub fn get(&self, platform: Platform, token: &str) -> Option<Orderbook> {
+        let books = self.books.read().expect("orderbooks lock poisoned");
+        books
+            .get(&(platform, token.to_string()))
+            .filter(|(_, updated_at)| updated_at.elapsed() <= MAX_BOOK_AGE)
+            .map(|(orderbook, _)| orderbook.clone())
+    }
+
+    /// same shape as `retrieve_orderbooks`, first element is the binance orderbook
+    pub fn orderbooks(&self, token: &str, quote: QuoteAsset) -> Option<(Orderbook, Orderbook)> {
+        let asset = symbols().asset(token);
+        let b = self.get(
+            Platform::Binance,
+            &quote.pair(&asset.symbol(Platform::Binance)),
+        )?;
+        let hl = self.get(Platform::Hyperliquid, &asset.symbol(Platform::Hyperliquid))?;
+
+        Some((
+            b.to_asset_units(asset.scale(Platform::Binance)),
+            hl.to_asset_units(asset.scale(Platform::Hyperliquid)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_books_are_not_served() {
+        let live = LiveOrderbooks::new();
+        let book = |platform| Orderbook {
+            platform,
+            bids: vec![],
+            asks: vec![],
+        };
+
+        live.insert("ETHUSDT", book(Platform::Binance));
+        assert!(live.get(Platform::Binance, "ETHUSDT").is_some());
+
+        let stalled = Instant::now() - MAX_BOOK_AGE * 2;
+        live.insert_updated_at("ETH", book(Platform::Hyperliquid), stalled);
+        assert!(live.get(Platform::Hyperliquid, "ETH").is_none());
+    }
+}
@@ -1,44 +1,131 @@
-mod balances;
-mod binance;
 mod cli_types;
-mod compare_funding_rates;
-mod constants;
-mod funding_history_table;
-mod hyperliquid;
-mod orderbook;
-mod quote;
-mod token_price;
-mod util;
-
-use anyhow::{bail, Result};
-use balances::{
-    build_account_balance_table, build_account_open_positions_table,
-    retrieve_account_open_positions,
-};
-use binance::{
-    get_trimmed_quantity, retrieve_binance_order_book, retrieve_binance_past_daily_fh,
-    retrieve_step_size,
-};
+mod dashboard;
+mod output;
+mod passphrase;
+mod server;
+
+use anyhow::{bail, Context, Result};
 use clap::Parser;
-use cli_types::{Cli, Commands};
-use compare_funding_rates::build_funding_rate_table;
-use funding_history_table::build_past_fr_table;
-use hyperliquid::{retrieve_hl_order_book, retrieve_hl_past_daily_fh};
-use numfmt::{Formatter, Precision};
-use orderbook::retrieve_orderbooks;
-use prettytable::{Cell, Row, Table};
-use quote::{get_expected_execution_price, retrieve_quote_, retrieve_quote_enter};
-use token_price::{get_mid_price, retrieve_token_price};
+use cli_types::{Cli, Commands, ConfigCommand};
+use dashboard::run_dashboard;
+use ethers::signers::Signer;
+use funding_rate_arbitrage::{
+    alerts::{run_alerts, AlertRules, Webhook},
+    balances::{
+        build_account_balance_table, build_account_open_positions_table,
+        build_all_accounts_balance_table, find_delta_drifts, find_liquidation_risks,
+        find_open_hedge, retrieve_account_balance, retrieve_account_open_positions,
+        retrieve_all_account_balances, total_balance,
+    },
+    binance::retrieve_binance_past_daily_fh,
+    break_even::{build_break_even_table, calculate_break_even, retrieve_funding_spread},
+    compare_funding_rates::{
+        build_funding_rate_table, build_funding_rate_table_from, compare_funding_rates,
+        screen_funding_rates, Screener, ScreenerCache,
+    },
+    config::{
+        binance_api_key, binance_secret_key, encrypt_secrets, encrypt_wallet, mnemonic, profile,
+        ConfigFile, Secrets, DEFAULT_CONFIG_PATH,
+    },
+    constants::{LIQUIDATION_RISK_DISTANCE, MIN_DELTA_DRIFT_USD},
+    execution::{adjust_hedge, open_hedge},
+    fees::retrieve_fee_schedule,
+    forecast::{build_forecast_table, retrieve_funding_forecasts},
+    funding_history_table::{build_past_fr_table, join_daily_funding_rates},
+    hyperliquid::{approve_hl_agent, get_wallet, retrieve_hl_past_daily_fh},
+    live_funding_rates::LiveFundingRates,
+    live_orderbooks::live_orderbooks,
+    orderbook::{format_orderbook_depth, latest_orderbooks, retrieve_orderbooks, summarize_depth},
+    quote::{
+        build_fill_table, build_quote_table, quote_hedge_exit, quote_round_trip, split_by_platform,
+        FillTarget, Leg, QuoteRow,
+    },
+    rebalance::{
+        build_rebalance_table, execute_rebalance, plan_rebalance, retrieve_venue_funds,
+        LiveTransferRails,
+    },
+    symbols::load_symbols,
+};
+use output::{to_csv, to_json, OutputFormat};
+use passphrase::{read_new_passphrase, read_passphrase};
+use serde_json::json;
+use server::serve;
+use std::{io::Write, path::Path, time::Duration};
 use tokio::try_join;
-use util::{calculate_pct_difference, format_token, Platform, Side};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     dotenv::dotenv().ok();
     env_logger::init();
 
-    match Cli::parse().command {
-        Commands::Balance => {
+    let cli = Cli::parse();
+    let output = cli.output;
+
+    // flags win over the profile, the profile over the environment, the environment over the defaults
+    let config = match &cli.config {
+        Some(path) => ConfigFile::load(path)?,
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+            ConfigFile::load(Path::new(DEFAULT_CONFIG_PATH))?
+        }
+        None => ConfigFile::default(),
+    };
+    let (profile_name, mut selected) = config.profile(cli.profile.as_deref())?;
+    if let Some(account) = &cli.account {
+        if let Commands::Balance { all_accounts: true } = cli.command {
+            bail!("--account and --all-accounts can't be combined")
+        }
+        selected.select_account(account)?;
+    }
+    // balance and positions have different columns, they don't fit in one csv
+    if let (
+        Commands::Balance {
+            all_accounts: false,
+        },
+        OutputFormat::Csv,
+    ) = (&cli.command, output)
+    {
+        bail!("csv output isn't supported for a single account balance, use json")
+    }
+    if let Commands::Config {
+        command: ConfigCommand::Check,
+    } = cli.command
+    {
+        // a wrong path would otherwise surface as a failed unlock
+        let missing = selected.missing_credential_files();
+        for problem in &missing {
+            println!("- {problem}");
+        }
+        if !missing.is_empty() {
+            bail!("Profile {profile_name} has {} problem(s)", missing.len())
+        }
+    }
+    if selected.is_encrypted() && needs_credentials(&cli.command) {
+        selected.unlock(&read_passphrase("Passphrase: ")?)?;
+    }
+    if uses_accounts(&cli.command) {
+        selected.ensure_venues_enabled()?;
+    }
+    selected.install()?;
+
+    match cli.command {
+        Commands::Balance { all_accounts: true } => {
+            let balances = retrieve_all_account_balances().await?;
+
+            match output {
+                OutputFormat::Table => {
+                    println!("{}", build_all_accounts_balance_table(&balances)?)
+                }
+                OutputFormat::Json => println!(
+                    "{}",
+                    to_json(&json!({ "accounts": balances, "total": total_balance(&balances) }))?
+                ),
+                OutputFormat::Csv => {
+                    let total = total_balance(&balances);
+                    println!("{}", to_csv(&[balances, vec![total]].concat())?)
+                }
+            }
+        }
+        Commands::Balance { .. } if output == OutputFormat::Table => {
             let (global_balances, open_positions) = try_join!(
                 build_account_balance_table(),
                 build_account_open_positions_table()
@@ -46,175 +133,479 @@ async fn main() -> Result<()> {
             println!("{global_balances}");
             println!("{open_positions}");
         }
-        Commands::FundingRates => {
-            let funding_rates_table = build_funding_rate_table().await?;
-            println!("{funding_rates_table}");
+        Commands::Balance { .. } => {
+            let (balance, positions) = try_join!(
+                retrieve_account_balance(),
+                retrieve_account_open_positions()
+            )?;
+
+            let liquidation_risks = find_liquidation_risks(&positions, LIQUIDATION_RISK_DISTANCE);
+            let delta_drifts = find_delta_drifts(&positions, MIN_DELTA_DRIFT_USD);
+            let json = json!({
+                "balance": balance,
+                "positions": positions,
+                "liquidation_risks": liquidation_risks,
+                "delta_drifts": delta_drifts,
+            });
+            println!("{}", to_json(&json)?);
         }
-        Commands::FundingHistory { token, past_days } => {
-            let (b_token, hl_token) = format_token(&token);
+        Commands::FundingRates {
+            watch,
+            top,
+            min_apr,
+            min_oi,
+            min_depth,
+            exclude,
+            only,
+            sort_by,
+            notional,
+            holding_days,
+        } => {
+            let screener = Screener {
+                top,
+                min_apr,
+                min_oi,
+                min_depth,
+                only,
+                exclude,
+                sort_by,
+                notional,
+                holding_days,
+            };
+
+            if watch {
+                let live_funding_rates = LiveFundingRates::start().await?;
+                // open interest and books refresh less often than the funding rates
+                let cache = ScreenerCache::default();
+
+                let mut interval = tokio::time::interval(Duration::from_secs(5));
+                loop {
+                    interval.tick().await;
+                    let fr = live_funding_rates.joint_funding_rates();
+                    match output {
+                        OutputFormat::Table => println!(
+                            "{}",
+                            build_funding_rate_table_from(fr, &screener, &cache).await?
+                        ),
+                        OutputFormat::Json => println!(
+                            "{}",
+                            to_json(&screen_funding_rates(fr, &screener, &cache).await?)?
+                        ),
+                        OutputFormat::Csv => println!(
+                            "{}",
+                            to_csv(&screen_funding_rates(fr, &screener, &cache).await?)?
+                        ),
+                    }
+                }
+            }
+
+            match output {
+                OutputFormat::Table => println!("{}", build_funding_rate_table(&screener).await?),
+                OutputFormat::Json => {
+                    let fr = compare_funding_rates().await?;
+                    let rows =
+                        screen_funding_rates(fr, &screener, &ScreenerCache::default()).await?;
+                    println!("{}", to_json(&rows)?)
+                }
+                OutputFormat::Csv => {
+                    let fr = compare_funding_rates().await?;
+                    let rows =
+                        screen_funding_rates(fr, &screener, &ScreenerCache::default()).await?;
+                    println!("{}", to_csv(&rows)?)
+                }
+            }
+        }
+        Commands::Forecast { top } => {
+            let forecasts = retrieve_funding_forecasts(top).await?;
+            match output {
+                OutputFormat::Table => println!("{}", build_forecast_table(&forecasts)),
+                OutputFormat::Json => println!("{}", to_json(&forecasts)?),
+                OutputFormat::Csv => println!("{}", to_csv(&forecasts)?),
+            }
+        }
+        Commands::Dashboard => run_dashboard().await?,
+        Commands::FundingHistory {
+            token,
+            past_days,
+            quote,
+        } => {
+            let past_days = past_days.unwrap_or(profile().funding_history_days());
+            let quote = quote.unwrap_or(profile().binance.quote);
+            let (b_token, hl_token) = load_symbols().await?.venue_symbols(&token);
             let (b_fh, hl_fh) = try_join!(
-                retrieve_binance_past_daily_fh(b_token, past_days.into()),
+                retrieve_binance_past_daily_fh(b_token, quote, past_days.into()),
                 retrieve_hl_past_daily_fh(hl_token, past_days.into())
             )?;
-            let past_daily_rates = build_past_fr_table(b_fh, hl_fh)?;
-            println!("{past_daily_rates}");
+            match output {
+                OutputFormat::Table => println!("{}", build_past_fr_table(b_fh, hl_fh)?),
+                OutputFormat::Json => {
+                    println!("{}", to_json(&join_daily_funding_rates(&b_fh, &hl_fh))?)
+                }
+                OutputFormat::Csv => {
+                    println!("{}", to_csv(&join_daily_funding_rates(&b_fh, &hl_fh))?)
+                }
+            }
         }
         Commands::Quote {
             token,
-            amount,
+            usd,
+            size,
+            exit_position,
             long,
+            quote,
         } => {
-            let (b, hl) = retrieve_orderbooks(&token).await?;
+            if exit_position {
+                let positions = retrieve_account_open_positions().await?;
+                let hedge = find_open_hedge(&positions, &token)?;
+                let ((b, hl), fees) = try_join!(
+                    latest_orderbooks(&token, hedge.binance_quote),
+                    retrieve_fee_schedule(&token, hedge.binance_quote)
+                )?;
 
-            let b_spot = get_mid_price(&b)?;
-            let hl_spot = get_mid_price(&hl)?;
+                let (exit_long, exit_short) =
+                    quote_hedge_exit(&b, &hl, hedge.long, hedge.size, &fees)?;
+                let (b_quote, hl_quote) = split_by_platform(&exit_long, &exit_short);
 
-            let (quote_a, quote_b) = match long {
-                Platform::Binance => (
-                    retrieve_quote_(b.asks, amount / 2.0, b_spot, Platform::Binance)?,
-                    retrieve_quote_(hl.bids, amount / 2.0, hl_spot, Platform::Hyperliquid)?,
-                ),
-                Platform::Hyperliquid => (
-                    retrieve_quote_(hl.asks, amount / 2.0, hl_spot, Platform::Hyperliquid)?,
-                    retrieve_quote_(b.bids, amount / 2.0, b_spot, Platform::Binance)?,
-                ),
-            };
-
-            let (b_slippage, hl_slippage) = if quote_a.platform == Platform::Binance {
-                (quote_a.slippage, quote_b.slippage)
-            } else {
-                (quote_b.slippage, quote_a.slippage)
-            };
+                match output {
+                    OutputFormat::Table => {
+                        println!("{}", build_quote_table("Exit", &exit_short, &exit_long));
+                        println!("{}", build_fill_table(b_quote, hl_quote));
+                    }
+                    OutputFormat::Json => {
+                        println!("{}", to_json(&json!({ "exit": [exit_short, exit_long] }))?)
+                    }
+                    OutputFormat::Csv => println!(
+                        "{}",
+                        to_csv(&[
+                            QuoteRow::new(Leg::Exit, &exit_short),
+                            QuoteRow::new(Leg::Exit, &exit_long)
+                        ])?
+                    ),
+                }
+                return Ok(());
+            }
 
-            let platform_fees_bps =
-                ((quote_a.platform_fees + quote_b.platform_fees) / 2.0) * 10_000.0;
-            let spread_bps = -(((quote_b.expected_execution_price
-                - quote_a.expected_execution_price)
-                / quote_a.expected_execution_price)
-                * 10_000.0);
-            let total_fees_bps = ((b_slippage + hl_slippage) * 10_000.0) + platform_fees_bps;
-
-            let mut t = Table::new();
-
-            t.add_row(Row::new(vec![Cell::new("Quote (bps)")]));
-            t.add_row(Row::new(vec![
-                Cell::new("Slippage Binance"),
-                Cell::new(&format!("{:.4}", b_slippage * 10_000.0)),
-            ]));
-            t.add_row(Row::new(vec![
-                Cell::new("Slippage Hyperliquid"),
-                Cell::new(&format!("{:.4}", hl_slippage * 10_000.0)),
-            ]));
-            t.add_row(Row::new(vec![
-                Cell::new("Platform Fees"),
-                Cell::new(&format!("{platform_fees_bps:.4}")),
-            ]));
-            t.add_row(Row::new(vec![
-                Cell::new("Total Fees"),
-                Cell::new(&format!("{:.4}", total_fees_bps)),
-            ]));
-
-            println!("Spread: {:.4}", spread_bps);
-            println!("{t}");
-        }
-        Commands::OrderbookDepth { token } => {
-            let (b_orderbook, hl_orderbook) = try_join!(
-                retrieve_binance_order_book(&token),
-                retrieve_hl_order_book(&token),
+            let long = long.context("--long is required when quoting a new position")?;
+            let target = FillTarget::from_args(usd, size)?;
+            let quote = quote.unwrap_or(profile().binance.quote);
+            let ((b, hl), funding_spread, fees) = try_join!(
+                latest_orderbooks(&token, quote),
+                retrieve_funding_spread(&token, quote, long),
+                retrieve_fee_schedule(&token, quote)
             )?;
 
-            let (b_bid, b_ask) = b_orderbook.get_total_depth();
-            let (hl_bid, hl_ask) = hl_orderbook.get_total_depth();
-
-            let mut f = Formatter::new()
-                .precision(Precision::Decimals(0))
-                .prefix("$")?
-                .separator(',')?;
-
-            let text = format!(
-                r#"Orderbook Depth {}
-Binance: Bids {} — Asks {}
-Hyperliquid: Bids {} — Asks {}
-"#,
-                token,
-                f.fmt2(b_bid).to_string(),
-                f.fmt2(b_ask).to_string(),
-                f.fmt2(hl_bid).to_string(),
-                f.fmt2(hl_ask).to_string()
-            );
-            println!("{text}");
+            let round_trip = quote_round_trip(&b, &hl, long, target, &fees)?;
+
+            match output {
+                OutputFormat::Table => {
+                    let [entry_long, entry_short] = &round_trip.entry;
+                    let [exit_short, exit_long] = &round_trip.exit;
+                    let (b_quote, hl_quote) = split_by_platform(entry_long, entry_short);
+
+                    println!("{}", build_quote_table("Entry", entry_long, entry_short));
+                    println!("{}", build_quote_table("Exit", exit_short, exit_long));
+                    println!(
+                        "Round trip: {:.4} bps",
+                        round_trip.round_trip_cost * 10_000.0
+                    );
+                    println!("{}", build_fill_table(b_quote, hl_quote));
+                    println!(
+                        "{}",
+                        build_break_even_table(round_trip.round_trip_cost, funding_spread)
+                    );
+                }
+                OutputFormat::Json => {
+                    let break_even =
+                        calculate_break_even(round_trip.round_trip_cost, funding_spread);
+                    let json = json!({
+                        "entry": round_trip.entry,
+                        "exit": round_trip.exit,
+                        "round_trip_cost": round_trip.round_trip_cost,
+                        "funding_spread": funding_spread,
+                        "break_even": break_even,
+                    });
+                    println!("{}", to_json(&json)?)
+                }
+                OutputFormat::Csv => println!("{}", to_csv(&round_trip.rows())?),
+            }
+        }
+        Commands::OrderbookDepth {
+            token,
+            watch,
+            quote,
+        } => {
+            let quote = quote.unwrap_or(profile().binance.quote);
+            if watch {
+                load_symbols().await?;
+                let live_orderbooks = live_orderbooks();
+                live_orderbooks.subscribe(&token, quote);
+
+                let mut interval = tokio::time::interval(Duration::from_secs(1));
+                loop {
+                    interval.tick().await;
+                    if let Some((b, hl)) = live_orderbooks.orderbooks(&token, quote) {
+                        match output {
+                            OutputFormat::Table => {
+                                println!("{}", format_orderbook_depth(&token, &b, &hl)?)
+                            }
+                            OutputFormat::Json => {
+                                println!("{}", to_json(&summarize_depth(&token, &b, &hl))?)
+                            }
+                            OutputFormat::Csv => {
+                                println!("{}", to_csv(&summarize_depth(&token, &b, &hl))?)
+                            }
+                        }
+                    }
+                }
+            }
+
+            let (b_orderbook, hl_orderbook) = retrieve_orderbooks(&token, quote).await?;
+
+            match output {
+                OutputFormat::Table => {
+                    let text = format_orderbook_depth(&token, &b_orderbook, &hl_orderbook)?;
+                    println!("{text}");
+                }
+                OutputFormat::Json => println!(
+                    "{}",
+                    to_json(&summarize_depth(&token, &b_orderbook, &hl_orderbook))?
+                ),
+                OutputFormat::Csv => println!(
+                    "{}",
+                    to_csv(&summarize_depth(&token, &b_orderbook, &hl_orderbook))?
+                ),
+            }
         }
         Commands::Execute {
             token,
+            usd,
             size,
             long,
             max_slippage,
+            leverage,
+            isolated,
+            cross,
+            quote,
         } => {
-            let (b, hl) = retrieve_orderbooks(&token).await?;
+            let target = FillTarget::from_args(usd, size)?;
+            let max_slippage = max_slippage.unwrap_or(profile().max_slippage());
+            let isolated = (isolated || cross).then_some(isolated);
+            let leverage = profile().leverage_settings(leverage, isolated);
+            if isolated.is_some() && leverage.is_none() {
+                bail!("--isolated and --cross need --leverage or a leverage in the profile")
+            }
+            let quote = quote.unwrap_or(profile().binance.quote);
+            let execution = open_hedge(&token, quote, target, long, max_slippage, leverage).await?;
+            let [b, h] = &execution.orders;
 
-            let b_mp = get_mid_price(&b)?;
-            let hl_mp = get_mid_price(&hl)?;
+            match output {
+                OutputFormat::Table => {
+                    println!("order filled one: {:?}", b);
+                    println!("order filled two: {:?}", h);
+                    println!("quote slippage: {:.4}", execution.quote_slippage);
+                    println!("real slippage: {:.4}", execution.real_slippage);
+                    println!("quote spread: {:.4}", execution.quote_spread);
+                    println!("real spread: {:.4}", execution.real_spread);
+                }
+                OutputFormat::Json => println!("{}", to_json(&execution)?),
+                OutputFormat::Csv => println!("{}", to_csv(&execution.orders)?),
+            }
+        }
+        Commands::Hedge { token, dry_run } => {
+            let adjustment = adjust_hedge(&token, dry_run).await?;
+            let drift = &adjustment.drift;
+            let quote = &adjustment.quote;
 
-            let ((buy_expected_px, buy_mp), (sell_expected_px, sell_mp)) = match long {
-                Platform::Binance => (
-                    (get_expected_execution_price(b.asks, size / 2.0)?, b_mp),
-                    (get_expected_execution_price(hl.bids, size / 2.0)?, hl_mp),
-                ),
-                Platform::Hyperliquid => (
-                    (get_expected_execution_price(hl.asks, size / 2.0)?, hl_mp),
-                    (get_expected_execution_price(b.bids, size / 2.0)?, b_mp),
-                ),
+            match output {
+                OutputFormat::Table => {
+                    println!(
+                        "{token} long {:.4} / short {:.4}, net {:.4} tokens (${:.2})",
+                        drift.long_size, drift.short_size, drift.net_size, drift.net_usd
+                    );
+                    println!(
+                        "{} {:.4} on {:?} at {:.6}, slippage {:.4} bps, fees {:.4} bps",
+                        if drift.net_size > 0.0 { "sell" } else { "buy" },
+                        quote.size,
+                        quote.platform,
+                        quote.expected_execution_price,
+                        quote.slippage * 10_000.0,
+                        quote.platform_fees * 10_000.0
+                    );
+                    if let Some(order) = &adjustment.order {
+                        println!(
+                            "filled {:?} {:.4} on {:?} at {:.6}",
+                            order.side, order.size, order.platform, order.avg_price
+                        );
+                    }
+                }
+                OutputFormat::Json => println!("{}", to_json(&adjustment)?),
+                OutputFormat::Csv => println!("{}", to_csv(&[drift])?),
+            }
+        }
+        Commands::Alerts {
+            webhooks,
+            min_spread_apr,
+            max_leverage,
+            min_liquidation_distance,
+            interval,
+        } => {
+            let strategy = &profile().strategy;
+            let rules = AlertRules {
+                // apr percentage to hourly decimal form
+                min_funding_spread: min_spread_apr
+                    .or(strategy.min_spread_apr)
+                    .map(|apr| apr / 100.0 / 365.0 / 24.0),
+                max_leverage: max_leverage.or(strategy.max_leverage),
+                // percentage to decimal form
+                min_liquidation_distance: min_liquidation_distance
+                    .or(strategy.min_liquidation_distance)
+                    .map(|pct| pct / 100.0),
+                spread_flip: true,
+                unhedged_legs: true,
             };
+            let webhooks = match webhooks.is_empty() {
+                true => profile().alerts.webhooks.clone(),
+                false => webhooks,
+            };
+            if webhooks.is_empty() {
+                bail!("No webhook to alert, pass --webhook or set alerts.webhooks in the profile")
+            }
+            let webhooks = webhooks.into_iter().map(Webhook::new).collect();
+            let interval = interval.unwrap_or(profile().alert_interval());
 
-            let buy_slippage = calculate_pct_difference(buy_expected_px, buy_mp);
-            let sell_slippage = calculate_pct_difference(sell_expected_px, sell_mp);
-
-            let total_slippage_bps = (buy_slippage + sell_slippage) * 10_000.0;
+            run_alerts(rules, webhooks, Duration::from_secs(interval)).await;
+        }
+        Commands::Rebalance {
+            execute,
+            yes,
+            min_transfer,
+        } => {
+            let (binance, hyperliquid) = retrieve_venue_funds().await?;
+            let min_transfer = min_transfer.unwrap_or(profile().min_rebalance_transfer());
+            let plan = plan_rebalance(
+                &binance,
+                &hyperliquid,
+                profile().binance.quote,
+                min_transfer,
+            );
 
-            if total_slippage_bps > max_slippage {
-                bail!(
-                    "Total slippage of {:.4} exceeds maximum slippage of {:.4}.",
-                    total_slippage_bps,
-                    max_slippage
-                )
+            match output {
+                OutputFormat::Table => println!(
+                    "{}",
+                    build_rebalance_table(&binance, &hyperliquid, plan.as_ref())
+                ),
+                OutputFormat::Json => println!(
+                    "{}",
+                    to_json(&json!({ "venues": [binance, hyperliquid], "plan": plan }))?
+                ),
+                OutputFormat::Csv => println!("{}", to_csv(&[binance, hyperliquid])?),
             }
 
-            let (b_token, hl_token) = format_token(&token);
-
-            let (b, h) = match long {
-                Platform::Binance => try_join!(
-                    binance::execute_mkt_order(b_token, size / 2.0, true),
-                    hyperliquid::execute_mkt_order(hl_token, size / 2.0, false)
-                )?,
-                Platform::Hyperliquid => try_join!(
-                    binance::execute_mkt_order(b_token, size / 2.0, false),
-                    hyperliquid::execute_mkt_order(hl_token, size / 2.0, true)
-                )?,
+            let Some(plan) = plan.filter(|_| execute) else {
+                return Ok(());
             };
 
-            // quote costs (bps)
-            let quote_slippage = total_slippage_bps;
-            let quote_spread =
-                -(((sell_expected_px - buy_expected_px) / buy_expected_px) * 10_000.0);
-
-            // real costs
-            let b_slippage = calculate_pct_difference(b.avg_price, b_mp);
-            let hl_slippage = calculate_pct_difference(h.avg_price, hl_mp);
-            let real_slippage = (b_slippage + hl_slippage) * 10_000.0; // bps
-            let real_spread = if b.side == Side::Buy {
-                -(((h.avg_price - b.avg_price) / b.avg_price) * 10_000.0)
-            } else {
-                -(((b.avg_price - h.avg_price) / h.avg_price) * 10_000.0)
+            if !yes {
+                print!(
+                    "Type yes to move ${:.2} from {:?} to {:?}: ",
+                    plan.amount, plan.from, plan.to
+                );
+                std::io::stdout().flush()?;
+
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if answer.trim() != "yes" {
+                    bail!("Rebalance cancelled")
+                }
+            }
+
+            execute_rebalance(&plan, &LiveTransferRails::default()).await?;
+            println!("Rebalance complete");
+        }
+        Commands::ApproveAgent { keystore } => {
+            // asked first so a mistyped passphrase doesn't leave an approved agent behind
+            let passphrase = read_new_passphrase()?;
+            let agent = approve_hl_agent().await?;
+            encrypt_wallet(&keystore, &agent, &passphrase)?;
+            println!(
+                "Approved agent {:?}, wrote {}. Set credentials.agent_keystore to it, \
+                 the master key is then only needed for withdrawals",
+                agent.address(),
+                keystore.display()
+            );
+        }
+        Commands::Serve { address } => serve(address).await?,
+        Commands::Config {
+            command: ConfigCommand::Check,
+        } => {
+            let problems = profile().validate(|key| std::env::var(key).ok());
+            if problems.is_empty() {
+                println!("Profile {profile_name} is valid");
+                return Ok(());
+            }
+            for problem in &problems {
+                println!("- {problem}");
+            }
+            bail!("Profile {profile_name} has {} problem(s)", problems.len())
+        }
+        Commands::Config {
+            command: ConfigCommand::EncryptSecrets { path },
+        } => {
+            let secrets = Secrets {
+                binance_api_key: binance_api_key().ok(),
+                binance_secret_key: binance_secret_key().ok(),
+                mnemonic: mnemonic().ok(),
             };
+            if secrets == Secrets::default() {
+                bail!("No credentials to encrypt in the profile or environment")
+            }
 
-            println!("order filled one: {:?}", b);
-            println!("order filled two: {:?}", h);
-            println!("quote slippage: {:.4}", quote_slippage);
-            println!("real slippage: {:.4}", real_slippage);
-            println!("quote spread: {:.4}", quote_spread);
-            println!("real spread: {:.4}", real_spread);
+            encrypt_secrets(&path, &secrets, &read_new_passphrase()?)?;
+            println!(
+                "Wrote {}, set credentials.secrets to it and remove the plain text keys",
+                path.display()
+            );
+        }
+        Commands::Config {
+            command: ConfigCommand::EncryptWallet { path },
+        } => {
+            encrypt_wallet(&path, &get_wallet()?, &read_new_passphrase()?)?;
+            println!(
+                "Wrote {}, set credentials.keystore to it and remove the mnemonic",
+                path.display()
+            );
         }
     }
 
     Ok(())
 }
+
+/// commands that sign requests, public market data is read without unlocking
+fn needs_credentials(command: &Commands) -> bool {
+    !matches!(
+        command,
+        Commands::FundingRates { .. }
+            | Commands::Forecast { .. }
+            | Commands::FundingHistory { .. }
+            | Commands::OrderbookDepth { .. }
+    )
+}
+
+/// commands that read the accounts or trade
+fn uses_accounts(command: &Commands) -> bool {
+    matches!(
+        command,
+        Commands::Balance { .. }
+            | Commands::Dashboard
+            | Commands::Quote {
+                exit_position: true,
+                ..
+            }
+            | Commands::Execute { .. }
+            | Commands::Hedge { .. }
+            | Commands::Alerts { .. }
+            | Commands::Rebalance { .. }
+            | Commands::ApproveAgent { .. }
+            | Commands::Serve { .. }
+    )
+}
@@ -0,0 +1,295 @@
+use crate::{
+    balances::{Balance, Position},
+    compare_funding_rates::JointFundingRate,
+    symbols::symbols,
+};
+use anyhow::Result;
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::{collections::HashMap, future::Future, sync::LazyLock, time::Instant};
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// process wide metrics, what `/metrics` exports
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+pub struct Metrics {
+    registry: Registry,
+    funding_spread: GaugeVec,
+    account_equity: GaugeVec,
+    margin_ratio: GaugeVec,
+    leverage: GaugeVec,
+    net_delta: GaugeVec,
+    funding_accrued: GaugeVec,
+    liquidation_distance: GaugeVec,
+    request_duration: HistogramVec,
+    request_errors: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("funding_arb".to_string()), None)
+            .expect("valid metrics prefix");
+
+        let gauge = |name: &str, help: &str, labels: &[&str]| {
+            let gauge = GaugeVec::new(Opts::new(name, help), labels).expect("valid gauge");
+            registry
+                .register(Box::new(gauge.clone()))
+                .expect("gauge registered once");
+            gauge
+        };
+
+        let funding_spread = gauge(
+            "funding_spread_hourly",
+            "hourly funding rate difference between both venues, decimal form",
+            &["token"],
+        );
+        let account_equity = gauge("account_equity_usd", "margin balance", &["venue"]);
+        let margin_ratio = gauge(
+            "account_margin_ratio",
+            "maintenance margin over margin balance, liquidation at 1",
+            &["venue"],
+        );
+        let leverage = gauge(
+            "account_leverage",
+            "open notional over margin balance",
+            &["venue"],
+        );
+        let net_delta = gauge(
+            "net_delta_usd",
+            "long minus short notional across both venues",
+            &["token"],
+        );
+        let funding_accrued = gauge(
+            "funding_accrued_usd",
+            "funding collected since the position was opened",
+            &["venue", "token"],
+        );
+        let liquidation_distance = gauge(
+            "liquidation_distance",
+            "price move that liquidates the leg, decimal form",
+            &["venue", "token"],
+        );
+
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("request_duration_seconds", "latency of exchange requests"),
+            &["endpoint"],
+        )
+        .expect("valid histogram");
+        let request_errors = IntCounterVec::new(
+            Opts::new("request_errors_total", "failed exchange requests"),
+            &["endpoint"],
+        )
+        .expect("valid counter");
+        registry
+            .register(Box::new(request_duration.clone()))
+            .expect("histogram registered once");
+        registry
+            .register(Box::new(request_errors.clone()))
+            .expect("counter registered once");
+
+        Self {
+            registry,
+            funding_spread,
+            account_equity,
+            margin_ratio,
+            leverage,
+            net_delta,
+            funding_accrued,
+            liquidation_distance,
+            request_duration,
+            request_errors,
+        }
+    }
+
+    pub fn record_funding_rates(&self, funding_rates: &[JointFundingRate]) {
+        self.funding_spread.reset();
+        for jfr in funding_rates {
+            self.funding_spread
+                .with_label_values(&[&jfr.name])
+                .set(jfr.funding_rate_difference);
+        }
+    }
+
+    pub fn record_balance(&self, balance: &Balance) {
+        let venues = [
+            (
+                "binance",
+                balance.binance_balance,
+                balance.binance_open_positions,
+                balance.binance_maintenance_margin,
+            ),
+            (
+                "hyperliquid",
+                balance.hyperliquid_balance,
+                balance.hyperliquid_open_positions,
+                balance.hyperliquid_maintenance_margin,
+            ),
+        ];
+
+        self.margin_ratio.reset();
+        self.leverage.reset();
+        for (venue, equity, open_positions, maintenance_margin) in venues {
+            self.account_equity.with_label_values(&[venue]).set(equity);
+            // ratios of an empty account mean nothing
+            if equity <= 0.0 {
+                continue;
+            }
+            self.margin_ratio
+                .with_label_values(&[venue])
+                .set(maintenance_margin / equity);
+            self.leverage
+                .with_label_values(&[venue])
+                .set(open_positions / equity);
+        }
+    }
+
+    pub fn record_positions(&self, positions: &[Position]) {
+        let mut net_delta: HashMap<String, f64> = HashMap::new();
+
+        self.funding_accrued.reset();
+        self.liquidation_distance.reset();
+        for p in positions {
+            let token = symbols().canonical(p.platform, &p.coin);
+            let venue = format!("{:?}", p.platform).to_lowercase();
+
+            // binance signs the notional of shorts, hyperliquid does not
+            let delta = if p.direction == "long" {
+                p.notional.abs()
+            } else {
+                -p.notional.abs()
+            };
+            *net_delta.entry(token.clone()).or_default() += delta;
+
+            self.funding_accrued
+                .with_label_values(&[&venue, &token])
+                .set(p.funding);
+            if let Some(distance) = p.liquidation_distance() {
+                self.liquidation_distance
+                    .with_label_values(&[&venue, &token])
+                    .set(distance);
+            }
+        }
+
+        self.net_delta.reset();
+        for (token, delta) in net_delta {
+            self.net_delta.with_label_values(&[&token]).set(delta);
+        }
+    }
+
+    /// times `request` and counts it under `endpoint` when it fails
+    pub async fn track<T>(
+        &self,
+        endpoint: &str,
+        request: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let start = Instant::now();
+        let result = request.await;
+
+        self.request_duration
+            .with_label_values(&[endpoint])
+            .observe(start.elapsed().as_secs_f64());
+        if result.is_err() {
+            self.request_errors.with_label_values(&[endpoint]).inc();
+        }
+
+        result
+    }
+
+    /// prometheus text format
+    pub fn encode(&self) -> Result<String> {
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_fixtures, util::Platform};
+    use anyhow::bail;
+
+    fn position(platform: Platform, coin: &str, direction: &str, notional: f64) -> Position {
+        Position {
+            funding: 12.5,
+            notional,
+            mark_price: 1.0,
+            liquidation_price: Some(0.5),
+            ..test_fixtures::position(platform, coin, direction)
+        }
+    }
+
+    #[test]
+    fn test_net_delta_per_token() -> Result<()> {
+        let metrics = Metrics::new();
+
+        metrics.record_positions(&[
+            position(Platform::Binance, "1000PEPE", "short", -1000.0),
+            position(Platform::Hyperliquid, "kPEPE", "long", 1010.0),
+            position(Platform::Binance, "ETH", "long", 500.0),
+        ]);
+
+        let text = metrics.encode()?;
+        assert!(text.contains(r#"funding_arb_net_delta_usd{token="PEPE"} 10"#));
+        assert!(text.contains(r#"funding_arb_net_delta_usd{token="ETH"} 500"#));
+        assert!(text
+            .contains(r#"funding_arb_funding_accrued_usd{token="PEPE",venue="hyperliquid"} 12.5"#));
+        assert!(
+            text.contains(r#"funding_arb_liquidation_distance{token="ETH",venue="binance"} 0.5"#)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_margin_ratio_skips_empty_venues() -> Result<()> {
+        let metrics = Metrics::new();
+
+        metrics.record_balance(&Balance {
+            binance_balance: 1000.0,
+            binance_open_positions: 3000.0,
+            binance_maintenance_margin: 50.0,
+            hyperliquid_balance: 0.0,
+            hyperliquid_open_positions: 0.0,
+            hyperliquid_maintenance_margin: 0.0,
+        });
+
+        let text = metrics.encode()?;
+        assert!(text.contains(r#"funding_arb_account_margin_ratio{venue="binance"} 0.05"#));
+        assert!(text.contains(r#"funding_arb_account_leverage{venue="binance"} 3"#));
+        assert!(text.contains(r#"funding_arb_account_equity_usd{venue="hyperliquid"} 0"#));
+        assert!(!text.contains(r#"margin_ratio{venue="hyperliquid"}"#));
+        assert!(!text.contains(r#"leverage{venue="hyperliquid"}"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_track_counts_errors() -> Result<()> {
+        let metrics = Metrics::new();
+
+        metrics.track("binance_order", async { Ok(()) }).await?;
+        let failed: Result<()> = metrics
+            .track("binance_order", async { bail!("rejected") })
+            .await;
+        assert!(failed.is_err());
+
+        let text = metrics.encode()?;
+        assert!(text.contains(r#"funding_arb_request_errors_total{endpoint="binance_order"} 1"#));
+        assert!(text
+            .contains(r#"funding_arb_request_duration_seconds_count{endpoint="binance_order"} 2"#));
+
+        Ok(())
+    }
+}
@@ -1,22 +1,120 @@
 use crate::{
     binance::retrieve_binance_order_book,
     hyperliquid::retrieve_hl_order_book,
-    util::{format_token, Orderbook},
+    live_orderbooks::live_orderbooks,
+    symbols::load_symbols,
+    util::{Orderbook, Platform, QuoteAsset},
 };
 use anyhow::{bail, Result};
+use numfmt::{Formatter, Precision};
+use serde::Serialize;
 use tokio::try_join;
 
-/// first element in the tuple is the binance orderbook
-pub async fn retrieve_orderbooks(token: &str) -> Result<(Orderbook, Orderbook)> {
-    let (b_token, hl_token) = format_token(token);
+/// first element in the tuple is the binance orderbook on its `quote` perpetual, both in units of the asset
+pub async fn retrieve_orderbooks(token: &str, quote: QuoteAsset) -> Result<(Orderbook, Orderbook)> {
+    let asset = load_symbols().await?.asset(token);
+    let (b_token, hl_token) = (
+        asset.symbol(Platform::Binance),
+        asset.symbol(Platform::Hyperliquid),
+    );
 
     let maybe_orderbooks = try_join!(
-        retrieve_binance_order_book(&b_token),
+        retrieve_binance_order_book(&b_token, quote),
         retrieve_hl_order_book(&hl_token)
     );
 
     match maybe_orderbooks {
         Err(e) => bail!("Could not retrieve orderbooks. {}", e),
-        Ok(orderbooks) => Ok(orderbooks),
+        Ok((b, hl)) => Ok((
+            b.to_asset_units(asset.scale(Platform::Binance)),
+            hl.to_asset_units(asset.scale(Platform::Hyperliquid)),
+        )),
+    }
+}
+
+/// the books streamed by `live_orderbooks` once both are synced, else a snapshot from `retrieve_orderbooks`
+pub async fn latest_orderbooks(token: &str, quote: QuoteAsset) -> Result<(Orderbook, Orderbook)> {
+    match live_orderbooks().orderbooks(token, quote) {
+        Some(orderbooks) => Ok(orderbooks),
+        None => retrieve_orderbooks(token, quote).await,
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DepthSummary {
+    pub token: String,
+    pub platform: Platform,
+    pub bids: f64, // USD
+    pub asks: f64, // USD
+}
+
+pub fn summarize_depth(token: &str, b: &Orderbook, hl: &Orderbook) -> Vec<DepthSummary> {
+    [b, hl]
+        .into_iter()
+        .map(|orderbook| {
+            let (bids, asks) = orderbook.get_total_depth();
+            DepthSummary {
+                token: token.to_string(),
+                platform: orderbook.platform,
+                bids,
+                asks,
+            }
+        })
+        .collect()
+}
+
+pub fn format_orderbook_depth(token: &str, b: &Orderbook, hl: &Orderbook) -> Result<String> {
+    let (b_bid, b_ask) = b.get_total_depth();
+    let (hl_bid, hl_ask) = hl.get_total_depth();
+
+    let mut f = Formatter::new()
+        .precision(Precision::Decimals(0))
+        .prefix("$")?
+        .separator(',')?;
+
+    let text = format!(
+        r#"Orderbook Depth {}
+Binance: Bids {} — Asks {}
+Hyperliquid: Bids {} — Asks {}
+"#,
+        token,
+        f.fmt2(b_bid).to_string(),
+        f.fmt2(b_ask).to_string(),
+        f.fmt2(hl_bid).to_string(),
+        f.fmt2(hl_ask).to_string()
+    );
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::LimitOrder;
+
+    fn book(platform: Platform, bid: f64) -> Orderbook {
+        Orderbook {
+            platform,
+            bids: vec![LimitOrder {
+                price: bid,
+                size: 1.0,
+            }],
+            asks: vec![LimitOrder {
+                price: bid + 1.0,
+                size: 1.0,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_latest_orderbooks_reads_streamed_books() -> Result<()> {
+        // streamed books of a token no venue lists, a rest request for it would fail
+        live_orderbooks().insert("LIVEUSDC", book(Platform::Binance, 10.0));
+        live_orderbooks().insert("LIVE", book(Platform::Hyperliquid, 11.0));
+
+        let (b, hl) = latest_orderbooks("LIVE", QuoteAsset::Usdc).await?;
+        assert_eq!((b.bids[0].price, hl.bids[0].price), (10.0, 11.0));
+
+        Ok(())
     }
 }
@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// how commands print their results
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// human readable tables
+    #[default]
+    Table,
+    /// raw values, rates in decimal form and amounts in USD
+    Json,
+    /// one row per struct with a header, same values as json
+    Csv,
+}
+
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+pub fn to_csv<T: Serialize>(rows: &[T]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use funding_rate_arbitrage::util::{OrderFilled, Platform, Side};
+
+    fn order_filled() -> OrderFilled {
+        OrderFilled {
+            token: "ETH".to_string(),
+            platform: Platform::Hyperliquid,
+            size: 1.5,
+            avg_price: 2500.25,
+            side: Side::Sell,
+        }
+    }
+
+    #[test]
+    fn test_csv_has_stable_header() -> Result<()> {
+        let csv = to_csv(&[order_filled()])?;
+
+        assert_eq!(
+            csv,
+            "token,platform,size,avg_price,side\nETH,hyperliquid,1.5,2500.25,sell\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_field_names() -> Result<()> {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[order_filled()])?)?;
+
+        assert_eq!(json[0]["platform"], "hyperliquid");
+        assert_eq!(json[0]["avg_price"], 2500.25);
+        assert_eq!(json[0]["side"], "sell");
+
+        Ok(())
+    }
+}
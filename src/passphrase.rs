@@ -0,0 +1,64 @@
+use anyhow::{bail, Result};
+use ratatui::crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use std::io::Write;
+
+/// `KEYSTORE_PASSPHRASE` when set, else typed on the terminal without echo
+pub fn read_passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("KEYSTORE_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+
+    eprint!("{prompt}");
+    std::io::stderr().flush()?;
+
+    enable_raw_mode()?;
+    let passphrase = read_hidden_line();
+    disable_raw_mode()?;
+    eprintln!();
+
+    passphrase
+}
+
+/// asks twice so a typo doesn't lock the file
+pub fn read_new_passphrase() -> Result<String> {
+    let passphrase = read_passphrase("New passphrase: ")?;
+    if passphrase.is_empty() {
+        bail!("The passphrase can't be empty")
+    }
+    if std::env::var("KEYSTORE_PASSPHRASE").is_err()
+        && read_passphrase("Repeat passphrase: ")? != passphrase
+    {
+        bail!("Passphrases don't match")
+    }
+
+    Ok(passphrase)
+}
+
+fn read_hidden_line() -> Result<String> {
+    let mut line = String::new();
+
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Enter => return Ok(line),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Esc => bail!("Cancelled"),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                bail!("Cancelled")
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+}
@@ -1,22 +1,63 @@
-use crate::binance::retrieve_binance_order_book;
 use crate::compare_funding_rates::compare_funding_rates;
-use crate::constants::{BINANCE_FEE, HYPERLIQUID_FEE};
-use crate::hyperliquid::retrieve_hl_order_book;
-use crate::util::{calculate_pct_difference, determine_short_based_on_fr, LimitOrder, Platform};
+use crate::fees::{retrieve_fee_schedule, FeeSchedule};
+use crate::orderbook::latest_orderbooks;
+use crate::token_price::get_mid_price;
+use crate::util::{
+    calculate_pct_difference, determine_short_based_on_fr, LimitOrder, Orderbook, Platform,
+};
 use anyhow::{bail, Result};
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
 use tokio::try_join;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Quote {
     pub platform: Platform,
-    pub expected_execution_price: f64,
+    pub expected_execution_price: f64, // vwap
+    pub worst_price: f64,
+    pub levels_consumed: usize,
     pub mid_price: f64,
     pub platform_fees: f64, // decimal pct
     pub slippage: f64,      // decimal pct
-    pub size: f64,
+    pub size: f64,          // amount of tokens filled
+    pub notional: f64,      // USD
 }
 
-/// first quote represents sell/short
+/// how much of the orderbook to take
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillTarget {
+    Usd(f64),
+    Size(f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub vwap: f64,
+    pub worst_price: f64,
+    pub levels_consumed: usize,
+    pub filled_size: f64,
+    pub notional: f64, // USD
+}
+
+impl FillTarget {
+    pub fn from_args(usd: Option<f64>, size: Option<f64>) -> Result<FillTarget> {
+        match (usd, size) {
+            (Some(usd), None) => Ok(FillTarget::Usd(usd)),
+            (None, Some(size)) => Ok(FillTarget::Size(size)),
+            _ => bail!("Provide either a USD amount or a token size"),
+        }
+    }
+
+    /// target for a single leg of the hedge
+    pub fn per_leg(self) -> FillTarget {
+        match self {
+            FillTarget::Usd(usd) => FillTarget::Usd(usd / 2.0),
+            FillTarget::Size(size) => FillTarget::Size(size / 2.0),
+        }
+    }
+}
+
+/// first quote represents sell/short, binance on the perpetual with the better funding
 pub async fn retrieve_quote_enter(token: String, amt: f64) -> Result<(Quote, Quote)> {
     let jfr = compare_funding_rates()
         .await?
@@ -24,131 +65,397 @@ pub async fn retrieve_quote_enter(token: String, amt: f64) -> Result<(Quote, Quo
         .find(|jfr| jfr.name == token)
         .expect("token must be in joint funding rates");
 
-    let platform = determine_short_based_on_fr(jfr);
+    let quote = jfr.binance_quote;
+    let platform = determine_short_based_on_fr(&jfr);
+    let ((b, hl), fees) = try_join!(
+        latest_orderbooks(&token, quote),
+        retrieve_fee_schedule(&token, quote)
+    )?;
 
     let (short_orderbook, long_orderbook) = match platform {
-        Platform::Binance => try_join!(
-            retrieve_binance_order_book(&token),
-            retrieve_hl_order_book(&token),
-        )?,
-        Platform::Hyperliquid => try_join!(
-            retrieve_hl_order_book(&token),
-            retrieve_binance_order_book(&token),
-        )?,
+        Platform::Binance => (b, hl),
+        Platform::Hyperliquid => (hl, b),
     };
 
     let mid_price_a = (short_orderbook.bids[0].price + short_orderbook.asks[0].price) / 2.0;
     let quote_a = retrieve_quote_(
-        short_orderbook.bids,
-        amt / 2.0,
+        &short_orderbook.bids,
+        FillTarget::Usd(amt / 2.0),
         mid_price_a,
         short_orderbook.platform,
+        &fees,
     )?;
 
     let mid_price_b = (long_orderbook.bids[0].price + long_orderbook.asks[0].price) / 2.0;
     let quote_b = retrieve_quote_(
-        long_orderbook.asks,
-        amt / 2.0,
+        &long_orderbook.asks,
+        FillTarget::Usd(amt / 2.0),
         mid_price_b,
         long_orderbook.platform,
+        &fees,
     )?;
 
     Ok((quote_a, quote_b))
 }
 
-pub fn retrieve_quote_(
-    orderbook: Vec<LimitOrder>,
-    amount: f64,
-    mid_price: f64,
-    platform: Platform,
-) -> Result<Quote> {
-    let mut remaining_amount = amount;
-    let mut total_cost = 0.0;
-    let mut total_quantity = 0.0;
+/// quotes both legs of a hedge, first quote is the long leg.
+/// both legs end up with the same token size so the hedge stays delta neutral
+pub fn quote_hedge(
+    b: &Orderbook,
+    hl: &Orderbook,
+    long: Platform,
+    target: FillTarget,
+    fees: &FeeSchedule,
+) -> Result<(Quote, Quote)> {
+    let (long_book, short_book) = match long {
+        Platform::Binance => (b, hl),
+        Platform::Hyperliquid => (hl, b),
+    };
 
-    if orderbook.is_empty() {
-        bail!("empty orderbook")
+    let long_quote = retrieve_quote_(
+        &long_book.asks,
+        target.per_leg(),
+        get_mid_price(long_book)?,
+        long_book.platform,
+        fees,
+    )?;
+    let short_quote = retrieve_quote_(
+        &short_book.bids,
+        FillTarget::Size(long_quote.size),
+        get_mid_price(short_book)?,
+        short_book.platform,
+        fees,
+    )?;
+
+    Ok((long_quote, short_quote))
+}
+
+/// quotes closing both legs of a hedge of `leg_size` tokens per leg, first quote is the long leg
+pub fn quote_hedge_exit(
+    b: &Orderbook,
+    hl: &Orderbook,
+    long: Platform,
+    leg_size: f64,
+    fees: &FeeSchedule,
+) -> Result<(Quote, Quote)> {
+    let (long_book, short_book) = match long {
+        Platform::Binance => (b, hl),
+        Platform::Hyperliquid => (hl, b),
+    };
+
+    // reverse sides: sell the long leg and buy back the short one
+    let long_quote = retrieve_quote_(
+        &long_book.bids,
+        FillTarget::Size(leg_size),
+        get_mid_price(long_book)?,
+        long_book.platform,
+        fees,
+    )?;
+    let short_quote = retrieve_quote_(
+        &short_book.asks,
+        FillTarget::Size(leg_size),
+        get_mid_price(short_book)?,
+        short_book.platform,
+        fees,
+    )?;
+
+    Ok((long_quote, short_quote))
+}
+
+/// entering a hedge and exiting it right away at the current books
+#[derive(Debug, Serialize)]
+pub struct RoundTripQuote {
+    pub entry: [Quote; 2],    // buy leg first
+    pub exit: [Quote; 2],     // buy leg first
+    pub round_trip_cost: f64, // decimal form relative to the notional of one leg
+}
+
+impl RoundTripQuote {
+    /// csv rows of the entry legs then the exit legs
+    pub fn rows(&self) -> Vec<QuoteRow> {
+        let entry = self.entry.iter().map(|q| QuoteRow::new(Leg::Entry, q));
+        let exit = self.exit.iter().map(|q| QuoteRow::new(Leg::Exit, q));
+        entry.chain(exit).collect()
     }
+}
 
-    for bid_ask in orderbook {
-        let price = bid_ask.price;
-        let size = bid_ask.size;
-        let total = price * size;
+/// half of a round trip
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Leg {
+    Entry,
+    Exit,
+}
 
-        // check if total is greater that remaining amount
-        if total > remaining_amount {
-            // partially take from that order
-            let quantity_needed = remaining_amount / price;
+/// a quote flattened into one csv row, csv can't nest the quote itself
+#[derive(Debug, Serialize)]
+pub struct QuoteRow {
+    pub leg: Leg,
+    pub platform: Platform,
+    pub expected_execution_price: f64,
+    pub worst_price: f64,
+    pub levels_consumed: usize,
+    pub mid_price: f64,
+    pub platform_fees: f64,
+    pub slippage: f64,
+    pub size: f64,
+    pub notional: f64,
+}
+
+impl QuoteRow {
+    pub fn new(leg: Leg, quote: &Quote) -> Self {
+        QuoteRow {
+            leg,
+            platform: quote.platform,
+            expected_execution_price: quote.expected_execution_price,
+            worst_price: quote.worst_price,
+            levels_consumed: quote.levels_consumed,
+            mid_price: quote.mid_price,
+            platform_fees: quote.platform_fees,
+            slippage: quote.slippage,
+            size: quote.size,
+            notional: quote.notional,
+        }
+    }
+}
 
-            total_cost += quantity_needed * price;
-            total_quantity += quantity_needed;
-            remaining_amount -= quantity_needed * price;
+pub fn quote_round_trip(
+    b: &Orderbook,
+    hl: &Orderbook,
+    long: Platform,
+    target: FillTarget,
+    fees: &FeeSchedule,
+) -> Result<RoundTripQuote> {
+    let (entry_long, entry_short) = quote_hedge(b, hl, long, target, fees)?;
+    let (exit_long, exit_short) = quote_hedge_exit(b, hl, long, entry_long.size, fees)?;
+
+    let round_trip_cost = calculate_hedge_cost(&entry_long, &entry_short)
+        + calculate_hedge_cost(&exit_long, &exit_short);
+
+    Ok(RoundTripQuote {
+        entry: [entry_long, entry_short],
+        exit: [exit_short, exit_long],
+        round_trip_cost,
+    })
+}
 
-            break;
+/// quotes trading the net size back to neutral on whichever venue costs less,
+/// selling when the long side is larger. `net_sizes` are trimmed to the lot of each venue, binance first,
+/// a venue whose lot is larger than the net size is skipped
+pub fn quote_delta_adjustment(
+    b: &Orderbook,
+    hl: &Orderbook,
+    net_sizes: (f64, f64),
+    fees: &FeeSchedule,
+) -> Result<Quote> {
+    let quote = |book: &Orderbook, net_size: f64| {
+        if net_size == 0.0 {
+            return Ok(None);
         }
+        let levels = if net_size > 0.0 {
+            &book.bids
+        } else {
+            &book.asks
+        };
+        retrieve_quote_(
+            levels,
+            FillTarget::Size(net_size.abs()),
+            get_mid_price(book)?,
+            book.platform,
+            fees,
+        )
+        .map(Some)
+    };
+
+    let cost = |q: &Quote| q.slippage + q.platform_fees;
 
-        total_cost += total;
-        total_quantity += size;
-        remaining_amount -= total;
+    match (quote(b, net_sizes.0)?, quote(hl, net_sizes.1)?) {
+        (Some(b_quote), Some(hl_quote)) if cost(&b_quote) > cost(&hl_quote) => Ok(hl_quote),
+        (Some(b_quote), _) => Ok(b_quote),
+        (None, Some(hl_quote)) => Ok(hl_quote),
+        (None, None) => bail!("Net size is below the lot size of both venues"),
     }
+}
 
-    // order book does not have enough orders for the amount
-    // leave one dollar as dust (more than enough)
-    if remaining_amount > 1.0 {
-        bail!("orderbook can't cover the amount to buy/sell")
+/// slippage plus fees of trading both legs, decimal form relative to the notional of one leg
+pub fn calculate_hedge_cost(quote_a: &Quote, quote_b: &Quote) -> f64 {
+    quote_a.slippage + quote_b.slippage + quote_a.platform_fees + quote_b.platform_fees
+}
+
+/// first element is the binance quote
+pub fn split_by_platform<'a>(quote_a: &'a Quote, quote_b: &'a Quote) -> (&'a Quote, &'a Quote) {
+    if quote_a.platform == Platform::Binance {
+        (quote_a, quote_b)
+    } else {
+        (quote_b, quote_a)
     }
+}
 
-    let execution_price = total_cost / total_quantity;
+/// quotes taking `target` from one side of the book, `mid_price` is the slippage reference.
+/// taking liquidity pays the taker rate of `fees`
+pub fn retrieve_quote_(
+    orderbook: &[LimitOrder],
+    target: FillTarget,
+    mid_price: f64,
+    platform: Platform,
+    fees: &FeeSchedule,
+) -> Result<Quote> {
+    let fill = walk_orderbook(orderbook, target)?;
 
     let quote = Quote {
-        expected_execution_price: execution_price,
+        expected_execution_price: fill.vwap,
+        worst_price: fill.worst_price,
+        levels_consumed: fill.levels_consumed,
         mid_price,
         platform,
-        slippage: calculate_pct_difference(execution_price, mid_price),
-        size: total_quantity,
-        platform_fees: match platform {
-            Platform::Binance => BINANCE_FEE,
-            Platform::Hyperliquid => HYPERLIQUID_FEE,
-        },
+        slippage: calculate_pct_difference(fill.vwap, mid_price),
+        size: fill.filled_size,
+        notional: fill.notional,
+        platform_fees: fees.taker(platform),
     };
 
     Ok(quote)
 }
 
-pub fn get_expected_execution_price(orderbook: Vec<LimitOrder>, size: f64) -> Result<f64> {
+/// `buy` and `sell` are the two legs of the same trade, in bps
+pub fn build_quote_table(title: &str, buy: &Quote, sell: &Quote) -> String {
+    let (b_quote, hl_quote) = split_by_platform(buy, sell);
+
+    let platform_fees_bps = ((buy.platform_fees + sell.platform_fees) / 2.0) * 10_000.0;
+    let spread_bps = -(((sell.expected_execution_price - buy.expected_execution_price)
+        / buy.expected_execution_price)
+        * 10_000.0);
+    let total_fees_bps = ((b_quote.slippage + hl_quote.slippage) * 10_000.0) + platform_fees_bps;
+
+    let mut t = Table::new();
+
+    t.add_row(Row::new(vec![Cell::new(&format!("{title} (bps)"))]));
+    t.add_row(Row::new(vec![
+        Cell::new("Spread"),
+        Cell::new(&format!("{spread_bps:.4}")),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Slippage Binance"),
+        Cell::new(&format!("{:.4}", b_quote.slippage * 10_000.0)),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Slippage Hyperliquid"),
+        Cell::new(&format!("{:.4}", hl_quote.slippage * 10_000.0)),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Platform Fees"),
+        Cell::new(&format!("{platform_fees_bps:.4}")),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Total Fees"),
+        Cell::new(&format!("{:.4}", total_fees_bps)),
+    ]));
+
+    t.to_string()
+}
+
+pub fn build_fill_table(b_quote: &Quote, hl_quote: &Quote) -> String {
+    let mut t = Table::new();
+
+    t.add_row(Row::new(vec![
+        Cell::new("Fill"),
+        Cell::new("Binance"),
+        Cell::new("Hyperliquid"),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("VWAP"),
+        Cell::new(&b_quote.expected_execution_price.to_string()),
+        Cell::new(&hl_quote.expected_execution_price.to_string()),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Worst price"),
+        Cell::new(&b_quote.worst_price.to_string()),
+        Cell::new(&hl_quote.worst_price.to_string()),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Levels consumed"),
+        Cell::new(&b_quote.levels_consumed.to_string()),
+        Cell::new(&hl_quote.levels_consumed.to_string()),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Size"),
+        Cell::new(&format!("{:.4}", b_quote.size)),
+        Cell::new(&format!("{:.4}", hl_quote.size)),
+    ]));
+    t.add_row(Row::new(vec![
+        Cell::new("Notional"),
+        Cell::new(&format!("${:.2}", b_quote.notional)),
+        Cell::new(&format!("${:.2}", hl_quote.notional)),
+    ]));
+
+    t.to_string()
+}
+
+/// takes liquidity level by level (best price first) until `target` is covered
+pub fn walk_orderbook(orderbook: &[LimitOrder], target: FillTarget) -> Result<Fill> {
     if orderbook.is_empty() {
         bail!("empty orderbook")
     }
 
-    let mut remaining_size = size;
     let mut total_cost = 0.0;
+    let mut total_quantity = 0.0;
+    let mut worst_price = orderbook[0].price;
+    let mut levels_consumed = 0;
+    // float leftovers below a billionth of the target count as filled
+    let is_filled = |total_cost: f64, total_quantity: f64| match target {
+        FillTarget::Usd(usd) => usd - total_cost <= usd * 1e-9,
+        FillTarget::Size(size) => size - total_quantity <= size * 1e-9,
+    };
 
     for order in orderbook {
-        if order.size >= remaining_size {
-            total_cost += remaining_size * order.price;
-            remaining_size = 0.0;
+        if is_filled(total_cost, total_quantity) {
             break;
         }
 
-        remaining_size -= order.size;
-        total_cost += order.size * order.price;
+        // what is still missing expressed in tokens at this level's price
+        let remaining_size = match target {
+            FillTarget::Usd(usd) => (usd - total_cost) / order.price,
+            FillTarget::Size(size) => size - total_quantity,
+        };
+
+        let quantity = order.size.min(remaining_size);
+
+        total_cost += quantity * order.price;
+        total_quantity += quantity;
+        worst_price = order.price;
+        levels_consumed += 1;
     }
 
-    if remaining_size > 0.0 {
-        bail!("orderbook can't cover the requested size")
+    // order book does not have enough orders for the amount
+    // leave one dollar as dust (more than enough)
+    let is_covered = match target {
+        FillTarget::Usd(usd) => usd - total_cost <= 1.0,
+        FillTarget::Size(_) => is_filled(total_cost, total_quantity),
+    };
+    if !is_covered || total_quantity == 0.0 {
+        bail!("orderbook can't cover the amount to buy/sell")
     }
 
-    Ok(total_cost / size)
+    Ok(Fill {
+        vwap: total_cost / total_quantity,
+        worst_price,
+        levels_consumed,
+        filled_size: total_quantity,
+        notional: total_cost,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::{LimitOrder, Platform};
+    use crate::{
+        constants::{BINANCE_FEE, HYPERLIQUID_FEE},
+        fees::TradingFees,
+    };
     use approx::assert_relative_eq;
 
     #[test]
-    fn test_get_expected_execution_price() -> Result<()> {
+    fn test_walk_orderbook_by_size() -> Result<()> {
         let orderbook = vec![
             LimitOrder {
                 price: 100.0,
@@ -163,8 +470,11 @@ mod tests {
                 size: 20.0,
             },
         ];
-        let result = get_expected_execution_price(orderbook, 25.0)?;
-        assert_relative_eq!(result, 100.6, max_relative = 0.0);
+        let result = walk_orderbook(&orderbook, FillTarget::Size(25.0))?;
+        assert_relative_eq!(result.vwap, 100.6, max_relative = 0.0);
+        assert_relative_eq!(result.worst_price, 101.0);
+        assert_eq!(result.levels_consumed, 2);
+        assert_relative_eq!(result.filled_size, 25.0);
 
         // Test case 3: Partial fill of last order
         let orderbook = vec![
@@ -181,11 +491,13 @@ mod tests {
                 size: 20.0,
             },
         ];
-        let result = get_expected_execution_price(orderbook, 30.0)?;
-        assert_relative_eq!(result, 100.8333333, epsilon = 1e-6);
+        let result = walk_orderbook(&orderbook, FillTarget::Size(30.0))?;
+        assert_relative_eq!(result.vwap, 100.8333333, epsilon = 1e-6);
+        assert_relative_eq!(result.worst_price, 102.0);
+        assert_eq!(result.levels_consumed, 3);
 
         let orderbook = vec![];
-        assert!(get_expected_execution_price(orderbook, 10.0).is_err());
+        assert!(walk_orderbook(&orderbook, FillTarget::Size(10.0)).is_err());
 
         let orderbook = vec![
             LimitOrder {
@@ -197,23 +509,89 @@ mod tests {
                 size: 15.0,
             },
         ];
-        assert!(get_expected_execution_price(orderbook, 30.0).is_err());
+        assert!(walk_orderbook(&orderbook, FillTarget::Size(30.0)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_orderbook_usd_and_size_agree() -> Result<()> {
+        let asks = get_mock_asks();
+
+        // first ask and half of the second one
+        let by_usd = walk_orderbook(&asks, FillTarget::Usd(104.5))?;
+        assert_relative_eq!(by_usd.filled_size, 12.5, epsilon = 1e-9);
+        assert_relative_eq!(by_usd.worst_price, 9.0);
+        assert_eq!(by_usd.levels_consumed, 2);
+
+        let by_size = walk_orderbook(&asks, FillTarget::Size(by_usd.filled_size))?;
+        assert_relative_eq!(by_size.vwap, by_usd.vwap, epsilon = 1e-9);
+        assert_relative_eq!(by_size.notional, 104.5, epsilon = 1e-9);
+        assert_eq!(by_size.levels_consumed, by_usd.levels_consumed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_orderbook_ignores_float_leftovers() -> Result<()> {
+        let level = |price, size| LimitOrder { price, size };
+        // 0.1 + 0.7 falls a hair short of 0.8
+        let asks = vec![level(10.0, 0.1), level(11.0, 0.7), level(50.0, 1.0)];
+
+        let fill = walk_orderbook(&asks, FillTarget::Size(0.8))?;
+        assert_eq!(fill.levels_consumed, 2);
+        assert_relative_eq!(fill.worst_price, 11.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_hedge_matches_leg_sizes() -> Result<()> {
+        let fees = FeeSchedule::default();
+        let b = Orderbook {
+            platform: Platform::Binance,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+        let hl = Orderbook {
+            platform: Platform::Hyperliquid,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+
+        let (long, short) = quote_hedge(
+            &b,
+            &hl,
+            Platform::Hyperliquid,
+            FillTarget::Usd(209.0),
+            &fees,
+        )?;
+        assert_eq!(long.platform, Platform::Hyperliquid);
+        assert_eq!(short.platform, Platform::Binance);
+        assert_relative_eq!(long.size, short.size, epsilon = 1e-9);
+
+        let (long, short) = quote_hedge(&b, &hl, Platform::Binance, FillTarget::Size(10.0), &fees)?;
+        assert_eq!(long.platform, Platform::Binance);
+        assert_relative_eq!(long.size, 5.0);
+        assert_relative_eq!(short.size, 5.0);
+        assert_relative_eq!(short.expected_execution_price, 10.0);
 
         Ok(())
     }
 
     #[test]
     fn test_sell_scenario() -> Result<()> {
+        let fees = FeeSchedule::default();
         let bids = get_mock_bids();
 
         // empty orderbook
-        let quote = retrieve_quote_(vec![], 247.0, 0.0, Platform::Binance);
+        let quote = retrieve_quote_(&[], FillTarget::Usd(247.0), 0.0, Platform::Binance, &fees);
         assert!(quote.is_err());
 
-        let quote = retrieve_quote_(bids.clone(), 247.0, 0.0, Platform::Binance);
+        let quote = retrieve_quote_(&bids, FillTarget::Usd(247.0), 0.0, Platform::Binance, &fees);
         assert!(quote.is_err());
 
-        let quote = retrieve_quote_(bids, 245.0, 0.0, Platform::Binance)?;
+        let quote = retrieve_quote_(&bids, FillTarget::Usd(245.0), 0.0, Platform::Binance, &fees)?;
         assert_relative_eq!(quote.expected_execution_price, 9.07, max_relative = 0.1);
 
         Ok(())
@@ -221,23 +599,202 @@ mod tests {
 
     #[test]
     fn test_buy_scenario() -> Result<()> {
+        let fees = FeeSchedule::default();
         let asks = get_mock_asks();
 
         // empty orderbook
-        let quote = retrieve_quote_(vec![], 247.0, 0.0, Platform::Hyperliquid);
+        let quote = retrieve_quote_(
+            &[],
+            FillTarget::Usd(247.0),
+            0.0,
+            Platform::Hyperliquid,
+            &fees,
+        );
         assert!(quote.is_err());
 
         // buy more than orderbook depth
-        let quote = retrieve_quote_(asks.clone(), 247.0, 0.0, Platform::Hyperliquid);
+        let quote = retrieve_quote_(
+            &asks,
+            FillTarget::Usd(247.0),
+            0.0,
+            Platform::Hyperliquid,
+            &fees,
+        );
         assert!(quote.is_err());
 
         // first ask and half of the second one
-        let quote = retrieve_quote_(asks, 104.5, 0.0, Platform::Hyperliquid)?;
+        let quote = retrieve_quote_(
+            &asks,
+            FillTarget::Usd(104.5),
+            0.0,
+            Platform::Hyperliquid,
+            &fees,
+        )?;
         assert_relative_eq!(quote.expected_execution_price, 8.36, max_relative = 0.1);
 
         Ok(())
     }
 
+    #[test]
+    fn test_quote_hedge_exit_reverses_sides() -> Result<()> {
+        let fees = FeeSchedule::default();
+        let b = Orderbook {
+            platform: Platform::Binance,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+        let hl = Orderbook {
+            platform: Platform::Hyperliquid,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+
+        let (entry_long, entry_short) =
+            quote_hedge(&b, &hl, Platform::Binance, FillTarget::Size(24.0), &fees)?;
+        let (exit_long, exit_short) =
+            quote_hedge_exit(&b, &hl, Platform::Binance, entry_long.size, &fees)?;
+
+        // long leg is bought from the asks and sold into the bids
+        assert_eq!(exit_long.platform, Platform::Binance);
+        assert_relative_eq!(
+            entry_long.expected_execution_price,
+            8.3333333,
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            exit_long.expected_execution_price,
+            entry_short.expected_execution_price
+        );
+        assert_relative_eq!(
+            exit_short.expected_execution_price,
+            entry_long.expected_execution_price
+        );
+        assert_relative_eq!(exit_long.size, 12.0);
+        assert_relative_eq!(exit_short.size, 12.0);
+
+        // more than the books can cover
+        assert!(quote_hedge_exit(&b, &hl, Platform::Binance, 30.0, &fees).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_rows_tag_legs() -> Result<()> {
+        let fees = FeeSchedule::default();
+        let b = Orderbook {
+            platform: Platform::Binance,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+        let hl = Orderbook {
+            platform: Platform::Hyperliquid,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+
+        let round_trip =
+            quote_round_trip(&b, &hl, Platform::Binance, FillTarget::Size(24.0), &fees)?;
+        let legs: Vec<_> = round_trip.rows().iter().map(|row| row.leg).collect();
+
+        assert_eq!(legs, [Leg::Entry, Leg::Entry, Leg::Exit, Leg::Exit]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_delta_adjustment_picks_cheaper_venue() -> Result<()> {
+        let fees = FeeSchedule::default();
+        let b = Orderbook {
+            platform: Platform::Binance,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+        let mut hl = Orderbook {
+            platform: Platform::Hyperliquid,
+            bids: get_mock_bids(),
+            asks: get_mock_asks(),
+        };
+
+        // same books, hyperliquid charges less
+        let quote = quote_delta_adjustment(&b, &hl, (5.0, 5.0), &fees)?;
+        assert_eq!(quote.platform, Platform::Hyperliquid);
+        assert_relative_eq!(quote.size, 5.0);
+        // net long is sold into the bids
+        assert_relative_eq!(quote.expected_execution_price, 10.0);
+
+        // thin hyperliquid bids cost more than the fee difference
+        hl.bids = vec![
+            LimitOrder {
+                price: 10.0,
+                size: 1.0,
+            },
+            LimitOrder {
+                price: 5.0,
+                size: 100.0,
+            },
+        ];
+        let quote = quote_delta_adjustment(&b, &hl, (5.0, 5.0), &fees)?;
+        assert_eq!(quote.platform, Platform::Binance);
+        // unless the net size is below the binance lot
+        let quote = quote_delta_adjustment(&b, &hl, (0.0, 5.0), &fees)?;
+        assert_eq!(quote.platform, Platform::Hyperliquid);
+        assert!(quote_delta_adjustment(&b, &hl, (0.0, 0.0), &fees).is_err());
+
+        // net short is bought from the asks
+        let quote = quote_delta_adjustment(&b, &hl, (-8.0, -8.0), &fees)?;
+        assert_relative_eq!(quote.expected_execution_price, 8.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quotes_use_account_taker_rate() -> Result<()> {
+        let asks = get_mock_asks();
+        let fees = FeeSchedule {
+            binance: TradingFees {
+                maker: -0.00005,
+                taker: 0.0003,
+            },
+            ..FeeSchedule::default()
+        };
+
+        let quote = retrieve_quote_(&asks, FillTarget::Size(8.0), 9.0, Platform::Binance, &fees)?;
+        assert_relative_eq!(quote.platform_fees, 0.0003);
+
+        let quote = retrieve_quote_(
+            &asks,
+            FillTarget::Size(8.0),
+            9.0,
+            Platform::Hyperliquid,
+            &fees,
+        )?;
+        assert_relative_eq!(quote.platform_fees, HYPERLIQUID_FEE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_hedge_cost() -> Result<()> {
+        let fees = FeeSchedule::default();
+        let asks = get_mock_asks();
+        let bids = get_mock_bids();
+
+        let buy = retrieve_quote_(&asks, FillTarget::Size(8.0), 9.0, Platform::Binance, &fees)?;
+        let sell = retrieve_quote_(
+            &bids,
+            FillTarget::Size(8.0),
+            9.0,
+            Platform::Hyperliquid,
+            &fees,
+        )?;
+
+        // one dollar away from the mid on both sides plus both venue fees
+        let expected = 1.0 / 9.0 * 2.0 + BINANCE_FEE + HYPERLIQUID_FEE;
+        assert_relative_eq!(calculate_hedge_cost(&buy, &sell), expected, epsilon = 1e-12);
+
+        Ok(())
+    }
+
     fn get_mock_bids() -> Vec<LimitOrder> {
         vec![
             LimitOrder {
@@ -0,0 +1,276 @@
+use super::{RebalancePlan, TransferStep};
+use crate::{
+    binance::{
+        convert_usdc, retrieve_spot_balance, retrieve_usdc_deposit_address, transfer_futures,
+        withdraw_usdc_to_arbitrum,
+    },
+    hyperliquid::{
+        deposit_to_bridge, get_wallet, retrieve_arbitrum_usdc_balance, transfer_arbitrum_usdc,
+        withdraw_from_bridge,
+    },
+    util::QuoteAsset,
+};
+use anyhow::{bail, Context, Result};
+use ethers::signers::Signer;
+use std::{collections::HashMap, future::Future, sync::Mutex, time::Duration};
+
+const FEE_ALLOWANCE: f64 = 5.0; // usd the hops before a step may have taken
+const ARBITRUM_WALLET: &str = "the arbitrum wallet";
+const BINANCE_SPOT: &str = "binance spot";
+
+/// carries out a single transfer step, live or against a mock
+pub trait TransferRails {
+    fn execute(&self, step: &TransferStep) -> impl Future<Output = Result<()>>;
+}
+
+/// moves real funds through the binance and hyperliquid apis and arbitrum
+pub struct LiveTransferRails {
+    pub poll_interval: Duration,
+    pub arrival_timeout: Duration, // withdrawals can take a while to land
+    // balance of a destination before the hop into it was sent, usdc already there isn't ours to move
+    baselines: Mutex<HashMap<&'static str, f64>>,
+}
+
+impl Default for LiveTransferRails {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(15),
+            arrival_timeout: Duration::from_secs(30 * 60),
+            baselines: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl LiveTransferRails {
+    /// remembers the balance of `what` before sending funds to it
+    async fn record_baseline<Fut>(&self, what: &'static str, balance: Fut) -> Result<()>
+    where
+        Fut: Future<Output = Result<f64>>,
+    {
+        let before = balance.await?;
+        self.baselines.lock().unwrap().insert(what, before);
+
+        Ok(())
+    }
+
+    /// polls `balance` until `amount` (minus fees) has arrived on top of the recorded baseline,
+    /// returns what arrived
+    async fn wait_for_arrival<F, Fut>(&self, what: &str, amount: f64, balance: F) -> Result<f64>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<f64>>,
+    {
+        let Some(before) = self.baselines.lock().unwrap().remove(what) else {
+            bail!("No balance of {what} was recorded before sending to it")
+        };
+        let deadline = tokio::time::Instant::now() + self.arrival_timeout;
+
+        loop {
+            let arrived = balance().await? - before;
+            if arrived >= amount - FEE_ALLOWANCE {
+                return Ok(arrived.min(amount));
+            }
+            if tokio::time::Instant::now() > deadline {
+                bail!(
+                    "{amount:.2} USDC did not arrive in {what} after {:?}, {arrived:.2} did",
+                    self.arrival_timeout
+                )
+            }
+
+            log::info!("Waiting for {amount:.2} USDC to arrive in {what} ({arrived:.2} so far)");
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+impl TransferRails for LiveTransferRails {
+    async fn execute(&self, step: &TransferStep) -> Result<()> {
+        match *step {
+            TransferStep::BinanceFuturesToSpot { asset, amount } => {
+                transfer_futures(asset, amount, false).await
+            }
+            TransferStep::BinanceBuyUsdc { amount } => convert_usdc(amount, true).await,
+            TransferStep::BinanceWithdrawToArbitrum { amount } => {
+                // the swap leaves slightly less usdc than usdt spent
+                let amount = amount.min(retrieve_spot_balance("USDC").await?);
+                let address = format!("{:?}", get_wallet()?.address());
+                self.record_baseline(ARBITRUM_WALLET, retrieve_arbitrum_usdc_balance())
+                    .await?;
+                withdraw_usdc_to_arbitrum(amount, &address).await
+            }
+            TransferStep::HyperliquidDeposit { amount } => {
+                let amount = self
+                    .wait_for_arrival(ARBITRUM_WALLET, amount, retrieve_arbitrum_usdc_balance)
+                    .await?;
+                deposit_to_bridge(amount).await
+            }
+            TransferStep::HyperliquidWithdraw { amount } => {
+                self.record_baseline(ARBITRUM_WALLET, retrieve_arbitrum_usdc_balance())
+                    .await?;
+                withdraw_from_bridge(amount).await
+            }
+            TransferStep::BinanceDepositFromArbitrum { amount } => {
+                let amount = self
+                    .wait_for_arrival(ARBITRUM_WALLET, amount, retrieve_arbitrum_usdc_balance)
+                    .await?;
+                let address = retrieve_usdc_deposit_address().await?;
+                self.record_baseline(BINANCE_SPOT, retrieve_spot_balance("USDC"))
+                    .await?;
+                transfer_arbitrum_usdc(amount, &address).await
+            }
+            TransferStep::BinanceSellUsdc { amount } => {
+                let amount = self
+                    .wait_for_arrival(BINANCE_SPOT, amount, || retrieve_spot_balance("USDC"))
+                    .await?;
+                convert_usdc(amount, false).await
+            }
+            TransferStep::BinanceSpotToFutures { asset, amount } => {
+                let amount = match asset {
+                    // nothing swapped it, the deposit from arbitrum may still be on its way
+                    QuoteAsset::Usdc => {
+                        self.wait_for_arrival(BINANCE_SPOT, amount, || {
+                            retrieve_spot_balance("USDC")
+                        })
+                        .await?
+                    }
+                    QuoteAsset::Usdt => amount.min(retrieve_spot_balance("USDT").await?),
+                };
+                transfer_futures(asset, amount, true).await
+            }
+        }
+    }
+}
+
+/// runs the plan's steps in order and stops at the first failure,
+/// funds moved by earlier steps are left where they are
+pub async fn execute_rebalance(plan: &RebalancePlan, rails: &impl TransferRails) -> Result<()> {
+    let total = plan.steps.len();
+
+    for (i, step) in plan.steps.iter().enumerate() {
+        log::info!("Step {}/{total}: {}", i + 1, step.describe());
+
+        rails.execute(step).await.with_context(|| {
+            format!(
+                "step {}/{total} ({}) failed, earlier steps already moved funds",
+                i + 1,
+                step.describe()
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rebalance::plan_rebalance, rebalance::VenueFunds, util::Platform};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockRails {
+        executed: Mutex<Vec<TransferStep>>,
+        fail_on: Option<usize>,
+    }
+
+    impl TransferRails for MockRails {
+        async fn execute(&self, step: &TransferStep) -> Result<()> {
+            let mut executed = self.executed.lock().unwrap();
+            if self.fail_on == Some(executed.len()) {
+                bail!("withdrawals suspended");
+            }
+            executed.push(step.clone());
+
+            Ok(())
+        }
+    }
+
+    fn plan() -> RebalancePlan {
+        let funds = |platform, equity| VenueFunds {
+            platform,
+            equity,
+            maintenance_margin: 0.0,
+            notional: 1000.0,
+            withdrawable: equity,
+        };
+
+        plan_rebalance(
+            &funds(Platform::Binance, 1500.0),
+            &funds(Platform::Hyperliquid, 500.0),
+            QuoteAsset::Usdt,
+            50.0,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_executes_steps_in_order() -> Result<()> {
+        let plan = plan();
+        let rails = MockRails::default();
+
+        execute_rebalance(&plan, &rails).await?;
+
+        assert_eq!(*rails.executed.lock().unwrap(), plan.steps);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_transfer_on_top_of_existing_balance() -> Result<()> {
+        let rails = LiveTransferRails {
+            poll_interval: Duration::from_millis(1),
+            arrival_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        // 1000 usdc sat in the wallet before the withdrawal, which lands on the third poll less a fee
+        let polls = Mutex::new(vec![1000.0, 1000.0, 1499.0]);
+        let balance = || async { Ok(polls.lock().unwrap().pop().unwrap_or(1499.0)) };
+        polls.lock().unwrap().reverse();
+
+        assert!(rails
+            .wait_for_arrival(ARBITRUM_WALLET, 500.0, balance)
+            .await
+            .is_err());
+
+        rails
+            .record_baseline(ARBITRUM_WALLET, async { Ok(1000.0) })
+            .await?;
+        let arrived = rails
+            .wait_for_arrival(ARBITRUM_WALLET, 500.0, balance)
+            .await?;
+        assert_eq!(arrived, 499.0);
+        assert!(polls.lock().unwrap().is_empty());
+
+        // nothing on top of the baseline never counts as arrived
+        let rails = LiveTransferRails {
+            arrival_timeout: Duration::ZERO,
+            ..rails
+        };
+        rails
+            .record_baseline(BINANCE_SPOT, async { Ok(1000.0) })
+            .await?;
+        let error = rails
+            .wait_for_arrival(BINANCE_SPOT, 500.0, || async { Ok(1000.0) })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("0.00 did"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_failed_step() {
+        let plan = plan();
+        let rails = MockRails {
+            fail_on: Some(2),
+            ..Default::default()
+        };
+
+        let error = execute_rebalance(&plan, &rails).await.unwrap_err();
+
+        assert_eq!(*rails.executed.lock().unwrap(), plan.steps[..2]);
+        assert!(error
+            .to_string()
+            .starts_with("step 3/4 (withdraw 500.00 USDC"));
+    }
+}
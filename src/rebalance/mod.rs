@@ -0,0 +1,8 @@
+mod execute;
+mod plan;
+
+pub use execute::{execute_rebalance, LiveTransferRails, TransferRails};
+pub use plan::{
+    build_rebalance_table, plan_rebalance, retrieve_venue_funds, RebalancePlan, TransferStep,
+    VenueFunds,
+};
@@ -0,0 +1,314 @@
+use crate::{
+    binance::account_information::retrieve_binance_account_info,
+    constants::HYPERLIQUID_WITHDRAW_FEE,
+    hyperliquid::account_information::retrieve_hl_account_info,
+    util::{Platform, QuoteAsset},
+};
+use anyhow::Result;
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+use tokio::try_join;
+
+/// margin of one venue, what the rebalance plan is computed from
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueFunds {
+    pub platform: Platform,
+    pub equity: f64,
+    pub maintenance_margin: f64,
+    pub notional: f64,
+    pub withdrawable: f64,
+}
+
+/// one hop of usdc between the venues, binance futures margin is usdt or usdc
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum TransferStep {
+    BinanceFuturesToSpot { asset: QuoteAsset, amount: f64 },
+    BinanceBuyUsdc { amount: f64 },
+    BinanceWithdrawToArbitrum { amount: f64 },
+    HyperliquidDeposit { amount: f64 },
+    HyperliquidWithdraw { amount: f64 },
+    BinanceDepositFromArbitrum { amount: f64 },
+    BinanceSellUsdc { amount: f64 },
+    BinanceSpotToFutures { asset: QuoteAsset, amount: f64 },
+}
+
+impl TransferStep {
+    pub fn describe(&self) -> String {
+        match self {
+            TransferStep::BinanceFuturesToSpot { asset, amount } => {
+                format!("move {amount:.2} {asset} from Binance futures to spot")
+            }
+            TransferStep::BinanceBuyUsdc { amount } => {
+                format!("swap {amount:.2} USDT for USDC on Binance spot")
+            }
+            TransferStep::BinanceWithdrawToArbitrum { amount } => {
+                format!("withdraw {amount:.2} USDC from Binance to the wallet on Arbitrum")
+            }
+            TransferStep::HyperliquidDeposit { amount } => {
+                format!("deposit {amount:.2} USDC to Hyperliquid through the bridge")
+            }
+            TransferStep::HyperliquidWithdraw { amount } => {
+                format!("withdraw {amount:.2} USDC from Hyperliquid to the wallet on Arbitrum")
+            }
+            TransferStep::BinanceDepositFromArbitrum { amount } => {
+                format!("send {amount:.2} USDC from the wallet to the Binance deposit address")
+            }
+            TransferStep::BinanceSellUsdc { amount } => {
+                format!("swap {amount:.2} USDC for USDT on Binance spot")
+            }
+            TransferStep::BinanceSpotToFutures { asset, amount } => {
+                format!("move {amount:.2} {asset} from Binance spot to futures")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RebalancePlan {
+    pub binance_target: f64,
+    pub hyperliquid_target: f64,
+    pub from: Platform,
+    pub to: Platform,
+    pub amount: f64, // usd leaving `from`, fees are taken along the way
+    pub steps: Vec<TransferStep>,
+}
+
+/// margin and open notional of both venues
+pub async fn retrieve_venue_funds() -> Result<(VenueFunds, VenueFunds)> {
+    let (b, hl) = try_join!(retrieve_binance_account_info(), retrieve_hl_account_info())?;
+
+    let binance = VenueFunds {
+        platform: Platform::Binance,
+        equity: b.total_margin_balance,
+        maintenance_margin: b.total_maint_margin,
+        notional: b.positions.iter().map(|p| p.notional.abs()).sum(),
+        withdrawable: b.max_withdraw_amount,
+    };
+    let hyperliquid = VenueFunds {
+        platform: Platform::Hyperliquid,
+        equity: hl.cross_margin_summary.account_value,
+        maintenance_margin: hl.cross_maintenance_margin_used,
+        notional: hl.cross_margin_summary.total_ntl_pos,
+        withdrawable: hl.withdrawable,
+    };
+
+    Ok((binance, hyperliquid))
+}
+
+/// splits equity so each venue keeps its maintenance margin and the rest follows open notional.
+/// binance futures margin in `margin` is swapped on spot unless it is usdc already.
+/// `None` when the venues are within `min_transfer` of their targets
+pub fn plan_rebalance(
+    binance: &VenueFunds,
+    hyperliquid: &VenueFunds,
+    margin: QuoteAsset,
+    min_transfer: f64,
+) -> Option<RebalancePlan> {
+    let total_equity = binance.equity + hyperliquid.equity;
+    let total_notional = binance.notional + hyperliquid.notional;
+    let free_margin = total_equity - binance.maintenance_margin - hyperliquid.maintenance_margin;
+
+    // no positions, split evenly
+    let binance_share = if total_notional > 0.0 {
+        binance.notional / total_notional
+    } else {
+        0.5
+    };
+
+    let binance_target = binance.maintenance_margin + free_margin * binance_share;
+    let hyperliquid_target = total_equity - binance_target;
+
+    let (from, surplus) = if binance.equity > binance_target {
+        (binance, binance.equity - binance_target)
+    } else {
+        (hyperliquid, hyperliquid.equity - hyperliquid_target)
+    };
+    let amount = surplus.min(from.withdrawable);
+
+    if amount < min_transfer {
+        return None;
+    }
+
+    let swap = margin == QuoteAsset::Usdt;
+    let steps = match from.platform {
+        Platform::Binance => [
+            Some(TransferStep::BinanceFuturesToSpot {
+                asset: margin,
+                amount,
+            }),
+            swap.then_some(TransferStep::BinanceBuyUsdc { amount }),
+            Some(TransferStep::BinanceWithdrawToArbitrum { amount }),
+            Some(TransferStep::HyperliquidDeposit { amount }),
+        ],
+        Platform::Hyperliquid => {
+            let arrived = amount - HYPERLIQUID_WITHDRAW_FEE;
+            [
+                Some(TransferStep::HyperliquidWithdraw { amount }),
+                Some(TransferStep::BinanceDepositFromArbitrum { amount: arrived }),
+                swap.then_some(TransferStep::BinanceSellUsdc { amount: arrived }),
+                Some(TransferStep::BinanceSpotToFutures {
+                    asset: margin,
+                    amount: arrived,
+                }),
+            ]
+        }
+    };
+    let steps = steps.into_iter().flatten().collect();
+
+    Some(RebalancePlan {
+        binance_target,
+        hyperliquid_target,
+        from: from.platform,
+        to: match from.platform {
+            Platform::Binance => Platform::Hyperliquid,
+            Platform::Hyperliquid => Platform::Binance,
+        },
+        amount,
+        steps,
+    })
+}
+
+/// equity next to the target of each venue, followed by the transfer steps
+pub fn build_rebalance_table(
+    binance: &VenueFunds,
+    hyperliquid: &VenueFunds,
+    plan: Option<&RebalancePlan>,
+) -> String {
+    let mut table = Table::new();
+
+    table.add_row(Row::new(vec![
+        Cell::new("Venue"),
+        Cell::new("Equity"),
+        Cell::new("Maintenance"),
+        Cell::new("Withdrawable"),
+        Cell::new("Target"),
+    ]));
+    for (venue, target) in [
+        (binance, plan.map(|p| p.binance_target)),
+        (hyperliquid, plan.map(|p| p.hyperliquid_target)),
+    ] {
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("{:?}", venue.platform)),
+            Cell::new(&format!("${:.2}", venue.equity)),
+            Cell::new(&format!("${:.2}", venue.maintenance_margin)),
+            Cell::new(&format!("${:.2}", venue.withdrawable)),
+            Cell::new(&target.map_or("-".to_string(), |t| format!("${t:.2}"))),
+        ]));
+    }
+
+    let Some(plan) = plan else {
+        return format!("{table}Balanced, nothing to transfer\n");
+    };
+
+    let steps: String = plan
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| format!("{}. {}\n", i + 1, step.describe()))
+        .collect();
+
+    format!(
+        "{table}Move ${:.2} from {:?} to {:?}:\n{steps}",
+        plan.amount, plan.from, plan.to
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn funds(platform: Platform, equity: f64, notional: f64) -> VenueFunds {
+        VenueFunds {
+            platform,
+            equity,
+            maintenance_margin: notional * 0.01,
+            notional,
+            withdrawable: equity,
+        }
+    }
+
+    #[test]
+    fn test_plan_moves_surplus_to_losing_leg() {
+        // same notional, hyperliquid leg lost 600 that binance gained
+        let binance = funds(Platform::Binance, 1600.0, 5000.0);
+        let hyperliquid = funds(Platform::Hyperliquid, 400.0, 5000.0);
+
+        let plan = plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdt, 50.0).unwrap();
+
+        assert_relative_eq!(plan.binance_target, 1000.0);
+        assert_relative_eq!(plan.hyperliquid_target, 1000.0);
+        assert_eq!(plan.from, Platform::Binance);
+        assert_eq!(plan.to, Platform::Hyperliquid);
+        assert_relative_eq!(plan.amount, 600.0);
+        assert_eq!(
+            plan.steps.last(),
+            Some(&TransferStep::HyperliquidDeposit { amount: 600.0 })
+        );
+
+        // withdrawals are capped by what the venue lets go of
+        let binance = VenueFunds {
+            withdrawable: 200.0,
+            ..binance
+        };
+        let plan = plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdt, 50.0).unwrap();
+        assert_relative_eq!(plan.amount, 200.0);
+    }
+
+    #[test]
+    fn test_plan_from_hyperliquid_pays_the_bridge_fee() {
+        let binance = funds(Platform::Binance, 700.0, 3000.0);
+        let hyperliquid = funds(Platform::Hyperliquid, 1300.0, 3000.0);
+
+        let plan = plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdt, 50.0).unwrap();
+
+        assert_eq!(
+            plan.steps,
+            vec![
+                TransferStep::HyperliquidWithdraw { amount: 300.0 },
+                TransferStep::BinanceDepositFromArbitrum { amount: 299.0 },
+                TransferStep::BinanceSellUsdc { amount: 299.0 },
+                TransferStep::BinanceSpotToFutures {
+                    asset: QuoteAsset::Usdt,
+                    amount: 299.0
+                },
+            ]
+        );
+
+        // already balanced
+        let hyperliquid = funds(Platform::Hyperliquid, 720.0, 3000.0);
+        assert!(plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdt, 50.0).is_none());
+    }
+
+    #[test]
+    fn test_plan_moves_usdc_margin_without_swapping() {
+        let binance = funds(Platform::Binance, 1600.0, 5000.0);
+        let hyperliquid = funds(Platform::Hyperliquid, 400.0, 5000.0);
+
+        let plan = plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdc, 50.0).unwrap();
+        assert_eq!(
+            plan.steps,
+            vec![
+                TransferStep::BinanceFuturesToSpot {
+                    asset: QuoteAsset::Usdc,
+                    amount: 600.0
+                },
+                TransferStep::BinanceWithdrawToArbitrum { amount: 600.0 },
+                TransferStep::HyperliquidDeposit { amount: 600.0 },
+            ]
+        );
+
+        let binance = funds(Platform::Binance, 700.0, 3000.0);
+        let hyperliquid = funds(Platform::Hyperliquid, 1300.0, 3000.0);
+        let plan = plan_rebalance(&binance, &hyperliquid, QuoteAsset::Usdc, 50.0).unwrap();
+        assert_eq!(
+            plan.steps.last(),
+            Some(&TransferStep::BinanceSpotToFutures {
+                asset: QuoteAsset::Usdc,
+                amount: 299.0
+            })
+        );
+        assert_eq!(plan.steps.len(), 3);
+    }
+}
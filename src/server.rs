@@ -0,0 +1,497 @@
+use anyhow::{Context, Result};
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use funding_rate_arbitrage::{
+    balances::{
+        find_open_hedge, retrieve_account_balance, retrieve_account_open_positions,
+        summarize_hedges, Balance, Position,
+    },
+    binance::retrieve_binance_past_daily_fh,
+    compare_funding_rates::{compare_funding_rates, JointFundingRate},
+    config::profile,
+    constants::MAX_DAYS_QUERY_FUNDING_HISTORY,
+    execution::{close_hedge, open_hedge, HedgeExecution},
+    fees::retrieve_fee_schedule,
+    funding_history_table::{join_daily_funding_rates, DailyFundingRate},
+    hyperliquid::retrieve_hl_past_daily_fh,
+    live_orderbooks::live_orderbooks,
+    metrics::metrics,
+    orderbook::latest_orderbooks,
+    quote::{quote_hedge_exit, quote_round_trip, FillTarget},
+    symbols::load_symbols,
+    util::{Platform, QuoteAsset},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{net::SocketAddr, time::Duration};
+use tokio::{join, net::TcpListener, try_join};
+
+const METRICS_REFRESH: Duration = Duration::from_secs(30);
+
+#[derive(Clone)]
+struct ServerState {
+    auth_token: Option<String>, // execute and close are disabled without one
+}
+
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl ToString) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+/// anything coming from the exchanges is on our side
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(e: E) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.into())
+    }
+}
+
+/// serves the same data as the cli over http, `SERVE_AUTH_TOKEN` enables execute and close
+pub async fn serve(address: SocketAddr) -> Result<()> {
+    let auth_token = std::env::var("SERVE_AUTH_TOKEN").ok();
+    if auth_token.is_none() {
+        log::warn!("SERVE_AUTH_TOKEN is not set, execute and close are disabled");
+    }
+
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Could not bind to {address}"))?;
+    log::info!("Listening on {address}");
+
+    tokio::spawn(refresh_metrics());
+    axum::serve(listener, router(auth_token)).await?;
+
+    Ok(())
+}
+
+/// keeps the `/metrics` gauges current with the same data as the cli,
+/// and the books of open hedges streaming for exit quotes and closes
+async fn refresh_metrics() {
+    let metrics = metrics();
+    let mut interval = tokio::time::interval(METRICS_REFRESH);
+
+    loop {
+        interval.tick().await;
+
+        let (funding_rates, positions, balance) = join!(
+            metrics.track("funding_rates", compare_funding_rates()),
+            metrics.track("open_positions", retrieve_account_open_positions()),
+            metrics.track("balance", retrieve_account_balance())
+        );
+
+        match funding_rates {
+            Ok(funding_rates) => metrics.record_funding_rates(&funding_rates),
+            Err(e) => log::warn!("Could not refresh funding rates. {e}"),
+        }
+        match positions {
+            Ok(positions) => {
+                metrics.record_positions(&positions);
+                for summary in summarize_hedges(&positions) {
+                    if let Some(hedge) = summary.hedge {
+                        live_orderbooks().subscribe(&summary.token, hedge.binance_quote);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Could not refresh open positions. {e}"),
+        }
+        match balance {
+            Ok(balance) => metrics.record_balance(&balance),
+            Err(e) => log::warn!("Could not refresh balances. {e}"),
+        }
+    }
+}
+
+fn router(auth_token: Option<String>) -> Router {
+    let state = ServerState { auth_token };
+
+    let trading = Router::new()
+        .route("/execute", post(execute))
+        .route("/close/:token", post(close))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_auth_token,
+        ));
+
+    Router::new()
+        .route("/funding-rates", get(funding_rates))
+        .route("/funding-history/:token", get(funding_history))
+        .route("/quote/:token", get(quote))
+        .route("/balance", get(balance))
+        .route("/positions", get(positions))
+        .route("/metrics", get(export_metrics))
+        .merge(trading)
+        .with_state(state)
+}
+
+async fn require_auth_token(
+    State(state): State<ServerState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(auth_token) = state.auth_token else {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Trading is disabled, set SERVE_AUTH_TOKEN to enable it",
+        ));
+    };
+
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if bearer != Some(auth_token.as_str()) {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid auth token",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+fn parse_platform(s: &str) -> Result<Platform, ApiError> {
+    s.parse()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))
+}
+
+fn parse_target(usd: Option<f64>, size: Option<f64>) -> Result<FillTarget, ApiError> {
+    FillTarget::from_args(usd, size).map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e))
+}
+
+async fn funding_rates() -> ApiResult<Vec<JointFundingRate>> {
+    Ok(Json(compare_funding_rates().await?))
+}
+
+#[derive(Deserialize)]
+struct FundingHistoryParams {
+    past_days: Option<u16>,
+    quote: Option<QuoteAsset>, // profile's binance quote
+}
+
+async fn funding_history(
+    Path(token): Path<String>,
+    Query(params): Query<FundingHistoryParams>,
+) -> ApiResult<Vec<DailyFundingRate>> {
+    let past_days = params.past_days.unwrap_or(7);
+    if past_days == 0 || i64::from(past_days) > MAX_DAYS_QUERY_FUNDING_HISTORY {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            format!("past_days must be between 1 and {MAX_DAYS_QUERY_FUNDING_HISTORY}"),
+        ));
+    }
+
+    let quote = params.quote.unwrap_or(profile().binance.quote);
+    let (b_token, hl_token) = load_symbols().await?.venue_symbols(&token.to_uppercase());
+    let (b_fh, hl_fh) = try_join!(
+        retrieve_binance_past_daily_fh(b_token, quote, past_days),
+        retrieve_hl_past_daily_fh(hl_token, past_days)
+    )?;
+
+    Ok(Json(join_daily_funding_rates(&b_fh, &hl_fh)))
+}
+
+#[derive(Deserialize)]
+struct QuoteParams {
+    usd: Option<f64>,
+    size: Option<f64>,
+    long: Option<String>,
+    quote: Option<QuoteAsset>, // profile's binance quote, the position's when exiting
+    #[serde(default)]
+    exit_position: bool,
+}
+
+async fn quote(Path(token): Path<String>, Query(params): Query<QuoteParams>) -> ApiResult<Value> {
+    let token = token.to_uppercase();
+
+    if params.exit_position {
+        let positions = retrieve_account_open_positions().await?;
+        let hedge = find_open_hedge(&positions, &token)
+            .map_err(|e| ApiError::new(StatusCode::NOT_FOUND, e))?;
+        let ((b, hl), fees) = try_join!(
+            latest_orderbooks(&token, hedge.binance_quote),
+            retrieve_fee_schedule(&token, hedge.binance_quote)
+        )?;
+
+        let (exit_long, exit_short) = quote_hedge_exit(&b, &hl, hedge.long, hedge.size, &fees)?;
+
+        return Ok(Json(json!({ "exit": [exit_short, exit_long] })));
+    }
+
+    let long = params.long.as_deref().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "long is required when quoting a new position",
+        )
+    })?;
+    let long = parse_platform(long)?;
+    let target = parse_target(params.usd, params.size)?;
+
+    let quote = params.quote.unwrap_or(profile().binance.quote);
+    let ((b, hl), fees) = try_join!(
+        latest_orderbooks(&token, quote),
+        retrieve_fee_schedule(&token, quote)
+    )?;
+    let round_trip = quote_round_trip(&b, &hl, long, target, &fees)?;
+
+    Ok(Json(serde_json::to_value(round_trip)?))
+}
+
+async fn balance() -> ApiResult<Balance> {
+    Ok(Json(retrieve_account_balance().await?))
+}
+
+async fn positions() -> ApiResult<Vec<Position>> {
+    Ok(Json(retrieve_account_open_positions().await?))
+}
+
+async fn export_metrics() -> Result<String, ApiError> {
+    Ok(metrics().encode()?)
+}
+
+#[derive(Deserialize)]
+struct ExecuteRequest {
+    token: String,
+    usd: Option<f64>,
+    size: Option<f64>,
+    long: String,
+    quote: Option<QuoteAsset>, // profile's binance quote
+    max_slippage: Option<f64>, // bps
+    leverage: Option<u32>,     // profile's leverage, else the account settings are kept
+    isolated: Option<bool>,    // profile's margin mode
+}
+
+async fn execute(Json(request): Json<ExecuteRequest>) -> ApiResult<HedgeExecution> {
+    let long = parse_platform(&request.long)?;
+    let target = parse_target(request.usd, request.size)?;
+    let max_slippage = request.max_slippage.unwrap_or(profile().max_slippage());
+    let leverage = profile().leverage_settings(request.leverage, request.isolated);
+
+    let execution = open_hedge(
+        &request.token.to_uppercase(),
+        request.quote.unwrap_or(profile().binance.quote),
+        target,
+        long,
+        max_slippage,
+        leverage,
+    )
+    .await?;
+
+    Ok(Json(execution))
+}
+
+async fn close(Path(token): Path<String>) -> ApiResult<Value> {
+    let token = token.to_uppercase();
+
+    let positions = retrieve_account_open_positions().await?;
+    let hedge =
+        find_open_hedge(&positions, &token).map_err(|e| ApiError::new(StatusCode::NOT_FOUND, e))?;
+
+    let (b, hl) = close_hedge(&token, &hedge).await?;
+
+    Ok(Json(json!({ "orders": [b, hl] })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use funding_rate_arbitrage::config::{in_profile, ConfigFile, Profile};
+    use reqwest::Client;
+
+    async fn spawn(app: Router) -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Ok(format!("http://{address}"))
+    }
+
+    /// binance and hyperliquid with the same book on both sides
+    fn mock_exchanges() -> Router {
+        Router::new()
+            .route(
+                "/fapi/v1/depth",
+                get(|| async {
+                    Json(json!({
+                        "bids": [["2499.0", "10.0"], ["2498.0", "10.0"]],
+                        "asks": [["2501.0", "10.0"], ["2502.0", "10.0"]]
+                    }))
+                }),
+            )
+            .route(
+                "/fapi/v1/exchangeInfo",
+                get(|| async {
+                    Json(json!({
+                        "symbols": [{
+                            "symbol": "ETHUSDT",
+                            "baseAsset": "ETH",
+                            "quoteAsset": "USDT",
+                            "contractType": "PERPETUAL",
+                            "requiredMarginPercent": "5.0000",
+                            "filters": [{}, {}, {"stepSize": "0.001"}]
+                        }]
+                    }))
+                }),
+            )
+            .route(
+                "/fapi/v1/commissionRate",
+                get(|| async {
+                    Json(json!({
+                        "symbol": "ETHUSDT",
+                        "makerCommissionRate": "0.00016",
+                        "takerCommissionRate": "0.0004"
+                    }))
+                }),
+            )
+            .route(
+                "/info",
+                post(|Json(body): Json<Value>| async move {
+                    if body["type"] == "userFees" {
+                        return Json(json!({ "userAddRate": "0.0", "userCrossRate": "0.0003" }));
+                    }
+                    if body["type"] == "meta" {
+                        return Json(json!({ "universe": [{ "name": "ETH", "szDecimals": 4 }] }));
+                    }
+                    Json(json!({
+                        "levels": [
+                            [{"px": "2499.5", "sz": "10.0", "n": 1}, {"px": "2498.5", "sz": "10.0", "n": 1}],
+                            [{"px": "2500.5", "sz": "10.0", "n": 1}, {"px": "2501.5", "sz": "10.0", "n": 1}]
+                        ]
+                    }))
+                }),
+            )
+    }
+
+    #[tokio::test]
+    async fn test_trading_requires_auth_token() -> Result<()> {
+        let client = Client::new();
+
+        let url = spawn(router(Some("secret".to_string()))).await?;
+        let res = client.post(format!("{url}/close/ETH")).send().await?;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        let res = client
+            .post(format!("{url}/execute"))
+            .bearer_auth("wrong")
+            .json(&json!({ "token": "ETH", "usd": 100.0, "long": "hl" }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+        // authorized, rejected by validation before reaching the exchanges
+        let res = client
+            .post(format!("{url}/execute"))
+            .bearer_auth("secret")
+            .json(&json!({ "token": "ETH", "usd": 100.0, "long": "kraken" }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let url = spawn(router(None)).await?;
+        let res = client
+            .post(format!("{url}/close/ETH"))
+            .bearer_auth("secret")
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_is_public() -> Result<()> {
+        metrics().record_balance(&Balance {
+            binance_balance: 1000.0,
+            binance_open_positions: 2000.0,
+            binance_maintenance_margin: 20.0,
+            hyperliquid_balance: 1000.0,
+            hyperliquid_open_positions: 2000.0,
+            hyperliquid_maintenance_margin: 20.0,
+        });
+
+        let url = spawn(router(Some("secret".to_string()))).await?;
+        let res = reqwest::get(format!("{url}/metrics")).await?;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let text = res.text().await?;
+        assert!(text.contains(r#"funding_arb_account_margin_ratio{venue="binance"} 0.02"#));
+        assert!(text.contains(r#"funding_arb_account_leverage{venue="binance"} 2"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quote_against_mock_exchanges() -> Result<()> {
+        let mock_url = spawn(mock_exchanges()).await?;
+        // every request runs against the mocks, without touching the process environment
+        let (_, mock_profile) = ConfigFile::parse(&format!(
+            r#"
+            [profiles.default.credentials]
+            binance_api_key = "key"
+            binance_secret_key = "secret"
+            mnemonic = "test test test test test test test test test test test junk"
+
+            [profiles.default.binance]
+            api_url = "{mock_url}"
+
+            [profiles.default.hyperliquid]
+            api_url = "{mock_url}"
+            "#
+        ))?
+        .profile(None)?;
+        let mock_profile: &'static Profile = Box::leak(Box::new(mock_profile));
+
+        let app = router(None).layer(middleware::from_fn(move |req: Request, next: Next| {
+            in_profile(mock_profile, next.run(req))
+        }));
+        let url = spawn(app).await?;
+        let client = Client::new();
+
+        let res = client
+            .get(format!("{url}/quote/eth?usd=5000&long=binance"))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let quote: Value = res.json().await?;
+        assert_eq!(quote["entry"][0]["platform"], "binance");
+        assert_eq!(quote["entry"][1]["platform"], "hyperliquid");
+        assert_eq!(quote["entry"][0]["size"], quote["entry"][1]["size"]);
+        assert!(quote["round_trip_cost"].as_f64().unwrap() > 0.0);
+        // account rates instead of the base tier
+        assert_eq!(quote["entry"][0]["platform_fees"], 0.0004);
+        assert_eq!(quote["entry"][1]["platform_fees"], 0.0003);
+
+        // neither amount
+        let res = client
+            .get(format!("{url}/quote/eth?long=binance"))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{
+    binance::retrieve_binance_general_info,
+    hyperliquid::retrieve_hl_perp_symbols,
+    util::{Platform, QuoteAsset},
+};
+use anyhow::Result;
+use std::collections::BTreeMap;
+use tokio::{sync::OnceCell, try_join};
+
+/// perpetual of an asset on one venue
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueSymbol {
+    pub symbol: String,           // without quote, e.g. 1000PEPE or kPEPE
+    pub multiplier: f64,          // tokens per contract
+    pub sz_decimals: Option<u32>, // decimals of order sizes in contracts, hyperliquid only
+}
+
+/// an asset and the perpetual listing it on each venue
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub name: String,
+    pub binance: Option<VenueSymbol>,
+    pub hyperliquid: Option<VenueSymbol>,
+}
+
+impl Asset {
+    /// listed under its own name on both venues
+    fn unlisted(name: &str) -> Self {
+        let venue = VenueSymbol {
+            symbol: name.to_string(),
+            multiplier: 1.0,
+            sz_decimals: None,
+        };
+
+        Self {
+            name: name.to_string(),
+            binance: Some(venue.clone()),
+            hyperliquid: Some(venue),
+        }
+    }
+
+    pub fn venue(&self, platform: Platform) -> Option<&VenueSymbol> {
+        match platform {
+            Platform::Binance => self.binance.as_ref(),
+            Platform::Hyperliquid => self.hyperliquid.as_ref(),
+        }
+    }
+
+    /// symbol traded on `platform`, the asset name when it isn't listed there
+    pub fn symbol(&self, platform: Platform) -> String {
+        self.venue(platform)
+            .map_or_else(|| self.name.clone(), |venue| venue.symbol.clone())
+    }
+
+    /// tokens in one unit of the sizes and prices quoted for the asset.
+    /// the smaller multiplier of both venues, so `1000PEPE` against `kPEPE` keeps quoting contracts
+    pub fn unit(&self) -> f64 {
+        [&self.binance, &self.hyperliquid]
+            .into_iter()
+            .flatten()
+            .map(|venue| venue.multiplier)
+            .reduce(f64::min)
+            .unwrap_or(1.0)
+    }
+
+    /// units of the asset in one contract of `platform`, 1 unless the venues list different multipliers
+    pub fn scale(&self, platform: Platform) -> f64 {
+        self.venue(platform)
+            .map_or(1.0, |venue| venue.multiplier / self.unit())
+    }
+}
+
+/// asset and multiplier behind a venue symbol, `1000SHIB` and `kSHIB` => (`SHIB`, 1000)
+pub fn parse_symbol(platform: Platform, symbol: &str) -> (String, f64) {
+    match platform {
+        Platform::Binance => {
+            if let Some(base) = symbol
+                .strip_prefix("1M")
+                .filter(|base| base.len() > 1 && !base.starts_with(|c: char| c.is_ascii_digit()))
+            {
+                return (base.to_string(), 1_000_000.0);
+            }
+
+            let base = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+            let prefix = &symbol[..symbol.len() - base.len()];
+
+            // 1INCH is an asset, only powers of ten from 1000 are contract sizes
+            match prefix.parse::<u64>() {
+                Ok(multiplier)
+                    if !base.is_empty()
+                        && multiplier >= 1000
+                        && 10u64.pow(multiplier.ilog10()) == multiplier =>
+                {
+                    (base.to_string(), multiplier as f64)
+                }
+                _ => (symbol.to_string(), 1.0),
+            }
+        }
+        Platform::Hyperliquid => match symbol.strip_prefix('k') {
+            Some(base) if base.starts_with(|c: char| c.is_ascii_uppercase()) => {
+                (base.to_string(), 1000.0)
+            }
+            _ => (symbol.to_string(), 1.0),
+        },
+    }
+}
+
+/// perpetuals listed on both venues keyed by asset
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRegistry {
+    assets: BTreeMap<String, Asset>,
+}
+
+impl SymbolRegistry {
+    /// `binance` and `hyperliquid` are the listed symbols without quote
+    pub fn new<'a>(
+        binance: impl IntoIterator<Item = &'a str>,
+        hyperliquid: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        Self::with_sz_decimals(binance, hyperliquid.into_iter().map(|s| (s, None)))
+    }
+
+    /// like `new`, hyperliquid symbols come with the size decimals of their orders
+    pub fn with_sz_decimals<'a>(
+        binance: impl IntoIterator<Item = &'a str>,
+        hyperliquid: impl IntoIterator<Item = (&'a str, Option<u32>)>,
+    ) -> Self {
+        let mut registry = Self::default();
+
+        for symbol in binance {
+            registry.insert(Platform::Binance, symbol, None);
+        }
+        for (symbol, sz_decimals) in hyperliquid {
+            registry.insert(Platform::Hyperliquid, symbol, sz_decimals);
+        }
+
+        registry
+    }
+
+    fn insert(&mut self, platform: Platform, symbol: &str, sz_decimals: Option<u32>) {
+        let (name, multiplier) = parse_symbol(platform, symbol);
+
+        let asset = self.assets.entry(name.clone()).or_insert(Asset {
+            name,
+            binance: None,
+            hyperliquid: None,
+        });
+        let venue = match platform {
+            Platform::Binance => &mut asset.binance,
+            Platform::Hyperliquid => &mut asset.hyperliquid,
+        };
+
+        // a venue listing the asset twice is traded on its smaller contract
+        if venue.as_ref().is_none_or(|v| multiplier < v.multiplier) {
+            *venue = Some(VenueSymbol {
+                symbol: symbol.to_string(),
+                multiplier,
+                sz_decimals,
+            });
+        }
+    }
+
+    /// `token` is the asset name, one missing from the registry trades under its name on both venues
+    pub fn asset(&self, token: &str) -> Asset {
+        self.assets
+            .get(token)
+            .cloned()
+            .unwrap_or_else(|| Asset::unlisted(token))
+    }
+
+    /// assets with a perpetual on both venues
+    pub fn joint_assets(&self) -> impl Iterator<Item = &Asset> {
+        self.assets
+            .values()
+            .filter(|asset| asset.binance.is_some() && asset.hyperliquid.is_some())
+    }
+
+    /// asset traded by a venue symbol, e.g. `kPEPE` => `PEPE`
+    pub fn canonical(&self, platform: Platform, symbol: &str) -> String {
+        parse_symbol(platform, symbol).0
+    }
+
+    /// first element is the binance symbol
+    pub fn venue_symbols(&self, token: &str) -> (String, String) {
+        let asset = self.asset(token);
+
+        (
+            asset.symbol(Platform::Binance),
+            asset.symbol(Platform::Hyperliquid),
+        )
+    }
+}
+
+/// base and quote of a binance futures symbol, e.g. `1000PEPEUSDC` => (`1000PEPE`, USDC).
+/// none for quotes other than USDT and USDC
+pub fn binance_pair(symbol: &str) -> Option<(&str, QuoteAsset)> {
+    [QuoteAsset::Usdt, QuoteAsset::Usdc]
+        .into_iter()
+        .find_map(|quote| Some((symbol.strip_suffix(&quote.to_string())?, quote)))
+}
+
+/// USDT and USDC perpetuals of binance and every perpetual of hyperliquid
+pub async fn retrieve_symbol_registry() -> Result<SymbolRegistry> {
+    let (b_listings, hl_listings) =
+        try_join!(retrieve_binance_general_info(), retrieve_hl_perp_symbols())?;
+
+    let b_symbols = b_listings
+        .iter()
+        .filter(|t| t.is_perpetual())
+        .map(|t| t.base_asset.as_str());
+    let hl_symbols = hl_listings
+        .iter()
+        .map(|meta| (meta.name.as_str(), Some(meta.sz_decimals)));
+
+    Ok(SymbolRegistry::with_sz_decimals(b_symbols, hl_symbols))
+}
+
+static SYMBOLS: OnceCell<SymbolRegistry> = OnceCell::const_new();
+static NO_SYMBOLS: SymbolRegistry = SymbolRegistry {
+    assets: BTreeMap::new(),
+};
+
+/// registry of the listed perpetuals, retrieved once per process
+pub async fn load_symbols() -> Result<&'static SymbolRegistry> {
+    SYMBOLS.get_or_try_init(retrieve_symbol_registry).await
+}
+
+/// the registry once `load_symbols` ran, empty before so only the symbol rules apply
+pub fn symbols() -> &'static SymbolRegistry {
+    SYMBOLS.get().unwrap_or(&NO_SYMBOLS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_symbol() {
+        let cases = [
+            (Platform::Binance, "1000SHIB", "SHIB", 1000.0),
+            (Platform::Binance, "1000000MOG", "MOG", 1_000_000.0),
+            (Platform::Binance, "1MBABYDOGE", "BABYDOGE", 1_000_000.0),
+            (Platform::Binance, "1INCH", "1INCH", 1.0),
+            (Platform::Binance, "ETH", "ETH", 1.0),
+            (Platform::Hyperliquid, "kBONK", "BONK", 1000.0),
+            (Platform::Hyperliquid, "KAS", "KAS", 1.0),
+        ];
+
+        for (platform, symbol, asset, multiplier) in cases {
+            assert_eq!(
+                parse_symbol(platform, symbol),
+                (asset.to_string(), multiplier),
+                "{symbol}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_registry_maps_venue_symbols() {
+        let registry = SymbolRegistry::new(
+            ["1000SHIB", "1000BONK", "DOGS", "ETH", "1INCH"],
+            ["kSHIB", "kBONK", "kDOGS", "ETH", "HYPE"],
+        );
+
+        assert_eq!(
+            registry.venue_symbols("SHIB"),
+            ("1000SHIB".to_string(), "kSHIB".to_string())
+        );
+        assert_eq!(registry.canonical(Platform::Hyperliquid, "kBONK"), "BONK");
+
+        // same contract size on both venues keeps quoting contracts
+        let bonk = registry.asset("BONK");
+        assert_eq!(bonk.unit(), 1000.0);
+        assert_eq!(bonk.scale(Platform::Binance), 1.0);
+        assert_eq!(bonk.scale(Platform::Hyperliquid), 1.0);
+
+        // one kDOGS contract is a thousand of binance's
+        let dogs = registry.asset("DOGS");
+        assert_eq!(dogs.scale(Platform::Binance), 1.0);
+        assert_eq!(dogs.scale(Platform::Hyperliquid), 1000.0);
+
+        let joint: Vec<_> = registry.joint_assets().map(|a| a.name.as_str()).collect();
+        assert_eq!(joint, ["BONK", "DOGS", "ETH", "SHIB"]);
+    }
+
+    #[test]
+    fn test_binance_pair() {
+        assert_eq!(
+            binance_pair("1000PEPEUSDC"),
+            Some(("1000PEPE", QuoteAsset::Usdc))
+        );
+        assert_eq!(binance_pair("ETHUSDT"), Some(("ETH", QuoteAsset::Usdt)));
+        assert_eq!(binance_pair("ETHBTC"), None);
+    }
+
+    #[test]
+    fn test_canonical_inverts_venue_symbols() {
+        let registry = SymbolRegistry::new(["1000PEPE", "ETH"], ["kPEPE", "ETH"]);
+
+        for token in ["PEPE", "ETH", "KAS"] {
+            let (b_token, hl_token) = registry.venue_symbols(token);
+            assert_eq!(registry.canonical(Platform::Binance, &b_token), token);
+            assert_eq!(registry.canonical(Platform::Hyperliquid, &hl_token), token);
+        }
+    }
+}
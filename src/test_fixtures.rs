@@ -0,0 +1,50 @@
+//! Builders of the structs many unit tests need, override fields with struct update syntax.
+
+use crate::{
+    balances::Position,
+    compare_funding_rates::JointFundingRate,
+    util::{Platform, QuoteAsset},
+};
+
+/// funding of `name` on the USDT perpetual, everything else zero
+pub fn joint_funding_rate(
+    name: &str,
+    binance_funding_rate: f64,
+    hyperliquid_funding_rate: f64,
+) -> JointFundingRate {
+    JointFundingRate {
+        name: name.to_string(),
+        binance_quote: QuoteAsset::Usdt,
+        binance_funding_rate,
+        binance_mark_price: 0.0,
+        hyperliquid_funding_rate,
+        hyperliquid_open_interest: 0.0,
+        hyperliquid_mark_price: 0.0,
+        funding_rate_difference: (binance_funding_rate - hyperliquid_funding_rate).abs(),
+        binance_next_funding_rate: 0.0,
+        binance_next_funding_time: 0,
+        binance_funding_interval: 8.0,
+        binance_interest_rate: 0.0,
+        binance_premium: 0.0,
+        hyperliquid_next_funding_rate: 0.0,
+        hyperliquid_next_funding_time: 0,
+        hyperliquid_premium: 0.0,
+    }
+}
+
+/// 10 units at 100 on the USDT perpetual, no pnl, funding or liquidation price
+pub fn position(platform: Platform, coin: &str, direction: &str) -> Position {
+    Position {
+        platform,
+        coin: coin.to_string(),
+        quote: QuoteAsset::Usdt,
+        direction: direction.to_string(),
+        size: 10.0,
+        pnl: 0.0,
+        funding: 0.0,
+        funding_rate: 0.0,
+        notional: 1000.0,
+        mark_price: 100.0,
+        liquidation_price: None,
+    }
+}
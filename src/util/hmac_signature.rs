@@ -1,9 +1,10 @@
+use crate::config::binance_secret_key;
 use anyhow::Result;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
 pub fn generate_hmac_signature(query_params: Option<String>) -> Result<String> {
-    let binance_secret = std::env::var("BINANCE_SECRET_KEY")?;
+    let binance_secret = binance_secret_key()?;
 
     let mut mac = Hmac::<Sha256>::new_from_slice(binance_secret.as_bytes())?;
 
@@ -20,14 +21,20 @@ pub fn generate_hmac_signature(query_params: Option<String>) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{in_account, Credentials};
 
-    #[test]
-    fn test_corroborate_signature() -> Result<()> {
-        let binance_secret = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
-        std::env::set_var("BINANCE_SECRET_KEY", binance_secret);
+    #[tokio::test]
+    async fn test_corroborate_signature() -> Result<()> {
+        let credentials = Credentials {
+            binance_secret_key: Some(
+                "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j".to_string(),
+            ),
+            ..Default::default()
+        };
 
         let query_params = Some("timestamp=1578963600000".to_string());
-        let signature = generate_hmac_signature(query_params)?;
+        let signature =
+            in_account(credentials, async { generate_hmac_signature(query_params) }).await?;
 
         assert_eq!(
             signature,
@@ -25,4 +25,76 @@ impl Orderbook {
             .fold(0.0, |acc, lo| acc + lo.price * lo.size);
         (bids_total, asks_total)
     }
+
+    /// prices and sizes of `scale` units of an asset per contract in units of the asset
+    pub fn to_asset_units(mut self, scale: f64) -> Self {
+        for lo in self.bids.iter_mut().chain(self.asks.iter_mut()) {
+            lo.price /= scale;
+            lo.size *= scale;
+        }
+        self
+    }
+
+    /// sets the resting size at `price`, a size of zero removes the level.
+    /// bids stay sorted descending and asks ascending
+    pub fn update_level(&mut self, is_bid: bool, price: f64, size: f64) {
+        let levels = if is_bid {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        };
+
+        let position = levels.binary_search_by(|lo| {
+            let ordering = lo.price.total_cmp(&price);
+            if is_bid {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        match (position, size > 0.0) {
+            (Ok(idx), true) => levels[idx].size = size,
+            (Ok(idx), false) => {
+                levels.remove(idx);
+            }
+            (Err(idx), true) => levels.insert(idx, LimitOrder { price, size }),
+            (Err(_), false) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_level() {
+        let mut orderbook = Orderbook {
+            platform: Platform::Binance,
+            bids: vec![],
+            asks: vec![],
+        };
+
+        orderbook.update_level(true, 99.0, 1.0);
+        orderbook.update_level(true, 100.0, 2.0);
+        orderbook.update_level(true, 98.0, 3.0);
+        orderbook.update_level(false, 102.0, 1.0);
+        orderbook.update_level(false, 101.0, 1.0);
+
+        let bid_prices: Vec<_> = orderbook.bids.iter().map(|lo| lo.price).collect();
+        let ask_prices: Vec<_> = orderbook.asks.iter().map(|lo| lo.price).collect();
+        assert_eq!(bid_prices, vec![100.0, 99.0, 98.0]);
+        assert_eq!(ask_prices, vec![101.0, 102.0]);
+
+        // update in place then remove
+        orderbook.update_level(true, 99.0, 5.0);
+        assert_eq!(orderbook.bids[1].size, 5.0);
+        orderbook.update_level(true, 100.0, 0.0);
+        assert_eq!(orderbook.bids[0].price, 99.0);
+
+        // removing a missing level is a no-op
+        orderbook.update_level(false, 150.0, 0.0);
+        assert_eq!(orderbook.asks.len(), 2);
+    }
 }
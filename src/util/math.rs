@@ -11,6 +11,20 @@ pub fn calculate_effective_rate(rate1: f64, rate2: f64) -> f64 {
     }
 }
 
+/// basis of a perpetual over its index or oracle, decimal form. 0 without a reference price
+pub fn calculate_premium(mark_price: f64, index_price: f64) -> f64 {
+    if index_price == 0.0 {
+        return 0.0;
+    }
+    (mark_price - index_price) / index_price
+}
+
+/// `amount` in whole cents, rounded down so a transfer capped at a balance never asks for more than it holds
+pub fn fmt_transfer_amount(amount: f64) -> String {
+    // the nudge keeps float noise like 0.29 * 100 = 28.999.. from losing a cent
+    format!("{:.2}", ((amount * 100.0) + 1e-6).floor() / 100.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,4 +47,19 @@ mod tests {
         assert_eq!(calculate_effective_rate(0.001, 0.002), 0.001);
         assert_eq!(calculate_effective_rate(-0.001, -0.002), 0.001);
     }
+
+    #[test]
+    fn test_calculate_premium() {
+        assert_relative_eq!(calculate_premium(101.0, 100.0), 0.01);
+        assert_relative_eq!(calculate_premium(99.0, 100.0), -0.01);
+        assert_eq!(calculate_premium(100.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_fmt_transfer_amount() {
+        assert_eq!(fmt_transfer_amount(1234.567), "1234.56");
+        assert_eq!(fmt_transfer_amount(0.299), "0.29");
+        assert_eq!(fmt_transfer_amount(0.29), "0.29");
+        assert_eq!(fmt_transfer_amount(50.0), "50.00");
+    }
 }
@@ -1,15 +1,15 @@
 mod effective_funding_rate;
-mod format_token;
 mod hmac_signature;
 mod limit_order;
 mod math;
 mod order_filled;
 mod platform;
+mod quote_asset;
 
 pub use effective_funding_rate::*;
-pub use format_token::*;
 pub use hmac_signature::generate_hmac_signature;
 pub use limit_order::{LimitOrder, Orderbook};
 pub use math::*;
 pub use order_filled::{OrderFilled, Side};
 pub use platform::Platform;
+pub use quote_asset::QuoteAsset;
@@ -1,8 +1,7 @@
 use super::Platform;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
-#[allow(unused)]
+#[derive(Debug, Clone, Serialize)]
 pub struct OrderFilled {
     pub token: String,
     pub platform: Platform,
@@ -11,7 +10,17 @@ pub struct OrderFilled {
     pub side: Side,
 }
 
-#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+impl OrderFilled {
+    /// fill of a contract holding `scale` units of an asset, in units of the asset
+    pub fn to_asset_units(mut self, scale: f64) -> Self {
+        self.size *= scale;
+        self.avg_price /= scale;
+        self
+    }
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all(serialize = "lowercase"))]
 pub enum Side {
     Buy,
     Sell,
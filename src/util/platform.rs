@@ -1,5 +1,22 @@
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+use anyhow::{bail, Error, Result};
+use serde::Serialize;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Platform {
     Binance,
     Hyperliquid,
 }
+
+impl FromStr for Platform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "binance" | "b" => Ok(Platform::Binance),
+            "hyperliquid" | "hl" | "h" => Ok(Platform::Hyperliquid),
+            _ => bail!("Invalid platform. Use 'binance' (or 'b') for Binance, or 'hyperliquid' (or 'hl', 'h') for Hyperliquid")
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// asset a perpetual is margined and settled in, binance lists both
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteAsset {
+    #[default]
+    Usdt,
+    Usdc,
+}
+
+impl QuoteAsset {
+    /// binance pair of `token` in this quote, e.g. `1000PEPEUSDC`
+    pub fn pair(self, token: &str) -> String {
+        format!("{token}{self}")
+    }
+}
+
+impl fmt::Display for QuoteAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteAsset::Usdt => write!(f, "USDT"),
+            QuoteAsset::Usdc => write!(f, "USDC"),
+        }
+    }
+}
+
+impl FromStr for QuoteAsset {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "usdt" => Ok(QuoteAsset::Usdt),
+            "usdc" => Ok(QuoteAsset::Usdc),
+            _ => bail!("Invalid quote asset. Use 'usdt' or 'usdc'"),
+        }
+    }
+}